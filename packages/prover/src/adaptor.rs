@@ -0,0 +1,543 @@
+use crate::{
+    poseidon::poseidon_hash_many, rfc6979::generate_k_rfc6979, util::short_string_to_field,
+};
+
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use starknet_curve::{Fq, Fr};
+
+/*
+Adaptor signatures, for atomic swaps: a pre-signature is bound to an adaptor point Y = y*G, it can
+be checked by anyone but only becomes a valid signature once adapted with the secret y, and anyone
+holding both the pre-signature and the signature can extract y.
+
+ecdsa produces starknet ECDSA signatures of the signature module, schnorr produces signatures of
+the schnorr module. The nonces of the pre-signatures depend on Y, so pre-signing the same message
+for two adaptor points never reuses a nonce.
+*/
+
+fn tagged_hash(tag: &str, data: &[Fq]) -> Fq {
+    // it's safe to unwrap, tags are short ascii strings
+    let mut input = vec![short_string_to_field(tag).unwrap()];
+    input.extend_from_slice(data);
+
+    poseidon_hash_many(&input)
+}
+
+fn to_scalar(element: &Fq) -> Fr {
+    Fr::from_le_bytes_mod_order(&element.into_repr().to_bytes_le())
+}
+
+fn nonce(priv_key: &Fr, tag: &str, data: &[Fq], seed: Option<u64>) -> Fr {
+    generate_k_rfc6979(
+        &starknet_curve::FrParameters::MODULUS,
+        priv_key,
+        &to_scalar(&tagged_hash(tag, data)),
+        seed,
+    )
+}
+
+pub mod ecdsa {
+    use super::{nonce, tagged_hash, to_scalar};
+    use crate::{
+        constants::TWO_MODULUS_BITS,
+        error::Error,
+        signature::{private_key_to_public_key, Signature, SigningParameters},
+    };
+
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+    use starknet_curve::{Affine, Fq, Fr};
+
+    /*
+    k is the nonce, R_a = k*G and R = k*Y. The pre-signature is s' = (msg_hash + r*x) / k with
+    r = R.x, together with a proof that R_a and R have the same discrete log with respect to G and Y.
+    The adapted signature is (r, s' / y), its nonce point is k*y*G = R.
+    */
+
+    const NONCE_TAG: &str = "STARK_ADAPTOR_ECDSA_NONCE";
+    const DLEQ_NONCE_TAG: &str = "STARK_ADAPTOR_DLEQ_NONCE";
+    const DLEQ_TAG: &str = "STARK_ADAPTOR_DLEQ";
+
+    /// Proof that log_G(R_a) = log_Y(R)
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct DleqProof {
+        pub c: Fr,
+        pub z: Fr,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct PreSignature {
+        pub nonce_point: Affine,
+        pub adapted_nonce_point: Affine,
+        pub s: Fr,
+        pub proof: DleqProof,
+    }
+
+    fn dleq_challenge(points: &[&Affine]) -> Fr {
+        let coordinates: Vec<Fq> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+        to_scalar(&tagged_hash(DLEQ_TAG, &coordinates))
+    }
+
+    fn is_in_range(element: &Fr) -> bool {
+        *element >= Fr::one() && element.into_repr() < TWO_MODULUS_BITS
+    }
+
+    impl PreSignature {
+        /// r of the adapted signature, it's checked to be in range when pre-signing
+        pub fn r(&self) -> Fr {
+            Fr::from_le_bytes_mod_order(&self.adapted_nonce_point.x.into_repr().to_bytes_le())
+        }
+    }
+
+    pub fn pre_sign(
+        parameters: &SigningParameters,
+        priv_key: Fr,
+        msg_hash: Fq,
+        adaptor_point: &Affine,
+    ) -> Result<PreSignature, Error> {
+        if priv_key.is_zero() {
+            return Err(Error::InvalidPrivateKey);
+        }
+        if adaptor_point.is_zero() || !adaptor_point.is_on_curve() {
+            return Err(Error::InvalidPublicKey);
+        }
+        if msg_hash.into_repr() >= TWO_MODULUS_BITS {
+            return Err(Error::OverflowError);
+        }
+
+        // since we checked that msg hash is in bound, it's safe to unwrap
+        let msg_hash_as_r = Fr::from_repr(msg_hash.into_repr()).unwrap();
+        let data = [msg_hash, adaptor_point.x, adaptor_point.y];
+
+        let mut seed = None;
+        loop {
+            let k = nonce(&priv_key, NONCE_TAG, &data, seed);
+            seed = Some(seed.map_or(1, |seed| seed + 1));
+
+            let adapted_nonce_point = adaptor_point.mul(k.into_repr()).into_affine();
+            if adapted_nonce_point.x.into_repr() >= TWO_MODULUS_BITS {
+                // Bad value. This fails with negligible probability.
+                continue;
+            }
+
+            // r < 2**251 so it's safe to unwrap
+            let r = Fr::from_repr(adapted_nonce_point.x.into_repr()).unwrap();
+            let temp = msg_hash_as_r + r * priv_key;
+            if r.is_zero() || temp.is_zero() {
+                // Bad value. This fails with negligible probability.
+                continue;
+            }
+
+            let nonce_point = parameters.generator.mul(k.into_repr()).into_affine();
+
+            let t = nonce(&k, DLEQ_NONCE_TAG, &[adapted_nonce_point.x], None);
+            let a1 = parameters.generator.mul(t.into_repr()).into_affine();
+            let a2 = adaptor_point.mul(t.into_repr()).into_affine();
+            let c = dleq_challenge(&[&nonce_point, adaptor_point, &adapted_nonce_point, &a1, &a2]);
+
+            // k is never zero so it's safe to unwrap
+            break Ok(PreSignature {
+                nonce_point,
+                adapted_nonce_point,
+                s: temp * k.inverse().unwrap(),
+                proof: DleqProof { c, z: t + c * k },
+            });
+        }
+    }
+
+    pub fn pre_verify(
+        parameters: &SigningParameters,
+        pub_key: &Affine,
+        msg_hash: &Fq,
+        adaptor_point: &Affine,
+        pre_signature: &PreSignature,
+    ) -> bool {
+        let PreSignature {
+            nonce_point,
+            adapted_nonce_point,
+            s,
+            proof,
+        } = pre_signature;
+
+        if pub_key.is_zero() || !pub_key.is_on_curve() {
+            return false;
+        }
+        if adaptor_point.is_zero() || !adaptor_point.is_on_curve() {
+            return false;
+        }
+        if !adapted_nonce_point.is_on_curve() || !nonce_point.is_on_curve() {
+            return false;
+        }
+        if msg_hash.into_repr() >= TWO_MODULUS_BITS {
+            return false;
+        }
+
+        let r = pre_signature.r();
+        if !is_in_range(&r) {
+            return false;
+        }
+
+        let a1 =
+            parameters.generator.mul(proof.z.into_repr()) - nonce_point.mul(proof.c.into_repr());
+        let a2 =
+            adaptor_point.mul(proof.z.into_repr()) - adapted_nonce_point.mul(proof.c.into_repr());
+        let c = dleq_challenge(&[
+            nonce_point,
+            adaptor_point,
+            adapted_nonce_point,
+            &a1.into_affine(),
+            &a2.into_affine(),
+        ]);
+        if c != proof.c {
+            return false;
+        }
+
+        let w = match s.inverse() {
+            Some(w) => w,
+            None => return false,
+        };
+        // msg hash is in bound, it's safe to unwrap
+        let msg_hash = Fr::from_repr(msg_hash.into_repr()).unwrap();
+        let point =
+            parameters.generator.mul((msg_hash * w).into_repr()) + pub_key.mul((r * w).into_repr());
+
+        point.into_affine() == *nonce_point
+    }
+
+    /// Fails if the adapted signature is out of the range accepted by cairo-lang,
+    /// which happens with negligible probability
+    pub fn adapt(pre_signature: &PreSignature, secret: &Fr) -> Result<Signature, Error> {
+        let w = *secret * pre_signature.s.inverse().ok_or(Error::InvalidSignature)?;
+        if !is_in_range(&w) {
+            return Err(Error::UnsignableMessage);
+        }
+
+        Ok(Signature {
+            r: pre_signature.r(),
+            // w is not zero so it's safe to unwrap
+            s: w.inverse().unwrap(),
+        })
+    }
+
+    /// The signature may have been malleated to (r, -s), which negates the extracted secret
+    pub fn extract(
+        parameters: &SigningParameters,
+        pre_signature: &PreSignature,
+        signature: &Signature,
+        adaptor_point: &Affine,
+    ) -> Result<Fr, Error> {
+        if signature.r != pre_signature.r() {
+            return Err(Error::InvalidSignature);
+        }
+
+        let secret = pre_signature.s * signature.s.inverse().ok_or(Error::InvalidSignature)?;
+        let point = private_key_to_public_key(parameters, secret).into_affine();
+        if point == *adaptor_point {
+            Ok(secret)
+        } else if point == -*adaptor_point {
+            Ok(-secret)
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
+
+pub mod schnorr {
+    use super::nonce;
+    use crate::{
+        error::Error,
+        hash::HashFunction,
+        schnorr::{challenge, lift_x, SchnorrSignature},
+        signature::{private_key_to_public_key, SigningParameters},
+    };
+
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{BigInteger, PrimeField, Zero};
+    use starknet_curve::{Affine, Fq, Fr};
+
+    /*
+    The final nonce point is R = R_a + Y with R_a = k*G, taken with an even y coordinate: when R.y
+    is odd both k and y are negated, which is recorded in the pre-signature. The pre-signature is
+    s' = k + e*x, where e is the challenge of R.x, and the adapted signature is (R.x, s' + y).
+    */
+
+    const NONCE_TAG: &str = "STARK_ADAPTOR_SCHNORR_NONCE";
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct PreSignature {
+        pub nonce_point: Affine,
+        pub r: Fq,
+        pub s: Fr,
+        pub negated: bool,
+    }
+
+    impl PreSignature {
+        fn secret(&self, secret: &Fr) -> Fr {
+            if self.negated {
+                -*secret
+            } else {
+                *secret
+            }
+        }
+    }
+
+    pub fn pre_sign(
+        parameters: &SigningParameters,
+        priv_key: Fr,
+        msg_hash: Fq,
+        adaptor_point: &Affine,
+        hasher: HashFunction,
+    ) -> Result<PreSignature, Error> {
+        if priv_key.is_zero() {
+            return Err(Error::InvalidPrivateKey);
+        }
+        if adaptor_point.is_zero() || !adaptor_point.is_on_curve() {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        let pub_key = private_key_to_public_key(parameters, priv_key).into_affine();
+        let priv_key = if pub_key.y.into_repr().is_odd() {
+            -priv_key
+        } else {
+            priv_key
+        };
+
+        let data = [msg_hash, adaptor_point.x, adaptor_point.y];
+        let mut seed = None;
+        loop {
+            let k = nonce(&priv_key, NONCE_TAG, &data, seed);
+            seed = Some(seed.map_or(1, |seed| seed + 1));
+
+            let point = (parameters.generator.mul(k.into_repr()) + adaptor_point.into_projective())
+                .into_affine();
+            if point.is_zero() {
+                // Bad value. This fails with negligible probability.
+                continue;
+            }
+
+            let negated = point.y.into_repr().is_odd();
+            let k = if negated { -k } else { k };
+            let e = challenge(hasher, &point.x, &pub_key.x, &msg_hash);
+
+            break Ok(PreSignature {
+                nonce_point: parameters.generator.mul(k.into_repr()).into_affine(),
+                r: point.x,
+                s: k + e * priv_key,
+                negated,
+            });
+        }
+    }
+
+    /// Checks s'*G - e*P = R_a and that R_a + Y, or its negation, is the final nonce point
+    pub fn pre_verify(
+        parameters: &SigningParameters,
+        pub_key: &Fq,
+        msg_hash: &Fq,
+        adaptor_point: &Affine,
+        pre_signature: &PreSignature,
+        hasher: HashFunction,
+    ) -> bool {
+        let point = match lift_x(pub_key) {
+            Some(point) => point,
+            None => return false,
+        };
+        if adaptor_point.is_zero() || !adaptor_point.is_on_curve() {
+            return false;
+        }
+
+        let e = challenge(hasher, &pre_signature.r, pub_key, msg_hash);
+        let nonce_point = (parameters.generator.mul(pre_signature.s.into_repr())
+            - point.mul(e.into_repr()))
+        .into_affine();
+        if nonce_point != pre_signature.nonce_point {
+            return false;
+        }
+
+        let adaptor_point = if pre_signature.negated {
+            -*adaptor_point
+        } else {
+            *adaptor_point
+        };
+        let final_nonce_point =
+            (nonce_point.into_projective() + adaptor_point.into_projective()).into_affine();
+
+        !final_nonce_point.is_zero()
+            && !final_nonce_point.y.into_repr().is_odd()
+            && final_nonce_point.x == pre_signature.r
+    }
+
+    pub fn adapt(pre_signature: &PreSignature, secret: &Fr) -> SchnorrSignature {
+        SchnorrSignature {
+            r: pre_signature.r,
+            s: pre_signature.s + pre_signature.secret(secret),
+        }
+    }
+
+    pub fn extract(
+        parameters: &SigningParameters,
+        pre_signature: &PreSignature,
+        signature: &SchnorrSignature,
+        adaptor_point: &Affine,
+    ) -> Result<Fr, Error> {
+        if signature.r != pre_signature.r {
+            return Err(Error::InvalidSignature);
+        }
+
+        let secret = pre_signature.secret(&(signature.s - pre_signature.s));
+        if private_key_to_public_key(parameters, secret).into_affine() == *adaptor_point {
+            Ok(secret)
+        } else {
+            Err(Error::InvalidSignature)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ecdsa, schnorr};
+    use crate::error::Error;
+    use crate::hash::HashFunction;
+    use crate::schnorr::verify as schnorr_verify;
+    use crate::signature::{parameters, private_key_to_public_key, verify, Signature};
+    use ark_ec::ProjectiveCurve;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    #[test]
+    fn test_ecdsa_adaptor() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let public_key = private_key_to_public_key(&parameters, private_key).into_affine();
+        let secret = Fr::rand(rng);
+        let adaptor_point = private_key_to_public_key(&parameters, secret).into_affine();
+        let msg_hash = Fq::from(0x1234u64);
+
+        let pre_signature =
+            ecdsa::pre_sign(&parameters, private_key, msg_hash, &adaptor_point).unwrap();
+        assert!(ecdsa::pre_verify(
+            &parameters,
+            &public_key,
+            &msg_hash,
+            &adaptor_point,
+            &pre_signature
+        ));
+
+        // the pre-signature alone is not a valid signature
+        let signature = ecdsa::adapt(&pre_signature, &secret).unwrap();
+        assert!(!verify(
+            &parameters,
+            &public_key,
+            &msg_hash,
+            &ecdsa::adapt(&pre_signature, &Fr::from(1u64)).unwrap()
+        ));
+        assert!(verify(&parameters, &public_key, &msg_hash, &signature));
+
+        assert_eq!(
+            secret,
+            ecdsa::extract(&parameters, &pre_signature, &signature, &adaptor_point).unwrap()
+        );
+
+        // (r, -s) is valid as well and still reveals the secret
+        let malleated = Signature {
+            r: signature.r,
+            s: -signature.s,
+        };
+        assert_eq!(
+            secret,
+            ecdsa::extract(&parameters, &pre_signature, &malleated, &adaptor_point).unwrap()
+        );
+
+        // bound to the adaptor point and the message
+        let other_point = private_key_to_public_key(&parameters, Fr::rand(rng)).into_affine();
+        assert!(!ecdsa::pre_verify(
+            &parameters,
+            &public_key,
+            &msg_hash,
+            &other_point,
+            &pre_signature
+        ));
+        assert!(!ecdsa::pre_verify(
+            &parameters,
+            &public_key,
+            &Fq::from(0x1235u64),
+            &adaptor_point,
+            &pre_signature
+        ));
+        assert_eq!(
+            Some(Error::InvalidSignature),
+            ecdsa::extract(&parameters, &pre_signature, &signature, &other_point).err()
+        );
+        assert_ne!(
+            pre_signature.nonce_point,
+            ecdsa::pre_sign(&parameters, private_key, msg_hash, &other_point)
+                .unwrap()
+                .nonce_point
+        );
+    }
+
+    #[test]
+    fn test_schnorr_adaptor() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        for hasher in [HashFunction::Pedersen, HashFunction::Poseidon] {
+            for _ in 0..4 {
+                let private_key = Fr::rand(rng);
+                let stark_key = private_key_to_public_key(&parameters, private_key)
+                    .into_affine()
+                    .x;
+                let secret = Fr::rand(rng);
+                let adaptor_point = private_key_to_public_key(&parameters, secret).into_affine();
+                let msg_hash = Fq::rand(rng);
+
+                let pre_signature =
+                    schnorr::pre_sign(&parameters, private_key, msg_hash, &adaptor_point, hasher)
+                        .unwrap();
+                assert!(schnorr::pre_verify(
+                    &parameters,
+                    &stark_key,
+                    &msg_hash,
+                    &adaptor_point,
+                    &pre_signature,
+                    hasher
+                ));
+
+                let signature = schnorr::adapt(&pre_signature, &secret);
+                assert!(schnorr_verify(
+                    &parameters,
+                    &stark_key,
+                    &msg_hash,
+                    &signature,
+                    hasher
+                ));
+                assert!(!schnorr_verify(
+                    &parameters,
+                    &stark_key,
+                    &msg_hash,
+                    &schnorr::adapt(&pre_signature, &Fr::rand(rng)),
+                    hasher
+                ));
+
+                assert_eq!(
+                    secret,
+                    schnorr::extract(&parameters, &pre_signature, &signature, &adaptor_point)
+                        .unwrap()
+                );
+
+                let other_point =
+                    private_key_to_public_key(&parameters, Fr::rand(rng)).into_affine();
+                assert!(!schnorr::pre_verify(
+                    &parameters,
+                    &stark_key,
+                    &msg_hash,
+                    &other_point,
+                    &pre_signature,
+                    hasher
+                ));
+            }
+        }
+    }
+}
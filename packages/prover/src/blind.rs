@@ -0,0 +1,254 @@
+use crate::{
+    error::Error,
+    hash::HashFunction,
+    schnorr::{challenge, lift_x, verify as schnorr_verify, SchnorrSignature},
+    signature::{private_key_to_public_key, SigningParameters},
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr};
+
+/*
+Clause blind Schnorr signatures (https://eprint.iacr.org/2022/1676), the signer signs a message it
+never sees and can't link the signature to the session that produced it.
+
+Plain blind Schnorr falls to the ROS attack when sessions run concurrently, so every session runs
+two instances: the signer commits to R_0 = r_0*G and R_1 = r_1*G, the requester blinds both with
+the same message and the signer completes only one of them, chosen at random.
+
+For each instance the requester picks alpha and beta, R' = R + alpha*G + beta*P with an even y
+coordinate, c' = H(R'.x, P.x, msg_hash) and sends c = c' + beta. The signer answers s = r + c*x
+and the unblinded signature (R'.x, s + alpha) is a signature of the schnorr module with
+poseidon_hash_many as H, which can be verified in cairo.
+*/
+
+const HASHER: HashFunction = HashFunction::Poseidon;
+
+/// The signer's nonces, consumed by the response so they are never used twice
+pub struct SignerSession {
+    nonces: [Fr; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SignerCommitment {
+    pub nonce_points: [Affine; 2],
+}
+
+/// The requester's blinding factors, consumed when unblinding
+pub struct RequesterSession {
+    msg_hash: Fq,
+    nonce_points: [Affine; 2],
+    blinded_nonce_points: [Affine; 2],
+    alphas: [Fr; 2],
+    challenges: [Fr; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlindedChallenges(pub [Fr; 2]);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SignerResponse {
+    pub index: usize,
+    pub s: Fr,
+}
+
+pub fn signer_commit<R: Rng>(
+    parameters: &SigningParameters,
+    rng: &mut R,
+) -> (SignerSession, SignerCommitment) {
+    let nonces = [Fr::rand(rng), Fr::rand(rng)];
+    let nonce_points = [
+        parameters
+            .generator
+            .mul(nonces[0].into_repr())
+            .into_affine(),
+        parameters
+            .generator
+            .mul(nonces[1].into_repr())
+            .into_affine(),
+    ];
+
+    (SignerSession { nonces }, SignerCommitment { nonce_points })
+}
+
+/// Blinds both instances of the session for the signer's stark key
+pub fn blind<R: Rng>(
+    parameters: &SigningParameters,
+    pub_key: &Fq,
+    commitment: &SignerCommitment,
+    msg_hash: Fq,
+    rng: &mut R,
+) -> Result<(RequesterSession, BlindedChallenges), Error> {
+    let point = lift_x(pub_key).ok_or(Error::InvalidPublicKey)?;
+
+    let mut alphas = [Fr::zero(); 2];
+    let mut blinded_nonce_points = [Affine::zero(); 2];
+    let mut challenges = [Fr::zero(); 2];
+    for (i, nonce_point) in commitment.nonce_points.iter().enumerate() {
+        if nonce_point.is_zero() || !nonce_point.is_on_curve() {
+            return Err(Error::InvalidSignature);
+        }
+
+        // about two tries until R' has an even y coordinate
+        let (alpha, beta, blinded_nonce_point) = loop {
+            let alpha = Fr::rand(rng);
+            let beta = Fr::rand(rng);
+            let blinded_nonce_point = (nonce_point.into_projective()
+                + parameters.generator.mul(alpha.into_repr())
+                + point.mul(beta.into_repr()))
+            .into_affine();
+
+            if !blinded_nonce_point.is_zero() && !blinded_nonce_point.y.into_repr().is_odd() {
+                break (alpha, beta, blinded_nonce_point);
+            }
+        };
+
+        alphas[i] = alpha;
+        blinded_nonce_points[i] = blinded_nonce_point;
+        challenges[i] = challenge(HASHER, &blinded_nonce_point.x, pub_key, &msg_hash) + beta;
+    }
+
+    let session = RequesterSession {
+        msg_hash,
+        nonce_points: commitment.nonce_points,
+        blinded_nonce_points,
+        alphas,
+        challenges,
+    };
+
+    Ok((session, BlindedChallenges(challenges)))
+}
+
+/// Completes one of the two instances at random, the session can't be reused
+pub fn respond<R: Rng>(
+    parameters: &SigningParameters,
+    session: SignerSession,
+    priv_key: Fr,
+    challenges: &BlindedChallenges,
+    rng: &mut R,
+) -> Result<SignerResponse, Error> {
+    if priv_key.is_zero() {
+        return Err(Error::InvalidPrivateKey);
+    }
+
+    // the public key is the point with an even y coordinate, as in the schnorr module
+    let pub_key = private_key_to_public_key(parameters, priv_key).into_affine();
+    let priv_key = if pub_key.y.into_repr().is_odd() {
+        -priv_key
+    } else {
+        priv_key
+    };
+
+    let index = rng.gen_range(0..2);
+    Ok(SignerResponse {
+        index,
+        s: session.nonces[index] + challenges.0[index] * priv_key,
+    })
+}
+
+/// Checks the response against the commitment and unblinds it into a schnorr signature
+pub fn unblind(
+    parameters: &SigningParameters,
+    session: RequesterSession,
+    pub_key: &Fq,
+    response: &SignerResponse,
+) -> Result<SchnorrSignature, Error> {
+    let point = lift_x(pub_key).ok_or(Error::InvalidPublicKey)?;
+    if response.index > 1 {
+        return Err(Error::InvalidSignature);
+    }
+
+    let i = response.index;
+    let expected =
+        session.nonce_points[i].into_projective() + point.mul(session.challenges[i].into_repr());
+    if parameters.generator.mul(response.s.into_repr()) != expected {
+        return Err(Error::InvalidSignature);
+    }
+
+    let signature = SchnorrSignature {
+        r: session.blinded_nonce_points[i].x,
+        s: response.s + session.alphas[i],
+    };
+
+    if !verify(parameters, pub_key, &session.msg_hash, &signature) {
+        return Err(Error::InvalidSignature);
+    }
+
+    Ok(signature)
+}
+
+pub fn verify(
+    parameters: &SigningParameters,
+    pub_key: &Fq,
+    msg_hash: &Fq,
+    signature: &SchnorrSignature,
+) -> bool {
+    schnorr_verify(parameters, pub_key, msg_hash, signature, HASHER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blind, respond, signer_commit, unblind, verify, BlindedChallenges};
+    use crate::error::Error;
+    use crate::signature::{parameters, private_key_to_public_key};
+    use ark_ec::ProjectiveCurve;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    #[test]
+    fn test_blind_signature() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let stark_key = private_key_to_public_key(&parameters, private_key)
+            .into_affine()
+            .x;
+
+        for _ in 0..4 {
+            let msg_hash = Fq::rand(rng);
+
+            let (signer_session, commitment) = signer_commit(&parameters, rng);
+            let (requester_session, challenges) =
+                blind(&parameters, &stark_key, &commitment, msg_hash, rng).unwrap();
+            let response =
+                respond(&parameters, signer_session, private_key, &challenges, rng).unwrap();
+            let signature = unblind(&parameters, requester_session, &stark_key, &response).unwrap();
+
+            assert!(verify(&parameters, &stark_key, &msg_hash, &signature));
+            assert!(!verify(&parameters, &stark_key, &Fq::rand(rng), &signature));
+
+            // the signer never sees the nonce point of the signature
+            assert!(!commitment
+                .nonce_points
+                .iter()
+                .any(|point| point.x == signature.r));
+        }
+    }
+
+    #[test]
+    fn test_invalid_response() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let stark_key = private_key_to_public_key(&parameters, private_key)
+            .into_affine()
+            .x;
+
+        let (signer_session, commitment) = signer_commit(&parameters, rng);
+        let (requester_session, _) =
+            blind(&parameters, &stark_key, &commitment, Fq::rand(rng), rng).unwrap();
+
+        // answering other challenges than the blinded ones
+        let challenges = BlindedChallenges([Fr::rand(rng), Fr::rand(rng)]);
+        let response = respond(&parameters, signer_session, private_key, &challenges, rng).unwrap();
+        assert_eq!(
+            Some(Error::InvalidSignature),
+            unblind(&parameters, requester_session, &stark_key, &response).err()
+        );
+    }
+}
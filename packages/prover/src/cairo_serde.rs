@@ -0,0 +1,993 @@
+use crate::{error::Error, util::be_bytes_to_field};
+
+use ark_ff::{BigInteger, BigInteger256, PrimeField};
+use starknet_curve::Fq;
+use std::collections::HashMap;
+
+/*
+Cairo 1 Serde, the encoding of calldata, events and storage values as felts
+(https://docs.starknet.io/architecture-and-concepts/smart-contracts/serialization-of-cairo-types/):
+    felt252, ContractAddress, ClassHash, bool, u8..u128    one felt
+    i8..i128                                                one felt, -x is P - x
+    bytes31                                                 one felt of at most 31 bytes
+    u256                                                    [low, high]
+    ByteArray                                               [n full words, words..., pending word, pending word len]
+    Array<T>, Span<T>                                       [len, elements...]
+    Option<T>                                               [0, value] for Some and [1] for None
+    enums                                                   [variant index, value]
+    structs and tuples                                      the members one after the other
+
+Rust types implement CairoSerde, and values that are only known at runtime, e.g. from JS, are
+encoded from their type in the contract's ABI with Abi and Value, where the ABI is built from the
+entries of its JSON with Abi::add_entries.
+*/
+
+// a ByteArray stores 31 bytes per word
+const BYTES_PER_WORD: usize = 31;
+
+pub trait CairoSerde: Sized {
+    fn serialize(&self, output: &mut Vec<Fq>);
+
+    /// Reads the value at the offset and moves the offset after it
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error>;
+}
+
+pub fn to_felts<T: CairoSerde>(value: &T) -> Vec<Fq> {
+    let mut output = vec![];
+    value.serialize(&mut output);
+
+    output
+}
+
+/// The input must be exactly one value
+pub fn from_felts<T: CairoSerde>(input: &[Fq]) -> Result<T, Error> {
+    let mut offset = 0;
+    let value = T::deserialize(input, &mut offset)?;
+    if offset != input.len() {
+        return Err(Error::IncorrectLenError);
+    }
+
+    Ok(value)
+}
+
+fn next(input: &[Fq], offset: &mut usize) -> Result<Fq, Error> {
+    let felt = input.get(*offset).ok_or(Error::IncorrectLenError)?;
+    *offset += 1;
+
+    Ok(*felt)
+}
+
+/// Felts that don't fit in 128 bits give an OverflowError
+fn felt_to_u128(felt: &Fq) -> Result<u128, Error> {
+    let repr = felt.into_repr();
+    if repr.0[2] != 0 || repr.0[3] != 0 {
+        return Err(Error::OverflowError);
+    }
+
+    Ok(((repr.0[1] as u128) << 64) + repr.0[0] as u128)
+}
+
+fn u128_to_felt(value: u128) -> Fq {
+    // it's safe to unwrap, 128 bits always fit in a felt
+    Fq::from_repr(BigInteger256([value as u64, (value >> 64) as u64, 0, 0])).unwrap()
+}
+
+/// Felts that are neither in [0, 2^127) nor in [P - 2^127, P) give an OverflowError
+fn felt_to_i128(felt: &Fq) -> Result<i128, Error> {
+    if let Ok(value) = felt_to_u128(felt) {
+        if value <= i128::MAX as u128 {
+            return Ok(value as i128);
+        }
+    }
+
+    let magnitude = felt_to_u128(&-*felt)?;
+    if magnitude > 1 << 127 {
+        return Err(Error::OverflowError);
+    }
+
+    // -2^127 is i128::MIN
+    Ok((magnitude as i128).wrapping_neg())
+}
+
+fn i128_to_felt(value: i128) -> Fq {
+    if value < 0 {
+        -u128_to_felt(value.unsigned_abs())
+    } else {
+        u128_to_felt(value as u128)
+    }
+}
+
+/// Integers of less than 128 bits must be in [-2^(bits - 1), 2^(bits - 1))
+fn check_int(value: i128, bits: u32) -> Result<i128, Error> {
+    if bits < 128 && !matches!(value >> (bits - 1), 0 | -1) {
+        return Err(Error::OverflowError);
+    }
+
+    Ok(value)
+}
+
+/// Felts of more than 31 bytes give an OverflowError
+fn check_bytes31(felt: Fq) -> Result<Fq, Error> {
+    if felt.into_repr().0[3] >> 56 != 0 {
+        return Err(Error::OverflowError);
+    }
+
+    Ok(felt)
+}
+
+/// Felt or bytes31 from the BE bytes of an integer, which must be less than P, and of at most 31
+/// bytes for bytes31
+pub fn felt_from_be_bytes(ty: &Type, bytes: &[u8]) -> Result<Fq, Error> {
+    if *ty == Type::Bytes31 && bytes.len() > BYTES_PER_WORD {
+        return Err(Error::OverflowError);
+    }
+
+    be_bytes_to_field(bytes)
+}
+
+impl CairoSerde for Fq {
+    fn serialize(&self, output: &mut Vec<Fq>) {
+        output.push(*self);
+    }
+
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+        next(input, offset)
+    }
+}
+
+impl CairoSerde for bool {
+    fn serialize(&self, output: &mut Vec<Fq>) {
+        output.push(Fq::from(*self as u64));
+    }
+
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+        match felt_to_u128(&next(input, offset)?)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error::TypeError),
+        }
+    }
+}
+
+macro_rules! impl_cairo_serde_uint {
+    ($($uint:ty),*) => {
+        $(
+            impl CairoSerde for $uint {
+                fn serialize(&self, output: &mut Vec<Fq>) {
+                    output.push(u128_to_felt(*self as u128));
+                }
+
+                fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+                    let value = felt_to_u128(&next(input, offset)?)?;
+                    <$uint>::try_from(value).map_err(|_| Error::OverflowError)
+                }
+            }
+        )*
+    };
+}
+
+impl_cairo_serde_uint!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_cairo_serde_int {
+    ($($int:ty),*) => {
+        $(
+            impl CairoSerde for $int {
+                fn serialize(&self, output: &mut Vec<Fq>) {
+                    output.push(i128_to_felt(*self as i128));
+                }
+
+                fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+                    let value = felt_to_i128(&next(input, offset)?)?;
+                    <$int>::try_from(value).map_err(|_| Error::OverflowError)
+                }
+            }
+        )*
+    };
+}
+
+impl_cairo_serde_int!(i8, i16, i32, i64, i128);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct U256 {
+    pub low: u128,
+    pub high: u128,
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        Self {
+            low: value,
+            high: 0,
+        }
+    }
+}
+
+impl CairoSerde for U256 {
+    fn serialize(&self, output: &mut Vec<Fq>) {
+        self.low.serialize(output);
+        self.high.serialize(output);
+    }
+
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+        Ok(Self {
+            low: u128::deserialize(input, offset)?,
+            high: u128::deserialize(input, offset)?,
+        })
+    }
+}
+
+/// ByteArray, strings that are not utf-8 give a TypeError when deserialized
+impl CairoSerde for String {
+    fn serialize(&self, output: &mut Vec<Fq>) {
+        let mut chunks = self.as_bytes().chunks_exact(BYTES_PER_WORD);
+
+        let words: Vec<Fq> = chunks.by_ref().map(Fq::from_be_bytes_mod_order).collect();
+        let pending_word = chunks.remainder();
+
+        output.push(Fq::from(words.len() as u64));
+        output.extend(words);
+        output.push(Fq::from_be_bytes_mod_order(pending_word));
+        output.push(Fq::from(pending_word.len() as u64));
+    }
+
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+        let word_bytes = |felt: &Fq, len: usize| -> Result<Vec<u8>, Error> {
+            let bytes = felt.into_repr().to_bytes_be();
+            if len > BYTES_PER_WORD || bytes[..bytes.len() - len].iter().any(|byte| *byte != 0) {
+                return Err(Error::OverflowError);
+            }
+
+            Ok(bytes[bytes.len() - len..].to_vec())
+        };
+
+        let n_words = u32::deserialize(input, offset)?;
+        let mut bytes = vec![];
+        for _ in 0..n_words {
+            bytes.extend(word_bytes(&next(input, offset)?, BYTES_PER_WORD)?);
+        }
+        let pending_word = next(input, offset)?;
+        let pending_len = u8::deserialize(input, offset)?;
+        bytes.extend(word_bytes(&pending_word, pending_len as usize)?);
+
+        String::from_utf8(bytes).map_err(|_| Error::TypeError)
+    }
+}
+
+/// Array and Span
+impl<T: CairoSerde> CairoSerde for Vec<T> {
+    fn serialize(&self, output: &mut Vec<Fq>) {
+        output.push(Fq::from(self.len() as u64));
+        for element in self.iter() {
+            element.serialize(output);
+        }
+    }
+
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+        let len = u32::deserialize(input, offset)?;
+        (0..len).map(|_| T::deserialize(input, offset)).collect()
+    }
+}
+
+impl<T: CairoSerde> CairoSerde for Option<T> {
+    fn serialize(&self, output: &mut Vec<Fq>) {
+        match self {
+            Some(value) => {
+                output.push(Fq::from(0u64));
+                value.serialize(output);
+            }
+            None => output.push(Fq::from(1u64)),
+        }
+    }
+
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+        match u32::deserialize(input, offset)? {
+            0 => Ok(Some(T::deserialize(input, offset)?)),
+            1 => Ok(None),
+            _ => Err(Error::TypeError),
+        }
+    }
+}
+
+impl<A: CairoSerde, B: CairoSerde> CairoSerde for (A, B) {
+    fn serialize(&self, output: &mut Vec<Fq>) {
+        self.0.serialize(output);
+        self.1.serialize(output);
+    }
+
+    fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+        Ok((
+            A::deserialize(input, offset)?,
+            B::deserialize(input, offset)?,
+        ))
+    }
+}
+
+/// Type of a value in a Cairo 1 ABI, structs and enums are referred to by name
+#[derive(Clone, Debug, PartialEq)]
+pub enum Type {
+    Felt,
+    Bool,
+    /// Unsigned integer of at most 128 bits
+    Uint(u32),
+    /// Signed integer of at most 128 bits
+    Int(u32),
+    U256,
+    Bytes31,
+    ByteArray,
+    Array(Box<Type>),
+    Option(Box<Type>),
+    /// The unit type () is the empty tuple
+    Tuple(Vec<Type>),
+    Named(String),
+}
+
+/// Splits on the commas that are not nested in brackets
+fn split_top_level(value: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !value[start..].trim().is_empty() {
+        parts.push(value[start..].trim());
+    }
+
+    parts
+}
+
+impl Type {
+    /// Parses type names of the ABI, e.g. "core::array::Array::<core::integer::u256>"
+    pub fn parse(name: &str) -> Result<Self, Error> {
+        let name = name.trim();
+
+        if let Some(inner) = name
+            .strip_prefix('(')
+            .and_then(|name| name.strip_suffix(')'))
+        {
+            return split_top_level(inner)
+                .into_iter()
+                .map(Type::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Type::Tuple);
+        }
+
+        for prefix in ["core::array::Array::<", "core::array::Span::<"] {
+            if let Some(inner) = name
+                .strip_prefix(prefix)
+                .and_then(|name| name.strip_suffix('>'))
+            {
+                return Ok(Type::Array(Box::new(Type::parse(inner)?)));
+            }
+        }
+        if let Some(inner) = name
+            .strip_prefix("core::option::Option::<")
+            .and_then(|name| name.strip_suffix('>'))
+        {
+            return Ok(Type::Option(Box::new(Type::parse(inner)?)));
+        }
+
+        let ty = match name {
+            "felt"
+            | "core::felt252"
+            | "core::starknet::contract_address::ContractAddress"
+            | "core::starknet::class_hash::ClassHash"
+            | "core::starknet::eth_address::EthAddress" => Type::Felt,
+            "core::bool" => Type::Bool,
+            "core::integer::u8" => Type::Uint(8),
+            "core::integer::u16" => Type::Uint(16),
+            "core::integer::u32" | "core::integer::usize" => Type::Uint(32),
+            "core::integer::u64" => Type::Uint(64),
+            "core::integer::u128" => Type::Uint(128),
+            "core::integer::u256" => Type::U256,
+            "core::integer::i8" => Type::Int(8),
+            "core::integer::i16" => Type::Int(16),
+            "core::integer::i32" => Type::Int(32),
+            "core::integer::i64" => Type::Int(64),
+            "core::integer::i128" => Type::Int(128),
+            "core::bytes_31::bytes31" => Type::Bytes31,
+            "core::byte_array::ByteArray" => Type::ByteArray,
+            _ if name.is_empty() => return Err(Error::TypeError),
+            _ => Type::Named(name.to_string()),
+        };
+
+        Ok(ty)
+    }
+}
+
+/// Value of a Type, struct members are in the order of the ABI and bytes31 is a Felt
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Felt(Fq),
+    Bool(bool),
+    Uint(u128),
+    Int(i128),
+    U256(U256),
+    ByteArray(String),
+    Array(Vec<Value>),
+    Option(Option<Box<Value>>),
+    Tuple(Vec<Value>),
+    Struct(Vec<Value>),
+    /// Variant name and value, the value of variants without data is the unit
+    Enum(String, Box<Value>),
+}
+
+/// Entry of a contract's JSON ABI, members, variants and inputs are (name, type name) pairs
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbiEntry {
+    Struct {
+        name: String,
+        members: Vec<(String, String)>,
+    },
+    Enum {
+        name: String,
+        variants: Vec<(String, String)>,
+    },
+    /// Functions, l1 handlers and the constructor
+    Function {
+        name: String,
+        inputs: Vec<(String, String)>,
+    },
+    Interface {
+        items: Vec<AbiEntry>,
+    },
+    /// Impls, events and the entries of older ABIs
+    Other,
+}
+
+fn parse_named_types(entries: &[(String, String)]) -> Result<Vec<(String, Type)>, Error> {
+    entries
+        .iter()
+        .map(|(name, ty)| Ok((name.clone(), Type::parse(ty)?)))
+        .collect()
+}
+
+/// Structs, enums and functions of a contract's ABI, as named members, variants and inputs with
+/// their type
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Abi {
+    structs: HashMap<String, Vec<(String, Type)>>,
+    enums: HashMap<String, Vec<(String, Type)>>,
+    functions: HashMap<String, Vec<(String, Type)>>,
+}
+
+impl Abi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the structs, enums and functions, also the ones of interfaces
+    pub fn add_entries(&mut self, entries: &[AbiEntry]) -> Result<(), Error> {
+        for entry in entries.iter() {
+            match entry {
+                AbiEntry::Struct { name, members } => {
+                    self.add_struct(name, parse_named_types(members)?)
+                }
+                AbiEntry::Enum { name, variants } => {
+                    self.add_enum(name, parse_named_types(variants)?)
+                }
+                AbiEntry::Function { name, inputs } => {
+                    self.add_function(name, parse_named_types(inputs)?)
+                }
+                AbiEntry::Interface { items } => self.add_entries(items)?,
+                AbiEntry::Other => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn add_struct(&mut self, name: &str, members: Vec<(String, Type)>) {
+        self.structs.insert(name.to_string(), members);
+    }
+
+    pub fn add_enum(&mut self, name: &str, variants: Vec<(String, Type)>) {
+        self.enums.insert(name.to_string(), variants);
+    }
+
+    pub fn add_function(&mut self, name: &str, inputs: Vec<(String, Type)>) {
+        self.functions.insert(name.to_string(), inputs);
+    }
+
+    pub fn struct_members(&self, name: &str) -> Option<&[(String, Type)]> {
+        self.structs.get(name).map(|members| members.as_slice())
+    }
+
+    pub fn enum_variants(&self, name: &str) -> Option<&[(String, Type)]> {
+        self.enums.get(name).map(|variants| variants.as_slice())
+    }
+
+    pub fn function_inputs(&self, name: &str) -> Option<&[(String, Type)]> {
+        self.functions.get(name).map(|inputs| inputs.as_slice())
+    }
+
+    /// Calldata of the function, the arguments are in the order of its inputs
+    pub fn serialize_calldata(&self, function: &str, args: &[Value]) -> Result<Vec<Fq>, Error> {
+        let inputs = self.function_inputs(function).ok_or(Error::TypeError)?;
+
+        let mut output = vec![];
+        self.serialize_members(inputs.iter().map(|(_, ty)| ty), args, &mut output)?;
+
+        Ok(output)
+    }
+
+    /// Values that don't match their type give a TypeError
+    pub fn serialize(&self, ty: &Type, value: &Value, output: &mut Vec<Fq>) -> Result<(), Error> {
+        match (ty, value) {
+            (Type::Felt, Value::Felt(felt)) => felt.serialize(output),
+            (Type::Bool, Value::Bool(value)) => value.serialize(output),
+            (Type::Uint(bits), Value::Uint(value)) => {
+                if *bits < 128 && *value >> bits != 0 {
+                    return Err(Error::OverflowError);
+                }
+                value.serialize(output);
+            }
+            (Type::Int(bits), Value::Int(value)) => check_int(*value, *bits)?.serialize(output),
+            (Type::U256, Value::U256(value)) => value.serialize(output),
+            (Type::Bytes31, Value::Felt(felt)) => check_bytes31(*felt)?.serialize(output),
+            (Type::ByteArray, Value::ByteArray(value)) => value.serialize(output),
+            (Type::Array(ty), Value::Array(values)) => {
+                output.push(Fq::from(values.len() as u64));
+                for value in values.iter() {
+                    self.serialize(ty, value, output)?;
+                }
+            }
+            (Type::Option(ty), Value::Option(value)) => match value {
+                Some(value) => {
+                    output.push(Fq::from(0u64));
+                    self.serialize(ty, value, output)?;
+                }
+                None => output.push(Fq::from(1u64)),
+            },
+            (Type::Tuple(types), Value::Tuple(values)) => {
+                self.serialize_members(types.iter(), values, output)?
+            }
+            (Type::Named(name), Value::Struct(values)) => {
+                let members = self.struct_members(name).ok_or(Error::TypeError)?;
+                self.serialize_members(members.iter().map(|(_, ty)| ty), values, output)?;
+            }
+            (Type::Named(name), Value::Enum(variant, value)) => {
+                let variants = self.enum_variants(name).ok_or(Error::TypeError)?;
+                let index = variants
+                    .iter()
+                    .position(|(name, _)| name == variant)
+                    .ok_or(Error::TypeError)?;
+
+                output.push(Fq::from(index as u64));
+                self.serialize(&variants[index].1, value, output)?;
+            }
+            _ => return Err(Error::TypeError),
+        }
+
+        Ok(())
+    }
+
+    fn serialize_members<'a>(
+        &self,
+        types: impl ExactSizeIterator<Item = &'a Type>,
+        values: &[Value],
+        output: &mut Vec<Fq>,
+    ) -> Result<(), Error> {
+        if types.len() != values.len() {
+            return Err(Error::IncorrectLenError);
+        }
+
+        types
+            .zip(values.iter())
+            .try_for_each(|(ty, value)| self.serialize(ty, value, output))
+    }
+
+    pub fn deserialize(&self, ty: &Type, input: &[Fq], offset: &mut usize) -> Result<Value, Error> {
+        let value = match ty {
+            Type::Felt => Value::Felt(next(input, offset)?),
+            Type::Bool => Value::Bool(bool::deserialize(input, offset)?),
+            Type::Uint(bits) => {
+                let value = u128::deserialize(input, offset)?;
+                if *bits < 128 && value >> bits != 0 {
+                    return Err(Error::OverflowError);
+                }
+                Value::Uint(value)
+            }
+            Type::Int(bits) => Value::Int(check_int(i128::deserialize(input, offset)?, *bits)?),
+            Type::U256 => Value::U256(U256::deserialize(input, offset)?),
+            Type::Bytes31 => Value::Felt(check_bytes31(next(input, offset)?)?),
+            Type::ByteArray => Value::ByteArray(String::deserialize(input, offset)?),
+            Type::Array(ty) => {
+                let len = u32::deserialize(input, offset)?;
+                Value::Array(
+                    (0..len)
+                        .map(|_| self.deserialize(ty, input, offset))
+                        .collect::<Result<Vec<_>, _>>()?,
+                )
+            }
+            Type::Option(ty) => match u32::deserialize(input, offset)? {
+                0 => Value::Option(Some(Box::new(self.deserialize(ty, input, offset)?))),
+                1 => Value::Option(None),
+                _ => return Err(Error::TypeError),
+            },
+            Type::Tuple(types) => Value::Tuple(
+                types
+                    .iter()
+                    .map(|ty| self.deserialize(ty, input, offset))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Type::Named(name) => {
+                if let Some(members) = self.struct_members(name) {
+                    Value::Struct(
+                        members
+                            .iter()
+                            .map(|(_, ty)| self.deserialize(ty, input, offset))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                } else {
+                    let variants = self.enum_variants(name).ok_or(Error::TypeError)?;
+                    let index = u32::deserialize(input, offset)? as usize;
+                    let (variant, ty) = variants.get(index).ok_or(Error::TypeError)?;
+
+                    Value::Enum(
+                        variant.clone(),
+                        Box::new(self.deserialize(ty, input, offset)?),
+                    )
+                }
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        felt_from_be_bytes, from_felts, to_felts, Abi, AbiEntry, CairoSerde, Type, Value, U256,
+    };
+    use crate::error::Error;
+    use crate::util::{hex_to_bytes, short_string_to_field};
+    use starknet_curve::Fq;
+
+    fn felts(values: &[u64]) -> Vec<Fq> {
+        values.iter().map(|value| Fq::from(*value)).collect()
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Transfer {
+        recipient: Fq,
+        amount: U256,
+        memo: Option<String>,
+    }
+
+    impl CairoSerde for Transfer {
+        fn serialize(&self, output: &mut Vec<Fq>) {
+            self.recipient.serialize(output);
+            self.amount.serialize(output);
+            self.memo.serialize(output);
+        }
+
+        fn deserialize(input: &[Fq], offset: &mut usize) -> Result<Self, Error> {
+            Ok(Self {
+                recipient: Fq::deserialize(input, offset)?,
+                amount: U256::deserialize(input, offset)?,
+                memo: Option::deserialize(input, offset)?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_rust_types() {
+        let transfer = Transfer {
+            recipient: Fq::from(0x1234u64),
+            amount: U256 { low: 5, high: 1 },
+            memo: Some("gm".to_string()),
+        };
+        let gm = short_string_to_field::<Fq>("gm").unwrap();
+
+        let expected = [felts(&[0x1234, 5, 1, 0, 0]), vec![gm], felts(&[2])].concat();
+        assert_eq!(expected, to_felts(&transfer));
+        assert_eq!(Ok(transfer), from_felts::<Transfer>(&expected));
+
+        let values = vec![(1u8, true), (2u8, false)];
+        assert_eq!(felts(&[2, 1, 1, 2, 0]), to_felts(&values));
+        assert_eq!(Ok(values), from_felts(&felts(&[2, 1, 1, 2, 0])));
+
+        // a string of 31 bytes is a full word and an empty pending word
+        let long = "a".repeat(31) + "bc";
+        assert_eq!(Ok(long.clone()), from_felts::<String>(&to_felts(&long)));
+        assert_eq!(4, to_felts(&long).len());
+
+        // negative integers are P - |value|
+        assert_eq!(vec![-Fq::from(1u64)], to_felts(&-1i8));
+        assert_eq!(Ok(-128i8), from_felts(&[-Fq::from(128u64)]));
+        assert_eq!(Ok(i128::MIN), from_felts(&to_felts(&i128::MIN)));
+        assert_eq!(Ok(i128::MAX), from_felts(&to_felts(&i128::MAX)));
+        assert_eq!(
+            Err(Error::OverflowError),
+            from_felts::<i8>(&[-Fq::from(129u64)])
+        );
+        assert_eq!(Err(Error::OverflowError), from_felts::<i8>(&felts(&[128])));
+
+        assert_eq!(Err(Error::OverflowError), from_felts::<u8>(&felts(&[256])));
+        assert_eq!(Err(Error::TypeError), from_felts::<bool>(&felts(&[2])));
+        assert_eq!(
+            Err(Error::IncorrectLenError),
+            from_felts::<U256>(&felts(&[1]))
+        );
+        assert_eq!(
+            Err(Error::IncorrectLenError),
+            from_felts::<u8>(&felts(&[1, 2]))
+        );
+    }
+
+    #[test]
+    fn test_abi_values() {
+        let mut abi = Abi::new();
+        abi.add_struct(
+            "game::Move",
+            vec![
+                (
+                    "player".to_string(),
+                    Type::parse("core::starknet::contract_address::ContractAddress").unwrap(),
+                ),
+                (
+                    "path".to_string(),
+                    Type::parse("core::array::Span::<(core::integer::u8, core::integer::u8)>")
+                        .unwrap(),
+                ),
+                (
+                    "bet".to_string(),
+                    Type::parse("core::option::Option::<core::integer::u256>").unwrap(),
+                ),
+            ],
+        );
+        abi.add_enum(
+            "game::Action",
+            vec![
+                ("Skip".to_string(), Type::parse("()").unwrap()),
+                ("Play".to_string(), Type::parse("game::Move").unwrap()),
+            ],
+        );
+        let ty = Type::parse("game::Action").unwrap();
+
+        let value = Value::Enum(
+            "Play".to_string(),
+            Box::new(Value::Struct(vec![
+                Value::Felt(Fq::from(0xaau64)),
+                Value::Array(vec![
+                    Value::Tuple(vec![Value::Uint(1), Value::Uint(2)]),
+                    Value::Tuple(vec![Value::Uint(3), Value::Uint(4)]),
+                ]),
+                Value::Option(Some(Box::new(Value::U256(U256::from(7u128))))),
+            ])),
+        );
+
+        let mut output = vec![];
+        abi.serialize(&ty, &value, &mut output).unwrap();
+        assert_eq!(felts(&[1, 0xaa, 2, 1, 2, 3, 4, 0, 7, 0]), output);
+        assert_eq!(Ok(value), abi.deserialize(&ty, &output, &mut 0));
+
+        let skip = Value::Enum("Skip".to_string(), Box::new(Value::Tuple(vec![])));
+        let mut output = vec![];
+        abi.serialize(&ty, &skip, &mut output).unwrap();
+        assert_eq!(felts(&[0]), output);
+
+        // values that don't match the type
+        let wrong = Value::Enum("Quit".to_string(), Box::new(Value::Tuple(vec![])));
+        assert_eq!(
+            Err(Error::TypeError),
+            abi.serialize(&ty, &wrong, &mut vec![])
+        );
+        assert_eq!(
+            Err(Error::OverflowError),
+            abi.serialize(&Type::Uint(8), &Value::Uint(256), &mut vec![])
+        );
+    }
+
+    #[test]
+    fn test_signed_and_bytes31() {
+        let abi = Abi::new();
+        let i8_type = Type::parse("core::integer::i8").unwrap();
+        assert_eq!(Type::Int(8), i8_type);
+
+        let mut output = vec![];
+        abi.serialize(&i8_type, &Value::Int(-128), &mut output)
+            .unwrap();
+        assert_eq!(vec![-Fq::from(128u64)], output);
+        assert_eq!(
+            Ok(Value::Int(-128)),
+            abi.deserialize(&i8_type, &output, &mut 0)
+        );
+        for value in [-129, 128] {
+            assert_eq!(
+                Err(Error::OverflowError),
+                abi.serialize(&i8_type, &Value::Int(value), &mut vec![])
+            );
+        }
+        assert_eq!(
+            Err(Error::OverflowError),
+            abi.deserialize(&i8_type, &[-Fq::from(129u64)], &mut 0)
+        );
+
+        let i128_type = Type::parse("core::integer::i128").unwrap();
+        let mut output = vec![];
+        abi.serialize(&i128_type, &Value::Int(i128::MIN), &mut output)
+            .unwrap();
+        assert_eq!(
+            Ok(Value::Int(i128::MIN)),
+            abi.deserialize(&i128_type, &output, &mut 0)
+        );
+
+        let bytes31 = Type::parse("core::bytes_31::bytes31").unwrap();
+        assert_eq!(Type::Bytes31, bytes31);
+        let word = short_string_to_field::<Fq>(&"a".repeat(31)).unwrap();
+        let mut output = vec![];
+        abi.serialize(&bytes31, &Value::Felt(word), &mut output)
+            .unwrap();
+        assert_eq!(vec![word], output);
+        assert_eq!(
+            Ok(Value::Felt(word)),
+            abi.deserialize(&bytes31, &output, &mut 0)
+        );
+
+        // 2^248 is 32 bytes
+        let too_long = Fq::from(1u64 << 62)
+            * Fq::from(1u64 << 62)
+            * Fq::from(1u64 << 62)
+            * Fq::from(1u64 << 62);
+        assert_eq!(
+            Err(Error::OverflowError),
+            abi.serialize(&bytes31, &Value::Felt(too_long), &mut vec![])
+        );
+        assert_eq!(
+            Err(Error::OverflowError),
+            abi.deserialize(&bytes31, &[too_long], &mut 0)
+        );
+
+        // integers from JS are bounds checked before they become felts
+        let bytes = |hex: &str| hex_to_bytes(hex).unwrap();
+        let p = bytes("0x800000000000011000000000000000000000000000000000000000000000001");
+        assert_eq!(
+            Err(Error::OverflowError),
+            felt_from_be_bytes(&Type::Felt, &p)
+        );
+        assert_eq!(
+            Err(Error::OverflowError),
+            felt_from_be_bytes(&Type::Bytes31, &p)
+        );
+        let two_248 = bytes(&format!("0x1{}", "0".repeat(62)));
+        assert_eq!(Ok(too_long), felt_from_be_bytes(&Type::Felt, &two_248));
+        assert_eq!(
+            Err(Error::OverflowError),
+            felt_from_be_bytes(&Type::Bytes31, &two_248)
+        );
+        assert_eq!(
+            Ok(too_long - Fq::from(1u64)),
+            felt_from_be_bytes(&Type::Bytes31, &bytes(&"ff".repeat(31)))
+        );
+    }
+
+    fn named(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(name, ty)| (name.to_string(), ty.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_abi_entries() {
+        let address = "core::starknet::contract_address::ContractAddress";
+        let u256 = "core::integer::u256";
+        let function = |name: &str, inputs: &[(&str, &str)]| AbiEntry::Function {
+            name: name.to_string(),
+            inputs: named(inputs),
+        };
+
+        // the JSON ABI of the ERC20 preset of OpenZeppelin Contracts for Cairo, without outputs,
+        // state mutability and the members of the events
+        let entries = vec![
+            AbiEntry::Other,
+            AbiEntry::Struct {
+                name: u256.to_string(),
+                members: named(&[
+                    ("low", "core::integer::u128"),
+                    ("high", "core::integer::u128"),
+                ]),
+            },
+            AbiEntry::Enum {
+                name: "core::bool".to_string(),
+                variants: named(&[("False", "()"), ("True", "()")]),
+            },
+            AbiEntry::Interface {
+                items: vec![
+                    function("total_supply", &[]),
+                    function("balance_of", &[("account", address)]),
+                    function("allowance", &[("owner", address), ("spender", address)]),
+                    function("transfer", &[("recipient", address), ("amount", u256)]),
+                    function(
+                        "transfer_from",
+                        &[
+                            ("sender", address),
+                            ("recipient", address),
+                            ("amount", u256),
+                        ],
+                    ),
+                    function("approve", &[("spender", address), ("amount", u256)]),
+                ],
+            },
+            function(
+                "constructor",
+                &[
+                    ("name", "core::byte_array::ByteArray"),
+                    ("symbol", "core::byte_array::ByteArray"),
+                    ("fixed_supply", u256),
+                    ("recipient", address),
+                ],
+            ),
+            AbiEntry::Other,
+        ];
+
+        let mut abi = Abi::new();
+        abi.add_entries(&entries).unwrap();
+        assert_eq!(
+            Some(
+                &[
+                    ("recipient".to_string(), Type::Felt),
+                    ("amount".to_string(), Type::U256)
+                ][..]
+            ),
+            abi.function_inputs("transfer")
+        );
+
+        let amount = Value::U256(U256 { low: 1000, high: 1 });
+        assert_eq!(
+            Ok(felts(&[0x1234, 1000, 1])),
+            abi.serialize_calldata(
+                "transfer",
+                &[Value::Felt(Fq::from(0x1234u64)), amount.clone()]
+            )
+        );
+        assert_eq!(Ok(vec![]), abi.serialize_calldata("total_supply", &[]));
+
+        let token = short_string_to_field::<Fq>("Token").unwrap();
+        let tkn = short_string_to_field::<Fq>("TKN").unwrap();
+        assert_eq!(
+            Ok([
+                felts(&[0]),
+                vec![token],
+                felts(&[5, 0]),
+                vec![tkn],
+                felts(&[3, 1000, 1, 0xaa])
+            ]
+            .concat()),
+            abi.serialize_calldata(
+                "constructor",
+                &[
+                    Value::ByteArray("Token".to_string()),
+                    Value::ByteArray("TKN".to_string()),
+                    amount.clone(),
+                    Value::Felt(Fq::from(0xaau64)),
+                ]
+            )
+        );
+
+        assert_eq!(
+            Err(Error::IncorrectLenError),
+            abi.serialize_calldata("transfer", &[Value::Felt(Fq::from(0x1234u64))])
+        );
+        assert_eq!(Err(Error::TypeError), abi.serialize_calldata("mint", &[]));
+        assert_eq!(
+            Err(Error::TypeError),
+            abi.serialize_calldata("transfer", &[amount, Value::Felt(Fq::from(0x1234u64))])
+        );
+        assert_eq!(
+            Err(Error::TypeError),
+            Abi::new().add_entries(&[function("transfer", &[("recipient", "")])])
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{
+    pedersen::compute_hash_on_elements, poseidon::poseidon_hash_many, util::short_string_to_field,
+};
+
+use ark_ff::{BigInteger256, PrimeField};
+use starknet_curve::Fq;
+
+/*
+Multicalls of an account's __execute__ and the hashes of the invoke transactions that send them.
+
+Cairo 0 accounts take the calls as a call array and the flattened calldata:
+    [call_array_len, (to, selector, data_offset, data_len)..., calldata_len, calldata...]
+and Cairo 1 accounts as an array of Call structs:
+    [calls_len, (to, selector, calldata_len, calldata...)...]
+
+Invoke v1 is hashed with Pedersen and pays a max fee, invoke v3 is hashed with Poseidon and pays
+within resource bounds (https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-8.md).
+*/
+
+const INVOKE_PREFIX: &str = "invoke";
+
+const L1_GAS: &str = "L1_GAS";
+const L2_GAS: &str = "L2_GAS";
+const L1_DATA_GAS: &str = "L1_DATA";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    pub to: Fq,
+    pub selector: Fq,
+    pub calldata: Vec<Fq>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CairoVersion {
+    Cairo0,
+    Cairo1,
+}
+
+/// Calldata of the account's __execute__ entry point
+pub fn execute_calldata(calls: &[Call], cairo_version: CairoVersion) -> Vec<Fq> {
+    let mut calldata = vec![Fq::from(calls.len() as u64)];
+
+    match cairo_version {
+        CairoVersion::Cairo0 => {
+            let mut flattened = vec![];
+            for call in calls.iter() {
+                calldata.extend_from_slice(&[
+                    call.to,
+                    call.selector,
+                    Fq::from(flattened.len() as u64),
+                    Fq::from(call.calldata.len() as u64),
+                ]);
+                flattened.extend_from_slice(&call.calldata);
+            }
+
+            calldata.push(Fq::from(flattened.len() as u64));
+            calldata.extend(flattened);
+        }
+        CairoVersion::Cairo1 => {
+            for call in calls.iter() {
+                calldata.extend_from_slice(&[
+                    call.to,
+                    call.selector,
+                    Fq::from(call.calldata.len() as u64),
+                ]);
+                calldata.extend_from_slice(&call.calldata);
+            }
+        }
+    }
+
+    calldata
+}
+
+fn prefix() -> Fq {
+    // it's safe to unwrap, the prefix is a short ascii string
+    short_string_to_field(INVOKE_PREFIX).unwrap()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvokeV1 {
+    pub sender_address: Fq,
+    pub calldata: Vec<Fq>,
+    pub max_fee: Fq,
+    pub chain_id: Fq,
+    pub nonce: Fq,
+}
+
+impl InvokeV1 {
+    pub fn hash(&self) -> Fq {
+        compute_hash_on_elements(&[
+            prefix(),
+            Fq::from(1u64),
+            self.sender_address,
+            // the entry point selector is always __execute__, which is not part of the hash
+            Fq::from(0u64),
+            compute_hash_on_elements(&self.calldata),
+            self.max_fee,
+            self.chain_id,
+            self.nonce,
+        ])
+    }
+}
+
+/// Maximum amount of a resource and maximum price per unit
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceBound {
+    pub max_amount: u64,
+    pub max_price_per_unit: u128,
+}
+
+impl ResourceBound {
+    /// resource name (60 bits) | max_amount (64 bits) | max_price_per_unit (128 bits)
+    fn encode(&self, name: &str) -> Fq {
+        // it's safe to unwrap, the names are short ascii strings of at most 7 characters
+        let name = short_string_to_field::<Fq>(name).unwrap().into_repr().0[0];
+        let price = self.max_price_per_unit;
+
+        Fq::from_repr(BigInteger256([
+            price as u64,
+            (price >> 64) as u64,
+            self.max_amount,
+            name,
+        ]))
+        .unwrap()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResourceBounds {
+    pub l1_gas: ResourceBound,
+    pub l2_gas: ResourceBound,
+    pub l1_data_gas: ResourceBound,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataAvailabilityMode {
+    L1,
+    L2,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvokeV3 {
+    pub sender_address: Fq,
+    pub calldata: Vec<Fq>,
+    pub chain_id: Fq,
+    pub nonce: Fq,
+    pub tip: u64,
+    pub resource_bounds: ResourceBounds,
+    pub paymaster_data: Vec<Fq>,
+    pub account_deployment_data: Vec<Fq>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+}
+
+impl InvokeV3 {
+    pub fn hash(&self) -> Fq {
+        let bounds = &self.resource_bounds;
+        let fee_fields_hash = poseidon_hash_many(&[
+            Fq::from(self.tip),
+            bounds.l1_gas.encode(L1_GAS),
+            bounds.l2_gas.encode(L2_GAS),
+            bounds.l1_data_gas.encode(L1_DATA_GAS),
+        ]);
+
+        // nonce mode << 32 | fee mode
+        let data_availability_modes = Fq::from(
+            ((self.nonce_data_availability_mode as u64) << 32)
+                + self.fee_data_availability_mode as u64,
+        );
+
+        poseidon_hash_many(&[
+            prefix(),
+            Fq::from(3u64),
+            self.sender_address,
+            fee_fields_hash,
+            poseidon_hash_many(&self.paymaster_data),
+            self.chain_id,
+            self.nonce,
+            data_availability_modes,
+            poseidon_hash_many(&self.account_deployment_data),
+            poseidon_hash_many(&self.calldata),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        execute_calldata, CairoVersion, Call, DataAvailabilityMode, InvokeV1, InvokeV3,
+        ResourceBound, ResourceBounds,
+    };
+    use crate::keccak::get_selector_from_name;
+    use crate::pedersen::compute_hash_on_elements;
+    use crate::poseidon::poseidon_hash_many;
+    use crate::util::{hex_to_field, short_string_to_field};
+    use starknet_curve::Fq;
+
+    fn felts(values: &[u64]) -> Vec<Fq> {
+        values.iter().map(|value| Fq::from(*value)).collect()
+    }
+
+    #[test]
+    fn test_execute_calldata() {
+        let calls = vec![
+            Call {
+                to: Fq::from(0x10u64),
+                selector: Fq::from(0x20u64),
+                calldata: felts(&[1, 2]),
+            },
+            Call {
+                to: Fq::from(0x30u64),
+                selector: Fq::from(0x40u64),
+                calldata: vec![],
+            },
+            Call {
+                to: Fq::from(0x50u64),
+                selector: Fq::from(0x60u64),
+                calldata: felts(&[3]),
+            },
+        ];
+
+        assert_eq!(
+            felts(&[3, 0x10, 0x20, 0, 2, 0x30, 0x40, 2, 0, 0x50, 0x60, 2, 1, 3, 1, 2, 3]),
+            execute_calldata(&calls, CairoVersion::Cairo0)
+        );
+        assert_eq!(
+            felts(&[3, 0x10, 0x20, 2, 1, 2, 0x30, 0x40, 0, 0x50, 0x60, 1, 3]),
+            execute_calldata(&calls, CairoVersion::Cairo1)
+        );
+
+        assert_eq!(felts(&[0, 0]), execute_calldata(&[], CairoVersion::Cairo0));
+        assert_eq!(felts(&[0]), execute_calldata(&[], CairoVersion::Cairo1));
+    }
+
+    #[test]
+    fn test_resource_bound() {
+        let bound = ResourceBound {
+            max_amount: 0x186a0,
+            max_price_per_unit: 0x5af3107a4000,
+        };
+
+        // 'L1_GAS' = 0x4c315f474153
+        let expected: Fq =
+            hex_to_field("0x4c315f47415300000000000186a0000000000000000000005af3107a4000").unwrap();
+        assert_eq!(expected, bound.encode("L1_GAS"));
+    }
+
+    #[test]
+    fn test_invoke_hash() {
+        let felt = |hex: &str| hex_to_field::<Fq>(hex).unwrap();
+
+        // an ETH transfer of 1000 to 0x1234 from a cairo 1 account on sepolia
+        let transfer = Call {
+            to: felt("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f8e004dc7"),
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: felts(&[0x1234, 1000, 0]),
+        };
+        let sender_address =
+            felt("0x2a8846878b6ad1f54f6ba46f5f40e11cee755c677f130b2c4b60566c9003f1f");
+        let calldata = execute_calldata(&[transfer], CairoVersion::Cairo1);
+        let chain_id = short_string_to_field("SN_SEPOLIA").unwrap();
+
+        // the fields of the hashes as listed in the starknet docs, 'invoke' = 0x696e766f6b65
+        let v1 = InvokeV1 {
+            sender_address,
+            calldata: calldata.clone(),
+            max_fee: Fq::from(1_000_000_000_000_000u64),
+            chain_id,
+            nonce: Fq::from(5u64),
+        };
+        assert_eq!(
+            compute_hash_on_elements(&[
+                felt("0x696e766f6b65"),
+                Fq::from(1u64),
+                sender_address,
+                Fq::from(0u64),
+                compute_hash_on_elements(&calldata),
+                Fq::from(1_000_000_000_000_000u64),
+                chain_id,
+                Fq::from(5u64),
+            ]),
+            v1.hash()
+        );
+
+        let v3 = InvokeV3 {
+            sender_address,
+            calldata: calldata.clone(),
+            chain_id,
+            nonce: Fq::from(5u64),
+            tip: 0,
+            resource_bounds: ResourceBounds {
+                l1_gas: ResourceBound {
+                    max_amount: 0x186a0,
+                    max_price_per_unit: 0x5af3107a4000,
+                },
+                l2_gas: ResourceBound {
+                    max_amount: 0x5f5e100,
+                    max_price_per_unit: 0x2540be400,
+                },
+                l1_data_gas: ResourceBound {
+                    max_amount: 0x1000,
+                    max_price_per_unit: 0x3b9aca00,
+                },
+            },
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+        };
+        let fee_fields_hash = poseidon_hash_many(&[
+            Fq::from(0u64),
+            felt("0x4c315f47415300000000000186a0000000000000000000005af3107a4000"),
+            felt("0x4c325f4741530000000005f5e100000000000000000000000002540be400"),
+            felt("0x4c315f4441544100000000000010000000000000000000000000003b9aca00"),
+        ]);
+        assert_eq!(
+            poseidon_hash_many(&[
+                felt("0x696e766f6b65"),
+                Fq::from(3u64),
+                sender_address,
+                fee_fields_hash,
+                poseidon_hash_many(&[]),
+                chain_id,
+                Fq::from(5u64),
+                Fq::from(0u64),
+                poseidon_hash_many(&[]),
+                poseidon_hash_many(&calldata),
+            ]),
+            v3.hash()
+        );
+
+        let l2_nonce = InvokeV3 {
+            nonce_data_availability_mode: DataAvailabilityMode::L2,
+            ..v3.clone()
+        };
+        assert_ne!(v3.hash(), l2_nonce.hash());
+        // L1 is 0 and L2 is 1
+        assert_eq!(
+            (0, 1),
+            (
+                DataAvailabilityMode::L1 as u64,
+                DataAvailabilityMode::L2 as u64
+            )
+        );
+    }
+}
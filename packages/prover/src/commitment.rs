@@ -0,0 +1,173 @@
+use crate::poseidon::poseidon_hash;
+use crate::util::short_string_to_field;
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand};
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr, Projective};
+use std::ops::{Add, Sub};
+
+// seed of the second generator H, nobody knows its discrete log with respect to G
+const H_SEED: &str = "STARK_PEDERSEN_COMMITMENT_H";
+
+lazy_static! {
+    static ref H: Affine = nothing_up_my_sleeve_point(H_SEED);
+}
+
+/// Hashes the seed together with a counter until the result is the x coordinate of a point,
+/// the point with the smaller y is taken. The curve has cofactor 1, so any point is a generator.
+fn nothing_up_my_sleeve_point(seed: &str) -> Affine {
+    // it's safe to unwrap, seeds are short ascii strings
+    let seed: Fq = short_string_to_field(seed).unwrap();
+
+    let mut counter = 0u64;
+    loop {
+        let x = poseidon_hash(&seed, &Fq::from(counter));
+        if let Some(point) = Affine::get_point_from_x(x, false) {
+            break point;
+        }
+
+        counter += 1;
+    }
+}
+
+pub struct CommitmentParameters {
+    pub g: Projective,
+    pub h: Projective,
+}
+
+pub fn parameters() -> CommitmentParameters {
+    CommitmentParameters {
+        g: Projective::prime_subgroup_generator(),
+        h: H.into_projective(),
+    }
+}
+
+/// Hiding and binding commitment C = m*G + r*H
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Commitment(pub Affine);
+
+/// Values that open a commitment, the blinding factor must be kept secret until opening
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Opening {
+    pub message: Fr,
+    pub blinding: Fr,
+}
+
+impl Opening {
+    pub fn new(message: Fr, blinding: Fr) -> Self {
+        Self { message, blinding }
+    }
+
+    /// Uses a random blinding factor
+    pub fn random<R: Rng>(message: Fr, rng: &mut R) -> Self {
+        Self::new(message, Fr::rand(rng))
+    }
+
+    pub fn commit(&self, parameters: &CommitmentParameters) -> Commitment {
+        let point = parameters.g.mul(self.message.into_repr())
+            + parameters.h.mul(self.blinding.into_repr());
+
+        Commitment(point.into_affine())
+    }
+}
+
+/// Commits to the message with a random blinding factor
+pub fn commit<R: Rng>(
+    parameters: &CommitmentParameters,
+    message: Fr,
+    rng: &mut R,
+) -> (Commitment, Opening) {
+    let opening = Opening::random(message, rng);
+    (opening.commit(parameters), opening)
+}
+
+pub fn verify(
+    parameters: &CommitmentParameters,
+    commitment: &Commitment,
+    opening: &Opening,
+) -> bool {
+    opening.commit(parameters) == *commitment
+}
+
+/// Commitments are homomorphic: C(m1, r1) + C(m2, r2) = C(m1 + m2, r1 + r2)
+impl Add for Commitment {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Sub for Commitment {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 + (-other.0))
+    }
+}
+
+impl Add for Opening {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.message + other.message, self.blinding + other.blinding)
+    }
+}
+
+impl Sub for Opening {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.message - other.message, self.blinding - other.blinding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commit, parameters, verify, Opening, H};
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{UniformRand, Zero};
+    use rand::thread_rng;
+    use starknet_curve::{Fr, Projective};
+
+    #[test]
+    fn test_h_is_a_valid_generator() {
+        assert!(H.is_on_curve());
+        assert!(!H.is_zero());
+        assert_ne!(Projective::prime_subgroup_generator().into_affine(), *H);
+    }
+
+    #[test]
+    fn test_commit_and_verify() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let bid = Fr::from(1000u64);
+        let (commitment, opening) = commit(&parameters, bid, rng);
+        assert!(verify(&parameters, &commitment, &opening));
+
+        let wrong_bid = Opening::new(Fr::from(999u64), opening.blinding);
+        assert!(!verify(&parameters, &commitment, &wrong_bid));
+
+        let wrong_blinding = Opening::new(bid, Fr::rand(rng));
+        assert!(!verify(&parameters, &commitment, &wrong_blinding));
+    }
+
+    #[test]
+    fn test_homomorphic_operations() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let (c1, o1) = commit(&parameters, Fr::from(30u64), rng);
+        let (c2, o2) = commit(&parameters, Fr::from(12u64), rng);
+
+        let sum = o1 + o2;
+        assert_eq!(Fr::from(42u64), sum.message);
+        assert!(verify(&parameters, &(c1 + c2), &sum));
+
+        let difference = o1 - o2;
+        assert_eq!(Fr::from(18u64), difference.message);
+        assert!(verify(&parameters, &(c1 - c2), &difference));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{error::Error, signature::SigningParameters, util::be_bytes_to_field};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use hkdf::Hkdf;
+use rand::{CryptoRng, Rng};
+use sha2::Sha256;
+use starknet_curve::{Affine, Fq, Fr};
+
+/*
+ECIES with Stark keys: ECDH on the STARK curve, HKDF-SHA256 and AES-256-GCM.
+
+Only x coordinates are used, so messages can be encrypted to a stark key: the x coordinate of
+x*P is the same for both points with the x coordinate of P.
+
+Wire format:
+    version (1 byte, 0x01)
+    || x coordinate of the ephemeral public key (32 bytes, BE)
+    || AES-GCM nonce (12 bytes)
+    || AES-GCM ciphertext with its 16 bytes tag
+
+The key is HKDF-SHA256 with the shared x coordinate (32 bytes, BE) as input key material, the
+ephemeral x coordinate (32 bytes, BE) as salt and INFO as info. The version and the ephemeral x
+coordinate are authenticated as associated data.
+*/
+
+pub const VERSION: u8 = 1;
+const INFO: &[u8] = b"STARK_ECIES_V1";
+
+const HEADER_LEN: usize = 1 + 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+fn to_be_bytes(element: &Fq) -> Vec<u8> {
+    element.into_repr().to_bytes_be()
+}
+
+/// Any point with the x coordinate of the stark key, both give the same shared secrets
+fn point_from_stark_key(stark_key: &Fq) -> Result<Affine, Error> {
+    Affine::get_point_from_x(*stark_key, false).ok_or(Error::InvalidPublicKey)
+}
+
+/// x coordinate of priv_key * pub_key
+pub fn ecdh(priv_key: &Fr, pub_key: &Affine) -> Result<Fq, Error> {
+    if priv_key.is_zero() {
+        return Err(Error::InvalidPrivateKey);
+    }
+    if pub_key.is_zero() || !pub_key.is_on_curve() {
+        return Err(Error::InvalidPublicKey);
+    }
+
+    Ok(pub_key.mul(priv_key.into_repr()).into_affine().x)
+}
+
+/// HKDF-SHA256 of the shared x coordinate, salt and info as in the wire format above
+pub fn derive_key(shared_secret: &Fq, salt: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), &to_be_bytes(shared_secret))
+        .expand(INFO, &mut key)
+        // 32 bytes is always a valid output length for SHA256
+        .unwrap();
+
+    key
+}
+
+pub fn encrypt<R: Rng + CryptoRng>(
+    parameters: &SigningParameters,
+    stark_key: &Fq,
+    plaintext: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, Error> {
+    let pub_key = point_from_stark_key(stark_key)?;
+
+    let ephemeral_key = Fr::rand(rng);
+    let ephemeral_pub_key = parameters
+        .generator
+        .mul(ephemeral_key.into_repr())
+        .into_affine();
+    let shared_secret = ecdh(&ephemeral_key, &pub_key)?;
+
+    let mut header = vec![VERSION];
+    header.extend(to_be_bytes(&ephemeral_pub_key.x));
+    let key = derive_key(&shared_secret, &header[1..]);
+
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+
+    let ciphertext = Aes256Gcm::new(&key.into())
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|_| Error::EncryptionError)?;
+
+    let mut bytes = header;
+    bytes.extend_from_slice(&nonce);
+    bytes.extend(ciphertext);
+
+    Ok(bytes)
+}
+
+pub fn decrypt(priv_key: &Fr, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+    if ciphertext.len() < HEADER_LEN + NONCE_LEN + TAG_LEN {
+        return Err(Error::IncorrectLenError);
+    }
+    if ciphertext[0] != VERSION {
+        return Err(Error::DecryptionError);
+    }
+
+    let (header, rest) = ciphertext.split_at(HEADER_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    // the header is untrusted, an x coordinate that isn't a felt is a decryption failure
+    let ephemeral_x = be_bytes_to_field(&header[1..]).map_err(|_| Error::DecryptionError)?;
+    let ephemeral_pub_key = point_from_stark_key(&ephemeral_x)?;
+    let shared_secret = ecdh(priv_key, &ephemeral_pub_key)?;
+    let key = derive_key(&shared_secret, &header[1..]);
+
+    Aes256Gcm::new(&key.into())
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| Error::DecryptionError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt, ecdh, encrypt, HEADER_LEN, NONCE_LEN, TAG_LEN};
+    use crate::error::Error;
+    use crate::signature::{parameters, private_key_to_public_key};
+    use crate::util::hex_to_bytes;
+    use ark_ec::ProjectiveCurve;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::Fr;
+
+    #[test]
+    fn test_ecdh() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let a_pub = private_key_to_public_key(&parameters, a).into_affine();
+        let b_pub = private_key_to_public_key(&parameters, b).into_affine();
+
+        assert_eq!(ecdh(&a, &b_pub).unwrap(), ecdh(&b, &a_pub).unwrap());
+        // only the x coordinate of the public key matters
+        assert_eq!(ecdh(&a, &b_pub).unwrap(), ecdh(&a, &-b_pub).unwrap());
+        assert_eq!(
+            Some(Error::InvalidPrivateKey),
+            ecdh(&Fr::from(0u64), &b_pub).err()
+        );
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let stark_key = private_key_to_public_key(&parameters, private_key)
+            .into_affine()
+            .x;
+
+        for plaintext in [&b""[..], b"gm", &[7u8; 1000]] {
+            let ciphertext = encrypt(&parameters, &stark_key, plaintext, rng).unwrap();
+            assert_eq!(
+                HEADER_LEN + NONCE_LEN + plaintext.len() + TAG_LEN,
+                ciphertext.len()
+            );
+            assert_eq!(plaintext, decrypt(&private_key, &ciphertext).unwrap());
+
+            // encryption is randomized
+            assert_ne!(
+                ciphertext,
+                encrypt(&parameters, &stark_key, plaintext, rng).unwrap()
+            );
+        }
+
+        let ciphertext = encrypt(&parameters, &stark_key, b"gm", rng).unwrap();
+        assert_eq!(
+            Some(Error::DecryptionError),
+            decrypt(&Fr::rand(rng), &ciphertext).err()
+        );
+
+        // every part of the message is authenticated
+        for i in [0, 5, HEADER_LEN + 1, ciphertext.len() - 1] {
+            let mut tampered = ciphertext.clone();
+            tampered[i] ^= 1;
+            assert!(decrypt(&private_key, &tampered).is_err());
+        }
+
+        assert_eq!(
+            Some(Error::IncorrectLenError),
+            decrypt(&private_key, &ciphertext[..HEADER_LEN + NONCE_LEN]).err()
+        );
+
+        // an ephemeral x coordinate equal to the field modulus
+        let mut overflowing = ciphertext.clone();
+        overflowing[1..HEADER_LEN].copy_from_slice(
+            &hex_to_bytes("0x0800000000000011000000000000000000000000000000000000000000000001")
+                .unwrap(),
+        );
+        assert_eq!(
+            Some(Error::DecryptionError),
+            decrypt(&private_key, &overflowing).err()
+        );
+    }
+}
@@ -10,6 +10,13 @@ pub enum Error {
     IOError,
     TypeError,
     UnsignableMessage,
+    InvalidSignature,
+    InvalidPublicKey,
+    InvalidPrivateKey,
+    InvalidThreshold,
+    InvalidShare,
+    EncryptionError,
+    DecryptionError,
 }
 
 impl Error {
@@ -18,7 +25,10 @@ impl Error {
 
         match self {
             EmptyDataError | OverflowError | IncorrectLenError | IOError | TypeError
-            | UnsignableMessage => JsValue::from(self.to_string()),
+            | UnsignableMessage | InvalidSignature | InvalidPublicKey | InvalidPrivateKey
+            | InvalidThreshold | InvalidShare | EncryptionError | DecryptionError => {
+                JsValue::from(self.to_string())
+            }
         }
     }
 }
@@ -50,6 +60,27 @@ impl fmt::Display for Error {
             Self::UnsignableMessage => {
                 write!(f, "Msg is not signable")
             }
+            Self::InvalidSignature => {
+                write!(f, "Invalid signature")
+            }
+            Self::InvalidPublicKey => {
+                write!(f, "Invalid public key")
+            }
+            Self::InvalidPrivateKey => {
+                write!(f, "Invalid private key")
+            }
+            Self::InvalidThreshold => {
+                write!(f, "Invalid threshold")
+            }
+            Self::InvalidShare => {
+                write!(f, "Invalid share")
+            }
+            Self::EncryptionError => {
+                write!(f, "Encryption failed")
+            }
+            Self::DecryptionError => {
+                write!(f, "Decryption failed")
+            }
         }
     }
 }
@@ -0,0 +1,877 @@
+use crate::{
+    error::Error,
+    hash::HashFunction,
+    poseidon::poseidon_hash_many,
+    schnorr::{challenge, verify as schnorr_verify, SchnorrSignature},
+    shares::{evaluate_commitment, evaluate_polynomial, lagrange_coefficient},
+    signature::SigningParameters,
+    util::short_string_to_field,
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr, Projective};
+
+/*
+FROST threshold Schnorr signatures (RFC 9591), producing signatures of the schnorr module.
+Hashes are Poseidon with a short string tag instead of the hash to field of the RFC ciphersuites.
+
+Keys are generated by a trusted dealer or with the distributed key generation of the FROST paper,
+where every participant shares a random polynomial and proves knowledge of its constant term.
+Signing takes two rounds: participants publish nonce commitments (D_i, E_i), then the coordinator
+sends a signing package with all the commitments and every participant answers with
+z_i = d_i + e_i * rho_i + lambda_i * c * x_i. The signature is (R.x, sum(z_i)) where
+R = sum(D_i + rho_i * E_i). As in the schnorr module, R and the group public key are taken with
+an even y coordinate, so nonces and shares are negated when needed.
+
+Identifiers are non zero integers, all messages are serialized with ark-serialize.
+*/
+
+const BINDING_FACTOR_TAG: &str = "STARK_FROST_RHO";
+const COMMITMENT_LIST_TAG: &str = "STARK_FROST_COM";
+const DKG_TAG: &str = "STARK_FROST_DKG";
+
+fn tagged_hash(tag: &str, data: &[Fq]) -> Fq {
+    // it's safe to unwrap, tags are short ascii strings
+    let mut input = vec![short_string_to_field(tag).unwrap()];
+    input.extend_from_slice(data);
+
+    poseidon_hash_many(&input)
+}
+
+fn to_scalar(element: &Fq) -> Fr {
+    Fr::from_le_bytes_mod_order(&element.into_repr().to_bytes_le())
+}
+
+fn sign_of(point: &Affine) -> Fr {
+    if point.y.into_repr().is_odd() {
+        -Fr::one()
+    } else {
+        Fr::one()
+    }
+}
+
+fn check_identifiers(identifiers: &[u64]) -> Result<(), Error> {
+    // identifiers must be sorted, unique and non zero, which also makes the encoding canonical
+    if identifiers.first() == Some(&0) || identifiers.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(Error::InvalidShare);
+    }
+
+    Ok(())
+}
+
+fn check_threshold(min_signers: u64, max_signers: u64) -> Result<(), Error> {
+    if min_signers < 2 || min_signers > max_signers {
+        return Err(Error::InvalidThreshold);
+    }
+
+    Ok(())
+}
+
+/// Long lived secret of a participant
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KeyPackage {
+    pub identifier: u64,
+    pub signing_share: Fr,
+    pub verifying_share: Affine,
+    pub group_public_key: Affine,
+    pub min_signers: u64,
+}
+
+impl KeyPackage {
+    /// The stark key that verifies the signatures of the group
+    pub fn group_key(&self) -> Fq {
+        self.group_public_key.x
+    }
+}
+
+/// Public data needed to verify signature shares
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PublicKeyPackage {
+    pub verifying_shares: Vec<(u64, Affine)>,
+    pub group_public_key: Affine,
+}
+
+impl PublicKeyPackage {
+    pub fn verifying_share(&self, identifier: u64) -> Option<Affine> {
+        self.verifying_shares
+            .iter()
+            .find(|(id, _)| *id == identifier)
+            .map(|(_, share)| *share)
+    }
+}
+
+/// Shares a random group key among participants 1..=max_signers with a degree min_signers - 1 polynomial
+pub fn trusted_dealer_keygen<R: Rng>(
+    parameters: &SigningParameters,
+    min_signers: u64,
+    max_signers: u64,
+    rng: &mut R,
+) -> Result<(Vec<KeyPackage>, PublicKeyPackage), Error> {
+    check_threshold(min_signers, max_signers)?;
+
+    let coefficients: Vec<Fr> = (0..min_signers).map(|_| Fr::rand(rng)).collect();
+    let group_public_key = parameters
+        .generator
+        .mul(coefficients[0].into_repr())
+        .into_affine();
+
+    let key_packages: Vec<KeyPackage> = (1..=max_signers)
+        .map(|identifier| {
+            let signing_share = evaluate_polynomial(&coefficients, &Fr::from(identifier));
+            KeyPackage {
+                identifier,
+                signing_share,
+                verifying_share: parameters
+                    .generator
+                    .mul(signing_share.into_repr())
+                    .into_affine(),
+                group_public_key,
+                min_signers,
+            }
+        })
+        .collect();
+
+    let public_key_package = PublicKeyPackage {
+        verifying_shares: key_packages
+            .iter()
+            .map(|package| (package.identifier, package.verifying_share))
+            .collect(),
+        group_public_key,
+    };
+
+    Ok((key_packages, public_key_package))
+}
+
+pub mod dkg {
+    use super::*;
+
+    /// Kept by the participant until the end of the key generation, it must not be shared
+    #[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+    pub struct Round1Secret {
+        pub identifier: u64,
+        coefficients: Vec<Fr>,
+        pub min_signers: u64,
+        pub max_signers: u64,
+    }
+
+    /// Broadcast to every other participant
+    #[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+    pub struct Round1Package {
+        pub identifier: u64,
+        pub commitment: Vec<Affine>,
+        // proof of knowledge of the secret coefficient
+        pub proof_r: Affine,
+        pub proof_mu: Fr,
+    }
+
+    /// Sent privately from `sender` to `receiver`
+    #[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+    pub struct Round2Package {
+        pub sender: u64,
+        pub receiver: u64,
+        pub signing_share: Fr,
+    }
+
+    fn proof_challenge(identifier: u64, public: &Affine, r: &Affine) -> Fr {
+        to_scalar(&tagged_hash(
+            DKG_TAG,
+            &[Fq::from(identifier), public.x, public.y, r.x, r.y],
+        ))
+    }
+
+    fn verify_proof(parameters: &SigningParameters, package: &Round1Package) -> bool {
+        let public = match package.commitment.first() {
+            Some(public) => public,
+            None => return false,
+        };
+
+        let c = proof_challenge(package.identifier, public, &package.proof_r);
+        parameters.generator.mul(package.proof_mu.into_repr())
+            == package.proof_r.into_projective() + public.mul(c.into_repr())
+    }
+
+    pub fn part1<R: Rng>(
+        parameters: &SigningParameters,
+        identifier: u64,
+        min_signers: u64,
+        max_signers: u64,
+        rng: &mut R,
+    ) -> Result<(Round1Secret, Round1Package), Error> {
+        check_threshold(min_signers, max_signers)?;
+        if identifier == 0 {
+            return Err(Error::InvalidShare);
+        }
+
+        let coefficients: Vec<Fr> = (0..min_signers).map(|_| Fr::rand(rng)).collect();
+        let commitment: Vec<Affine> = coefficients
+            .iter()
+            .map(|coefficient| {
+                parameters
+                    .generator
+                    .mul(coefficient.into_repr())
+                    .into_affine()
+            })
+            .collect();
+
+        let k = Fr::rand(rng);
+        let proof_r = parameters.generator.mul(k.into_repr()).into_affine();
+        let c = proof_challenge(identifier, &commitment[0], &proof_r);
+
+        let package = Round1Package {
+            identifier,
+            commitment,
+            proof_r,
+            proof_mu: k + coefficients[0] * c,
+        };
+        let secret = Round1Secret {
+            identifier,
+            coefficients,
+            min_signers,
+            max_signers,
+        };
+
+        Ok((secret, package))
+    }
+
+    /// Checks the packages of the other participants and computes the share of each of them
+    pub fn part2(
+        parameters: &SigningParameters,
+        secret: &Round1Secret,
+        round1_packages: &[Round1Package],
+    ) -> Result<Vec<Round2Package>, Error> {
+        if round1_packages.len() as u64 != secret.max_signers - 1 {
+            return Err(Error::IncorrectLenError);
+        }
+
+        round1_packages
+            .iter()
+            .map(|package| {
+                if package.identifier == secret.identifier
+                    || package.commitment.len() as u64 != secret.min_signers
+                    || !verify_proof(parameters, package)
+                {
+                    return Err(Error::InvalidShare);
+                }
+
+                Ok(Round2Package {
+                    sender: secret.identifier,
+                    receiver: package.identifier,
+                    signing_share: evaluate_polynomial(
+                        &secret.coefficients,
+                        &Fr::from(package.identifier),
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Checks the received shares against the commitments and derives the keys of the group
+    pub fn part3(
+        parameters: &SigningParameters,
+        secret: &Round1Secret,
+        round1_packages: &[Round1Package],
+        round2_packages: &[Round2Package],
+    ) -> Result<(KeyPackage, PublicKeyPackage), Error> {
+        if round1_packages.len() as u64 != secret.max_signers - 1
+            || round2_packages.len() != round1_packages.len()
+        {
+            return Err(Error::IncorrectLenError);
+        }
+
+        // the shares are checked against the commitments of every coefficient
+        if round1_packages
+            .iter()
+            .any(|package| package.commitment.len() as u64 != secret.min_signers)
+        {
+            return Err(Error::InvalidShare);
+        }
+
+        let mut senders: Vec<u64> = round1_packages
+            .iter()
+            .map(|package| package.identifier)
+            .collect();
+        senders.sort_unstable();
+
+        let mut identifiers = senders.clone();
+        identifiers.push(secret.identifier);
+        identifiers.sort_unstable();
+        check_identifiers(&identifiers)?;
+
+        // every participant must have sent exactly one share
+        let mut round2_senders: Vec<u64> = round2_packages
+            .iter()
+            .map(|package| package.sender)
+            .collect();
+        round2_senders.sort_unstable();
+        if round2_senders != senders {
+            return Err(Error::InvalidShare);
+        }
+
+        let mut signing_share =
+            evaluate_polynomial(&secret.coefficients, &Fr::from(secret.identifier));
+        for round2_package in round2_packages {
+            let round1_package = round1_packages
+                .iter()
+                .find(|package| package.identifier == round2_package.sender)
+                .ok_or(Error::InvalidShare)?;
+
+            let expected =
+                evaluate_commitment(&round1_package.commitment, &Fr::from(secret.identifier));
+            if round2_package.receiver != secret.identifier
+                || parameters
+                    .generator
+                    .mul(round2_package.signing_share.into_repr())
+                    != expected
+            {
+                return Err(Error::InvalidShare);
+            }
+
+            signing_share += round2_package.signing_share;
+        }
+
+        // commitment to the sum of all the polynomials
+        let own_commitment: Vec<Affine> = secret
+            .coefficients
+            .iter()
+            .map(|coefficient| {
+                parameters
+                    .generator
+                    .mul(coefficient.into_repr())
+                    .into_affine()
+            })
+            .collect();
+        let group_commitment: Vec<Affine> = (0..secret.min_signers as usize)
+            .map(|k| {
+                round1_packages
+                    .iter()
+                    .fold(own_commitment[k].into_projective(), |acc, package| {
+                        acc.add_mixed(&package.commitment[k])
+                    })
+                    .into_affine()
+            })
+            .collect();
+
+        let public_key_package = PublicKeyPackage {
+            verifying_shares: identifiers
+                .iter()
+                .map(|identifier| {
+                    let share = evaluate_commitment(&group_commitment, &Fr::from(*identifier));
+                    (*identifier, share.into_affine())
+                })
+                .collect(),
+            group_public_key: group_commitment[0],
+        };
+
+        let key_package = KeyPackage {
+            identifier: secret.identifier,
+            signing_share,
+            verifying_share: parameters
+                .generator
+                .mul(signing_share.into_repr())
+                .into_affine(),
+            group_public_key: group_commitment[0],
+            min_signers: secret.min_signers,
+        };
+
+        Ok((key_package, public_key_package))
+    }
+}
+
+/// Must be used for a single signature and never be shared, reusing them leaks the signing share
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct SigningNonces {
+    hiding: Fr,
+    binding: Fr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SigningCommitments {
+    pub identifier: u64,
+    pub hiding: Affine,
+    pub binding: Affine,
+}
+
+/// Sent by the coordinator to the participants of the signature, commitments are sorted by identifier
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SigningPackage {
+    pub commitments: Vec<SigningCommitments>,
+    pub msg_hash: Fq,
+    pub hasher: HashFunction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SignatureShare {
+    pub identifier: u64,
+    pub share: Fr,
+}
+
+/// First round of the signature
+pub fn commit<R: Rng>(
+    parameters: &SigningParameters,
+    key_package: &KeyPackage,
+    rng: &mut R,
+) -> (SigningNonces, SigningCommitments) {
+    let nonces = SigningNonces {
+        hiding: Fr::rand(rng),
+        binding: Fr::rand(rng),
+    };
+    let commitments = SigningCommitments {
+        identifier: key_package.identifier,
+        hiding: parameters
+            .generator
+            .mul(nonces.hiding.into_repr())
+            .into_affine(),
+        binding: parameters
+            .generator
+            .mul(nonces.binding.into_repr())
+            .into_affine(),
+    };
+
+    (nonces, commitments)
+}
+
+/// Values of a signing package shared by all the participants
+struct SigningContext {
+    identifiers: Vec<Fr>,
+    binding_factors: Vec<Fr>,
+    group_commitment: Affine,
+    challenge: Fr,
+}
+
+impl SigningContext {
+    fn new(package: &SigningPackage, group_public_key: &Affine) -> Result<Self, Error> {
+        let ids: Vec<u64> = package.commitments.iter().map(|c| c.identifier).collect();
+        check_identifiers(&ids)?;
+
+        let encoded: Vec<Fq> = package
+            .commitments
+            .iter()
+            .flat_map(|c| {
+                [
+                    Fq::from(c.identifier),
+                    c.hiding.x,
+                    c.hiding.y,
+                    c.binding.x,
+                    c.binding.y,
+                ]
+            })
+            .collect();
+        let commitments_hash = tagged_hash(COMMITMENT_LIST_TAG, &encoded);
+
+        let binding_factors: Vec<Fr> = ids
+            .iter()
+            .map(|identifier| {
+                to_scalar(&tagged_hash(
+                    BINDING_FACTOR_TAG,
+                    &[
+                        group_public_key.x,
+                        package.msg_hash,
+                        commitments_hash,
+                        Fq::from(*identifier),
+                    ],
+                ))
+            })
+            .collect();
+
+        let group_commitment = package
+            .commitments
+            .iter()
+            .zip(binding_factors.iter())
+            .fold(Projective::zero(), |acc, (c, rho)| {
+                acc.add_mixed(&c.hiding) + c.binding.mul(rho.into_repr())
+            })
+            .into_affine();
+
+        let challenge = challenge(
+            package.hasher,
+            &group_commitment.x,
+            &group_public_key.x,
+            &package.msg_hash,
+        );
+
+        Ok(Self {
+            identifiers: ids.into_iter().map(Fr::from).collect(),
+            binding_factors,
+            group_commitment,
+            challenge,
+        })
+    }
+
+    fn index(&self, identifier: u64) -> Result<usize, Error> {
+        let identifier = Fr::from(identifier);
+        self.identifiers
+            .iter()
+            .position(|id| *id == identifier)
+            .ok_or(Error::InvalidShare)
+    }
+
+    fn lambda(&self, index: usize) -> Result<Fr, Error> {
+        lagrange_coefficient(&self.identifiers[index], &self.identifiers)
+    }
+}
+
+/// Second round, the nonces are consumed so that they can't be used twice
+pub fn sign(
+    parameters: &SigningParameters,
+    signing_package: &SigningPackage,
+    nonces: SigningNonces,
+    key_package: &KeyPackage,
+) -> Result<SignatureShare, Error> {
+    if (signing_package.commitments.len() as u64) < key_package.min_signers {
+        return Err(Error::InvalidThreshold);
+    }
+
+    let context = SigningContext::new(signing_package, &key_package.group_public_key)?;
+    let index = context.index(key_package.identifier)?;
+
+    // the nonces must be the ones committed to in the first round
+    let commitments = signing_package.commitments[index];
+    if parameters.generator.mul(nonces.hiding.into_repr()) != commitments.hiding.into_projective()
+        || parameters.generator.mul(nonces.binding.into_repr())
+            != commitments.binding.into_projective()
+    {
+        return Err(Error::InvalidShare);
+    }
+
+    let nonce_sign = sign_of(&context.group_commitment);
+    let key_sign = sign_of(&key_package.group_public_key);
+    let k = nonces.hiding + nonces.binding * context.binding_factors[index];
+
+    Ok(SignatureShare {
+        identifier: key_package.identifier,
+        share: nonce_sign * k
+            + context.lambda(index)? * context.challenge * key_sign * key_package.signing_share,
+    })
+}
+
+fn verify_share(
+    parameters: &SigningParameters,
+    context: &SigningContext,
+    signing_package: &SigningPackage,
+    public_key_package: &PublicKeyPackage,
+    share: &SignatureShare,
+) -> Result<bool, Error> {
+    let index = context.index(share.identifier)?;
+    let verifying_share = public_key_package
+        .verifying_share(share.identifier)
+        .ok_or(Error::InvalidShare)?;
+
+    let commitments = signing_package.commitments[index];
+    let nonce_sign = sign_of(&context.group_commitment);
+    let key_sign = sign_of(&public_key_package.group_public_key);
+
+    let commitment_share = commitments.hiding.into_projective()
+        + commitments
+            .binding
+            .mul(context.binding_factors[index].into_repr());
+    let expected = commitment_share.mul(nonce_sign.into_repr())
+        + verifying_share.mul((context.lambda(index)? * context.challenge * key_sign).into_repr());
+
+    Ok(parameters.generator.mul(share.share.into_repr()) == expected)
+}
+
+/// Checks z_i*G = D_i + rho_i*E_i + lambda_i*c*Y_i, with the same sign adjustments as sign
+pub fn verify_signature_share(
+    parameters: &SigningParameters,
+    signing_package: &SigningPackage,
+    public_key_package: &PublicKeyPackage,
+    share: &SignatureShare,
+) -> Result<bool, Error> {
+    let context = SigningContext::new(signing_package, &public_key_package.group_public_key)?;
+    verify_share(
+        parameters,
+        &context,
+        signing_package,
+        public_key_package,
+        share,
+    )
+}
+
+/// Sums the shares into a signature verified by schnorr::verify with the group key.
+/// If the signature is invalid, the shares are checked to fail with InvalidShare on a cheater.
+pub fn aggregate(
+    parameters: &SigningParameters,
+    signing_package: &SigningPackage,
+    shares: &[SignatureShare],
+    public_key_package: &PublicKeyPackage,
+) -> Result<SchnorrSignature, Error> {
+    if shares.len() != signing_package.commitments.len() {
+        return Err(Error::IncorrectLenError);
+    }
+
+    let context = SigningContext::new(signing_package, &public_key_package.group_public_key)?;
+    let signature = SchnorrSignature {
+        r: context.group_commitment.x,
+        s: shares.iter().fold(Fr::zero(), |s, share| s + share.share),
+    };
+
+    if schnorr_verify(
+        parameters,
+        &public_key_package.group_public_key.x,
+        &signing_package.msg_hash,
+        &signature,
+        signing_package.hasher,
+    ) {
+        return Ok(signature);
+    }
+
+    for share in shares {
+        if !verify_share(
+            parameters,
+            &context,
+            signing_package,
+            public_key_package,
+            share,
+        )? {
+            return Err(Error::InvalidShare);
+        }
+    }
+
+    Err(Error::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        aggregate, commit, dkg, sign, trusted_dealer_keygen, verify_signature_share, KeyPackage,
+        PublicKeyPackage, SignatureShare, SigningCommitments, SigningNonces, SigningPackage,
+    };
+    use crate::error::Error;
+    use crate::hash::HashFunction;
+    use crate::schnorr::verify;
+    use crate::signature::parameters;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    fn sign_with(
+        key_packages: &[&KeyPackage],
+        public_key_package: &PublicKeyPackage,
+        msg_hash: Fq,
+        hasher: HashFunction,
+    ) -> Result<crate::schnorr::SchnorrSignature, Error> {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let (nonces, commitments): (Vec<SigningNonces>, Vec<SigningCommitments>) = key_packages
+            .iter()
+            .map(|package| commit(&parameters, package, rng))
+            .unzip();
+        let signing_package = SigningPackage {
+            commitments,
+            msg_hash,
+            hasher,
+        };
+
+        let shares: Vec<SignatureShare> = nonces
+            .into_iter()
+            .zip(key_packages)
+            .map(|(nonces, package)| sign(&parameters, &signing_package, nonces, package))
+            .collect::<Result<_, _>>()?;
+
+        for share in shares.iter() {
+            assert!(verify_signature_share(
+                &parameters,
+                &signing_package,
+                public_key_package,
+                share
+            )
+            .unwrap());
+        }
+
+        aggregate(&parameters, &signing_package, &shares, public_key_package)
+    }
+
+    #[test]
+    fn test_trusted_dealer() {
+        let parameters = parameters();
+        let rng = &mut thread_rng();
+
+        for hasher in [HashFunction::Pedersen, HashFunction::Poseidon] {
+            let (key_packages, public_key_package) =
+                trusted_dealer_keygen(&parameters, 3, 5, rng).unwrap();
+            let group_key = public_key_package.group_public_key.x;
+
+            for signers in [[0, 1, 2], [0, 2, 4], [1, 3, 4]] {
+                let msg_hash = Fq::rand(rng);
+                let signers: Vec<&KeyPackage> = signers.iter().map(|i| &key_packages[*i]).collect();
+
+                let sig = sign_with(&signers, &public_key_package, msg_hash, hasher).unwrap();
+                assert!(verify(&parameters, &group_key, &msg_hash, &sig, hasher));
+            }
+
+            // not enough signers
+            let signers: Vec<&KeyPackage> = key_packages[..2].iter().collect();
+            assert_eq!(
+                Some(Error::InvalidThreshold),
+                sign_with(&signers, &public_key_package, Fq::rand(rng), hasher).err()
+            );
+        }
+
+        assert_eq!(
+            Some(Error::InvalidThreshold),
+            trusted_dealer_keygen(&parameters, 4, 3, rng).err()
+        );
+    }
+
+    #[test]
+    fn test_dkg() {
+        let parameters = parameters();
+        let rng = &mut thread_rng();
+        let (min_signers, max_signers) = (2, 3);
+
+        let (secrets, round1_packages): (Vec<dkg::Round1Secret>, Vec<dkg::Round1Package>) = (1
+            ..=max_signers)
+            .map(|identifier| dkg::part1(&parameters, identifier, min_signers, max_signers, rng))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .into_iter()
+            .unzip();
+
+        let others = |identifier: u64| -> Vec<dkg::Round1Package> {
+            round1_packages
+                .iter()
+                .filter(|package| package.identifier != identifier)
+                .cloned()
+                .collect()
+        };
+
+        let round2_packages: Vec<dkg::Round2Package> = secrets
+            .iter()
+            .flat_map(|secret| dkg::part2(&parameters, secret, &others(secret.identifier)).unwrap())
+            .collect();
+
+        let mut key_packages = vec![];
+        let mut public_key_packages = vec![];
+        for secret in secrets.iter() {
+            let received: Vec<dkg::Round2Package> = round2_packages
+                .iter()
+                .filter(|package| package.receiver == secret.identifier)
+                .cloned()
+                .collect();
+
+            let (key_package, public_key_package) =
+                dkg::part3(&parameters, secret, &others(secret.identifier), &received).unwrap();
+            key_packages.push(key_package);
+            public_key_packages.push(public_key_package);
+        }
+
+        // everyone agrees on the group key
+        assert!(public_key_packages
+            .windows(2)
+            .all(|pair| pair[0] == pair[1]));
+
+        let msg_hash = Fq::rand(rng);
+        let signers = [&key_packages[0], &key_packages[2]];
+        let sig = sign_with(
+            &signers,
+            &public_key_packages[0],
+            msg_hash,
+            HashFunction::Poseidon,
+        )
+        .unwrap();
+        assert!(verify(
+            &parameters,
+            &key_packages[0].group_key(),
+            &msg_hash,
+            &sig,
+            HashFunction::Poseidon
+        ));
+
+        // a tampered share is detected
+        let mut received: Vec<dkg::Round2Package> = round2_packages
+            .iter()
+            .filter(|package| package.receiver == 1)
+            .cloned()
+            .collect();
+        received[0].signing_share += Fr::from(1u64);
+        assert_eq!(
+            Some(Error::InvalidShare),
+            dkg::part3(&parameters, &secrets[0], &others(1), &received).err()
+        );
+
+        // and so is an invalid proof of knowledge
+        let mut packages = others(1);
+        packages[0].proof_mu += Fr::from(1u64);
+        assert_eq!(
+            Some(Error::InvalidShare),
+            dkg::part2(&parameters, &secrets[0], &packages).err()
+        );
+
+        // round2 senders must be exactly the round1 participants
+        let received: Vec<dkg::Round2Package> = round2_packages
+            .iter()
+            .filter(|package| package.receiver == 1)
+            .cloned()
+            .collect();
+        let duplicated = vec![received[0].clone(), received[0].clone()];
+        assert_eq!(
+            Some(Error::InvalidShare),
+            dkg::part3(&parameters, &secrets[0], &others(1), &duplicated).err()
+        );
+        assert_eq!(
+            Some(Error::IncorrectLenError),
+            dkg::part3(&parameters, &secrets[0], &others(1), &received[..1]).err()
+        );
+        let mut unknown = received.clone();
+        unknown[1].sender = 4;
+        assert_eq!(
+            Some(Error::InvalidShare),
+            dkg::part3(&parameters, &secrets[0], &others(1), &unknown).err()
+        );
+
+        // malformed round1 packages are rejected before they are used
+        let mut packages = others(1);
+        packages[0].commitment.pop();
+        assert_eq!(
+            Some(Error::InvalidShare),
+            dkg::part3(&parameters, &secrets[0], &packages, &received).err()
+        );
+        let mut packages = others(1);
+        packages[1].identifier = packages[0].identifier;
+        assert_eq!(
+            Some(Error::InvalidShare),
+            dkg::part3(&parameters, &secrets[0], &packages, &received).err()
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let parameters = parameters();
+        let rng = &mut thread_rng();
+
+        let (key_packages, public_key_package) =
+            trusted_dealer_keygen(&parameters, 2, 2, rng).unwrap();
+
+        let mut bytes = vec![];
+        key_packages[0].serialize(&mut bytes).unwrap();
+        assert_eq!(
+            key_packages[0],
+            KeyPackage::deserialize(bytes.as_slice()).unwrap()
+        );
+
+        let mut bytes = vec![];
+        public_key_package.serialize(&mut bytes).unwrap();
+        assert_eq!(
+            public_key_package,
+            PublicKeyPackage::deserialize(bytes.as_slice()).unwrap()
+        );
+
+        let (_, commitments) = commit(&parameters, &key_packages[0], rng);
+        let signing_package = SigningPackage {
+            commitments: vec![commitments],
+            msg_hash: Fq::rand(rng),
+            hasher: HashFunction::Pedersen,
+        };
+        let mut bytes = vec![];
+        signing_package.serialize(&mut bytes).unwrap();
+        assert_eq!(
+            signing_package,
+            SigningPackage::deserialize(bytes.as_slice()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use crate::pedersen::{compute_hash_on_elements, pedersen_hash};
+use crate::poseidon::{poseidon_hash, poseidon_hash_many};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use starknet_curve::Fq;
+use wasm_bindgen::prelude::*;
+
+/// Two to one hash functions available in Starknet
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HashFunction {
+    Pedersen,
+    Poseidon,
+}
+
+impl HashFunction {
+    pub fn hash(&self, x: &Fq, y: &Fq) -> Fq {
+        match self {
+            Self::Pedersen => pedersen_hash(x, y),
+            Self::Poseidon => poseidon_hash(x, y),
+        }
+    }
+
+    /// compute_hash_on_elements for Pedersen and poseidon_hash_many for Poseidon,
+    /// which is what hashing an array gives in cairo
+    pub fn hash_many(&self, data: &[Fq]) -> Fq {
+        match self {
+            Self::Pedersen => compute_hash_on_elements(data),
+            Self::Poseidon => poseidon_hash_many(data),
+        }
+    }
+}
+
+/// Serialized as a single byte, so that it can be part of serialized protocol messages
+impl CanonicalSerialize for HashFunction {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        (*self as u8).serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        1
+    }
+}
+
+impl CanonicalDeserialize for HashFunction {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        match u8::deserialize(reader)? {
+            0 => Ok(Self::Pedersen),
+            1 => Ok(Self::Poseidon),
+            _ => Err(SerializationError::InvalidData),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{
+    constants::{
+        ALPHA, BETA, HASH_SHIFT_POINT_X, HASH_SHIFT_POINT_Y, P0_STATIC_X, P0_STATIC_Y, P1_STATIC_X,
+        P1_STATIC_Y, P2_STATIC_X, P2_STATIC_Y, P3_STATIC_X, P3_STATIC_Y, PI_DIGITS,
+    },
+    error::Error,
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{field_new, BigInteger, Field, One, PrimeField, SquareRootField, Zero};
+use sha2::{Digest, Sha256};
+use starknet_curve::{Affine, Fq};
+
+/*
+Hashing to the STARK curve following RFC 9380 (https://www.rfc-editor.org/rfc/rfc9380)
+
+expand_message_xmd with SHA-256, hash_to_field with L = 48 bytes (k = 128) and the simplified SWU
+map, which applies directly since A and B are both non zero. The cofactor is 1, so clear_cofactor
+is the identity. Suites are STARK_XMD:SHA-256_SSWU_RO_ (hash_to_curve) and
+STARK_XMD:SHA-256_SSWU_NU_ (encode_to_curve), each application should use its own DST.
+*/
+
+pub const SUITE_RO: &str = "STARK_XMD:SHA-256_SSWU_RO_";
+pub const SUITE_NU: &str = "STARK_XMD:SHA-256_SSWU_NU_";
+
+// ceil((ceil(log2(p)) + k) / 8) with k = 128
+const L: usize = 48;
+const MAX_DST_LEN: usize = 255;
+const MAX_ELL: usize = 255;
+
+// the Z returned by find_z_sswu of RFC 9380 appendix H.2, -3 is rejected since g(B / (-3 A)) isn't a
+// square, test_z runs the search again
+const Z: Fq = field_new!(Fq, "19");
+
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Result<Vec<u8>, Error> {
+    let b_in_bytes = Sha256::output_size();
+    let ell = len_in_bytes.div_ceil(b_in_bytes);
+    if ell > MAX_ELL || len_in_bytes > u16::MAX as usize || dst.len() > MAX_DST_LEN {
+        return Err(Error::IncorrectLenError);
+    }
+
+    let mut dst_prime = dst.to_vec();
+    dst_prime.push(dst.len() as u8);
+
+    // r_in_bytes of SHA-256 is 64
+    let b_0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut uniform_bytes = b_i.to_vec();
+
+    for i in 2..=ell {
+        let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    Ok(uniform_bytes)
+}
+
+pub fn hash_to_field(msg: &[u8], dst: &[u8], count: usize) -> Result<Vec<Fq>, Error> {
+    let uniform_bytes = expand_message_xmd(msg, dst, count * L)?;
+
+    Ok(uniform_bytes
+        .chunks(L)
+        .map(|chunk| {
+            let mut le_bytes = chunk.to_vec();
+            le_bytes.reverse();
+            Fq::from_le_bytes_mod_order(&le_bytes)
+        })
+        .collect())
+}
+
+fn sgn0(element: &Fq) -> bool {
+    element.into_repr().is_odd()
+}
+
+fn curve_equation(x: &Fq) -> Fq {
+    x.square() * x + ALPHA * x + BETA
+}
+
+/// Simplified SWU of RFC 9380 section 6.6.2
+pub fn map_to_curve(u: &Fq) -> Affine {
+    let z_u2 = Z * u.square();
+    let tv1 = (z_u2.square() + z_u2).inverse().unwrap_or_else(Fq::zero);
+
+    let x1 = if tv1.is_zero() {
+        // ZA is not zero
+        BETA * (Z * ALPHA).inverse().unwrap()
+    } else {
+        // A is not zero
+        -BETA * ALPHA.inverse().unwrap() * (Fq::one() + tv1)
+    };
+
+    // Z was chosen so that one of gx1 and gx2 is a square
+    let (x, y) = match curve_equation(&x1).sqrt() {
+        Some(y) => (x1, y),
+        None => {
+            let x2 = z_u2 * x1;
+            (x2, curve_equation(&x2).sqrt().unwrap())
+        }
+    };
+
+    let y = if sgn0(u) != sgn0(&y) { -y } else { y };
+    Affine::new(x, y, false)
+}
+
+/// Uniform encoding, hash_to_curve of RFC 9380 section 3
+pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Result<Affine, Error> {
+    let u = hash_to_field(msg, dst, 2)?;
+    let q0 = map_to_curve(&u[0]);
+    let q1 = map_to_curve(&u[1]);
+
+    Ok((q0.into_projective() + q1.into_projective()).into_affine())
+}
+
+/// Non uniform encoding, encode_to_curve of RFC 9380 section 3
+pub fn encode_to_curve(msg: &[u8], dst: &[u8]) -> Result<Affine, Error> {
+    let u = hash_to_field(msg, dst, 1)?;
+    Ok(map_to_curve(&u[0]))
+}
+
+/// StarkWare's derivation of the constant points: the chunk of pi digits is reduced into a field
+/// element, incremented until it is the x coordinate of a point and the point with the smaller y
+/// is taken. Chunk 1 is the shift point, chunk 2 the generator and chunks 3 to 6 the static points.
+pub fn pi_point(chunk: usize) -> Result<Affine, Error> {
+    let digits = PI_DIGITS.get(chunk).ok_or(Error::IncorrectLenError)?;
+
+    let ten = Fq::from(10u64);
+    let mut x = digits.bytes().fold(Fq::zero(), |acc, digit| {
+        acc * ten + Fq::from((digit - b'0') as u64)
+    });
+
+    loop {
+        if let Some(point) = Affine::get_point_from_x(x, false) {
+            break Ok(point);
+        }
+
+        x += Fq::one();
+    }
+}
+
+/// Regenerates the shift point and P0_STATIC..P3_STATIC, and checks them against the constants
+pub fn check_constant_points() -> Result<bool, Error> {
+    let constants = [
+        (HASH_SHIFT_POINT_X, HASH_SHIFT_POINT_Y),
+        (P0_STATIC_X, P0_STATIC_Y),
+        (P1_STATIC_X, P1_STATIC_Y),
+        (P2_STATIC_X, P2_STATIC_Y),
+        (P3_STATIC_X, P3_STATIC_Y),
+    ];
+
+    for (chunk, (x, y)) in [1, 3, 4, 5, 6].into_iter().zip(constants) {
+        if pi_point(chunk)? != Affine::new(x, y, false) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_constant_points, curve_equation, encode_to_curve, expand_message_xmd, hash_to_curve,
+        map_to_curve, pi_point, Z,
+    };
+    use crate::constants::{ALPHA, BETA};
+    use crate::signature::parameters;
+    use crate::util::hex_to_bytes;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{field_new, BitIteratorBE, Field, One, SquareRootField, Zero};
+    use starknet_curve::Fq;
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // RFC 9380 appendix K.1
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            hex_to_bytes("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")
+                .unwrap(),
+            expand_message_xmd(b"", dst, 0x20).unwrap()
+        );
+        assert_eq!(
+            hex_to_bytes("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615")
+                .unwrap(),
+            expand_message_xmd(b"abc", dst, 0x20).unwrap()
+        );
+        assert!(expand_message_xmd(b"abc", &[0u8; 256], 0x20).is_err());
+    }
+
+    #[test]
+    fn test_hash_to_curve() {
+        let dst = b"STARK_TEST-V01-CS01-with-STARK_XMD:SHA-256_SSWU_RO_";
+
+        // from python_scripts/hash_to_curve.py
+        let point = hash_to_curve(b"abc", dst).unwrap();
+        assert!(point.is_on_curve());
+        assert_eq!(
+            field_new!(
+                Fq,
+                "2090449838004615745382049599067939464328862877745818583908506447959240114869"
+            ),
+            point.x
+        );
+        assert_eq!(
+            field_new!(
+                Fq,
+                "2081915497971688017863726367263073809034420512792161933949056423036840917672"
+            ),
+            point.y
+        );
+        assert_eq!(point, hash_to_curve(b"abc", dst).unwrap());
+        assert_ne!(point, hash_to_curve(b"abd", dst).unwrap());
+        assert_ne!(point, hash_to_curve(b"abc", b"OTHER_DST").unwrap());
+        assert_ne!(point, encode_to_curve(b"abc", dst).unwrap());
+
+        for u in [0u64, 1, 2, 19, 12345] {
+            assert!(map_to_curve(&Fq::from(u)).is_on_curve());
+        }
+    }
+
+    fn trim(mut f: Vec<Fq>) -> Vec<Fq> {
+        while f.last().is_some_and(|c| c.is_zero()) {
+            f.pop();
+        }
+        f
+    }
+
+    // whether x^3 + Ax + c has a root, i.e. gcd(x^P - x, x^3 + Ax + c) != 1
+    fn has_root(c: Fq) -> bool {
+        let mul_mod = |a: &[Fq; 3], b: &[Fq; 3]| {
+            let mut r = [Fq::zero(); 5];
+            for i in 0..3 {
+                for j in 0..3 {
+                    r[i + j] += a[i] * b[j];
+                }
+            }
+            for d in [4, 3] {
+                let top = r[d];
+                r[d] = Fq::zero();
+                r[d - 2] -= top * ALPHA;
+                r[d - 3] -= top * c;
+            }
+            [r[0], r[1], r[2]]
+        };
+
+        let mut x_p = [Fq::one(), Fq::zero(), Fq::zero()];
+        for bit in BitIteratorBE::without_leading_zeros(Fq::characteristic()) {
+            x_p = mul_mod(&x_p, &x_p);
+            if bit {
+                x_p = mul_mod(&x_p, &[Fq::zero(), Fq::one(), Fq::zero()]);
+            }
+        }
+
+        let mut f = vec![c, ALPHA, Fq::zero(), Fq::one()];
+        let mut h = trim(vec![x_p[0], x_p[1] - Fq::one(), x_p[2]]);
+        while !h.is_empty() {
+            while f.len() >= h.len() {
+                let q = *f.last().unwrap() * h.last().unwrap().inverse().unwrap();
+                let shift = f.len() - h.len();
+                for (i, coeff) in h.iter().enumerate() {
+                    f[shift + i] -= q * coeff;
+                }
+                f = trim(f);
+            }
+            std::mem::swap(&mut f, &mut h);
+        }
+        f.len() > 1
+    }
+
+    // the criteria of find_z_sswu, RFC 9380 appendix H.2
+    fn is_valid_z(z: Fq) -> bool {
+        z.sqrt().is_none()
+            && z != -Fq::one()
+            && !has_root(BETA - z)
+            && curve_equation(&(BETA * (z * ALPHA).inverse().unwrap()))
+                .sqrt()
+                .is_some()
+    }
+
+    #[test]
+    fn test_z() {
+        // 3 is a non square but g(x) - 3 has a root, -3 fails the last criterion
+        assert!(Fq::from(3u64).sqrt().is_none() && has_root(BETA - Fq::from(3u64)));
+        assert!(!has_root(BETA + Fq::from(3u64)));
+        assert!(!is_valid_z(Fq::from(3u64)));
+        assert!(!is_valid_z(-Fq::from(3u64)));
+
+        let found = (1u64..)
+            .flat_map(|ctr| [Fq::from(ctr), -Fq::from(ctr)])
+            .find(|z| is_valid_z(*z))
+            .unwrap();
+        assert_eq!(Z, found);
+        assert!(curve_equation(&(BETA * (Z * ALPHA).inverse().unwrap()))
+            .sqrt()
+            .is_some());
+    }
+
+    #[test]
+    fn test_constant_points() {
+        assert!(check_constant_points().unwrap());
+        assert_eq!(parameters().generator.into_affine(), pi_point(2).unwrap());
+    }
+}
@@ -0,0 +1,59 @@
+use crate::error::Error;
+
+use ark_ff::{PrimeField, Zero};
+use sha3::{Digest, Keccak256};
+use starknet_curve::Fq;
+
+const DEFAULT_ENTRY_POINT_NAME: &str = "__default__";
+const DEFAULT_L1_ENTRY_POINT_NAME: &str = "__l1_default__";
+
+/// Keccak256 of the data masked to the 250 lowest bits so that it always fits in a felt
+pub fn starknet_keccak(data: &[u8]) -> Fq {
+    let mut hash: [u8; 32] = Keccak256::digest(data).into();
+    hash[0] &= 0x03;
+
+    // 250 bits always fit in Fq
+    Fq::from_be_bytes_mod_order(&hash)
+}
+
+/// Computes the selector of an entry point, default entry points have selector 0
+pub fn get_selector_from_name(name: &str) -> Result<Fq, Error> {
+    if name == DEFAULT_ENTRY_POINT_NAME || name == DEFAULT_L1_ENTRY_POINT_NAME {
+        return Ok(Fq::zero());
+    }
+
+    // cairo-lang encodes names in ascii
+    if !name.is_ascii() {
+        return Err(Error::TypeError);
+    }
+
+    Ok(starknet_keccak(name.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_selector_from_name, starknet_keccak};
+    use crate::error::Error;
+    use ark_ff::field_new;
+    use starknet_curve::Fq;
+
+    #[test]
+    fn test_starknet_keccak_with_cairo() {
+        // CAIRO: starknet_keccak(b"execute") -> 0x240060cdb34fcc260f41eac7474ee1d7c80b7e3607daff9ac67c7ea2ebb1c44
+
+        let expected = field_new!(
+            Fq,
+            "1017745666394979726211766185068760164586829337678283062942418931026954492996"
+        );
+
+        assert_eq!(expected, starknet_keccak(b"execute"));
+        assert_eq!(Ok(expected), get_selector_from_name("execute"));
+    }
+
+    #[test]
+    fn test_default_and_invalid_selectors() {
+        assert_eq!(Ok(Fq::from(0u64)), get_selector_from_name("__default__"));
+        assert_eq!(Ok(Fq::from(0u64)), get_selector_from_name("__l1_default__"));
+        assert_eq!(Err(Error::TypeError), get_selector_from_name("🦀"));
+    }
+}
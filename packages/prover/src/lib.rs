@@ -1,23 +1,59 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod adaptor;
+pub mod blind;
+pub mod cairo_serde;
+pub mod calls;
+pub mod commitment;
 mod constants;
+pub mod ecies;
 mod error;
-mod pedersen;
+pub mod frost;
+pub mod hash;
+pub mod hash_to_curve;
+pub mod keccak;
+pub mod merkle;
+pub mod musig;
+pub mod outside_execution;
+pub mod paillier;
+pub mod pedersen;
+pub mod poseidon;
 mod rfc6979;
+pub mod ring;
+pub mod schnorr;
+pub mod session;
+pub mod shares;
+pub mod sigma;
 mod signature;
+mod stark_curve;
+pub mod storage;
+pub mod transcript;
+pub mod trie;
+pub mod two_party_ecdsa;
+pub mod typed_data;
 mod util;
+pub mod vrf;
 
 use ark_ec::ProjectiveCurve;
 use ark_ff::UniformRand;
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::{BigInteger, BigInteger256, PrimeField};
 use js_sys::Uint8Array;
+use num_bigint_dig::BigUint;
 use rand::rngs::OsRng;
-use starknet_curve::{Fq, Fr};
+use starknet_curve::{Affine, Fq, Fr};
 
 use error::Error;
-use pedersen::compute_hash_on_elements;
-use signature::{parameters, private_key_to_public_key, sign as starknet_sign};
+use hash::HashFunction;
+use keccak::get_selector_from_name;
+use merkle::verify_merkle_proof;
+use pedersen::{
+    compute_hash_chain_with_length_prefix, compute_hash_on_elements,
+    compute_hash_on_elements_with_initial, compute_hash_on_elements_without_length, pedersen_hash,
+};
+use signature::{parameters, private_key_to_public_key, sign as starknet_sign, SigningParameters};
+use stark_curve::MAX_SAFE_INTEGER;
+use storage::get_storage_var_address;
 use util::{bytes_safe, try_bytes_to_field};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
@@ -49,6 +85,25 @@ impl PublicKey {
     pub fn get_y(&self) -> Uint8Array {
         Uint8Array::from(&self.y[..])
     }
+
+    fn from_inner(point: &Affine) -> Self {
+        Self::new(
+            point.x.into_repr().to_bytes_le(),
+            point.y.into_repr().to_bytes_le(),
+        )
+    }
+
+    fn to_inner(&self) -> Result<Affine, JsValue> {
+        let x = try_bytes_to_field(&self.x).map_err(|e| e.to_jsval())?;
+        let y = try_bytes_to_field(&self.y).map_err(|e| e.to_jsval())?;
+
+        let point = Affine::new(x, y, false);
+        if !point.is_on_curve() {
+            return Err(Error::InvalidPublicKey.to_jsval());
+        }
+
+        Ok(point)
+    }
 }
 
 #[wasm_bindgen]
@@ -126,8 +181,8 @@ impl StarknetModule {
         let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
         let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
 
-        let felts = self.parse_felts(felts).map_err(|e| e.to_jsval())?;
-        let msg_hash = compute_hash_on_elements(&felts).map_err(|e| e.to_jsval())?;
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        let msg_hash = compute_hash_on_elements(&felts);
 
         let sig =
             starknet_sign(&parameters, private_key, msg_hash, None).map_err(|e| e.to_jsval())?;
@@ -147,8 +202,8 @@ impl StarknetModule {
         let parameters = parameters();
 
         let private_key: Fr = try_bytes_to_field(&private_key_bytes).map_err(|e| e.to_jsval())?;
-        let felts = self.parse_felts(felts).map_err(|e| e.to_jsval())?;
-        let msg_hash = compute_hash_on_elements(&felts).map_err(|e| e.to_jsval())?;
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        let msg_hash = compute_hash_on_elements(&felts);
 
         let sig =
             starknet_sign(&parameters, private_key, msg_hash, None).map_err(|e| e.to_jsval())?;
@@ -179,25 +234,1563 @@ impl StarknetModule {
 
     #[wasm_bindgen]
     pub fn hash_felts(&self, felts: js_sys::Array) -> Result<Vec<u8>, JsValue> {
-        let felts = self.parse_felts(felts).map_err(|e| e.to_jsval())?;
-        let msg_hash = compute_hash_on_elements(&felts).map_err(|e| e.to_jsval())?;
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        let msg_hash = compute_hash_on_elements(&felts);
 
         Ok(msg_hash.into_repr().to_bytes_le())
     }
 
-    /// felts are interpreted in le form since FromBytes expects LE representation
-    fn parse_felts(&self, felts: js_sys::Array) -> Result<Vec<Fq>, Error> {
-        let felts: Result<Vec<Uint8Array>, JsValue> = felts
-            .values()
+    /// bytes are expected to be in LE representation
+    #[wasm_bindgen]
+    pub fn pedersen_hash(&self, x: Vec<u8>, y: Vec<u8>) -> Result<Vec<u8>, JsValue> {
+        let x = try_bytes_to_field(&x).map_err(|e| e.to_jsval())?;
+        let y = try_bytes_to_field(&y).map_err(|e| e.to_jsval())?;
+
+        Ok(pedersen_hash(&x, &y).into_repr().to_bytes_le())
+    }
+
+    #[wasm_bindgen]
+    pub fn hash_felts_with_initial(
+        &self,
+        initial: Vec<u8>,
+        felts: js_sys::Array,
+    ) -> Result<Vec<u8>, JsValue> {
+        let initial = try_bytes_to_field(&initial).map_err(|e| e.to_jsval())?;
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        let hash = compute_hash_on_elements_with_initial(&initial, &felts);
+
+        Ok(hash.into_repr().to_bytes_le())
+    }
+
+    #[wasm_bindgen]
+    pub fn hash_felts_without_length(
+        &self,
+        initial: Vec<u8>,
+        felts: js_sys::Array,
+    ) -> Result<Vec<u8>, JsValue> {
+        let initial = try_bytes_to_field(&initial).map_err(|e| e.to_jsval())?;
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        let hash = compute_hash_on_elements_without_length(&initial, &felts);
+
+        Ok(hash.into_repr().to_bytes_le())
+    }
+
+    #[wasm_bindgen]
+    pub fn hash_chain_with_length_prefix(&self, felts: js_sys::Array) -> Result<Vec<u8>, JsValue> {
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        let hash = compute_hash_chain_with_length_prefix(&felts);
+
+        Ok(hash.into_repr().to_bytes_le())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_selector_from_name(&self, name: String) -> Result<Vec<u8>, JsValue> {
+        let selector = get_selector_from_name(&name).map_err(|e| e.to_jsval())?;
+
+        Ok(selector.into_repr().to_bytes_le())
+    }
+
+    /// keys are the felts of the mapping keys, empty for a plain storage variable
+    #[wasm_bindgen]
+    pub fn get_storage_var_address(
+        &self,
+        var_name: String,
+        keys: js_sys::Array,
+    ) -> Result<Vec<u8>, JsValue> {
+        let keys = parse_felts(keys).map_err(|e| e.to_jsval())?;
+        let address = get_storage_var_address(&var_name, &keys).map_err(|e| e.to_jsval())?;
+
+        Ok(address.into_repr().to_bytes_le())
+    }
+
+    /// Encrypts to the stark key given as LE bytes, see the ecies module for the wire format
+    #[wasm_bindgen]
+    pub fn encrypt(&self, stark_key: Vec<u8>, plaintext: Vec<u8>) -> Result<Vec<u8>, JsValue> {
+        let stark_key = try_bytes_to_field(&stark_key).map_err(|e| e.to_jsval())?;
+
+        ecies::encrypt(&parameters(), &stark_key, &plaintext, &mut OsRng).map_err(|e| e.to_jsval())
+    }
+
+    #[wasm_bindgen]
+    pub fn decrypt(&self, ciphertext: Vec<u8>) -> Result<Vec<u8>, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        ecies::decrypt(&private_key, &ciphertext).map_err(|e| e.to_jsval())
+    }
+
+    /// x coordinate of the ECDH shared point with the stark key, bytes are in LE representation
+    #[wasm_bindgen]
+    pub fn get_shared_secret(&self, stark_key: Vec<u8>) -> Result<Vec<u8>, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let stark_key: Fq = try_bytes_to_field(&stark_key).map_err(|e| e.to_jsval())?;
+        let public_key =
+            Affine::get_point_from_x(stark_key, false).ok_or(Error::InvalidPublicKey.to_jsval())?;
+        let shared_secret = ecies::ecdh(&private_key, &public_key).map_err(|e| e.to_jsval())?;
+
+        Ok(shared_secret.into_repr().to_bytes_le())
+    }
+
+    /// Splits the private key into n shares, any threshold of them recover it
+    #[wasm_bindgen]
+    pub fn split_sk(&self, threshold: u32, n: u32) -> Result<js_sys::Array, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let (shares, commitment) = shares::split(
+            &parameters(),
+            private_key,
+            threshold as u64,
+            n as u64,
+            &mut OsRng,
+        )
+        .map_err(|e| e.to_jsval())?;
+
+        Ok(shares
+            .iter()
+            .map(|share| JsValue::from(SecretShare::from_inner(share, &commitment)))
+            .collect())
+    }
+
+    /// Loads the private key recovered from the shares, values are bytes in LE representation.
+    /// The shares are checked against the commitment [x0, y0, x1, y1, ...] given with them by
+    /// split_sk, and the recovered key against its public key
+    #[wasm_bindgen]
+    pub fn recover_sk(
+        &mut self,
+        indices: Vec<u32>,
+        values: js_sys::Array,
+        commitment: js_sys::Array,
+    ) -> Result<(), JsValue> {
+        let values: Vec<Fr> = parse_felts(values).map_err(|e| e.to_jsval())?;
+        if indices.len() != values.len() {
+            return Err(Error::IncorrectLenError.to_jsval());
+        }
+        let commitment: Vec<Fq> = parse_felts(commitment).map_err(|e| e.to_jsval())?;
+        let commitment =
+            shares::FeldmanCommitment(parse_points(&commitment).map_err(|e| e.to_jsval())?);
+
+        let shares: Vec<shares::Share> = indices
+            .iter()
+            .zip(values.iter())
+            .map(|(index, value)| shares::Share {
+                index: *index as u64,
+                value: *value,
+            })
+            .collect();
+        let private_key = shares::reconstruct_verified(&parameters(), &shares, &commitment)
+            .map_err(|e| e.to_jsval())?;
+
+        self.private_key = Some(private_key.into_repr().to_bytes_le());
+        Ok(())
+    }
+
+    /// r is the x coordinate of the nonce point, it's an element of Fq unlike the ECDSA r
+    #[wasm_bindgen]
+    pub fn schnorr_sign(
+        &self,
+        felts: js_sys::Array,
+        hasher: HashFunction,
+    ) -> Result<Signature, JsValue> {
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        self.schnorr_sign_hashed(hasher.hash_many(&felts).into_repr().to_bytes_le(), hasher)
+    }
+
+    #[wasm_bindgen]
+    pub fn schnorr_sign_hashed(
+        &self,
+        msg_hash_bytes: Vec<u8>,
+        hasher: HashFunction,
+    ) -> Result<Signature, JsValue> {
+        let parameters = parameters();
+
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let msg_hash = try_bytes_to_field(&msg_hash_bytes).map_err(|e| e.to_jsval())?;
+
+        let sig =
+            schnorr::sign(&parameters, private_key, msg_hash, hasher).map_err(|e| e.to_jsval())?;
+
+        Ok(Signature::new(
+            sig.r.into_repr().to_bytes_le(),
+            sig.s.into_repr().to_bytes_le(),
+        ))
+    }
+
+    /// public_key is the stark key, the x coordinate of the public key
+    #[wasm_bindgen]
+    pub fn schnorr_verify(
+        &self,
+        public_key: Vec<u8>,
+        msg_hash: Vec<u8>,
+        signature: &Signature,
+        hasher: HashFunction,
+    ) -> Result<bool, JsValue> {
+        let public_key = try_bytes_to_field(&public_key).map_err(|e| e.to_jsval())?;
+        let msg_hash = try_bytes_to_field(&msg_hash).map_err(|e| e.to_jsval())?;
+        let signature = schnorr::SchnorrSignature {
+            r: try_bytes_to_field(&signature.r).map_err(|e| e.to_jsval())?,
+            s: try_bytes_to_field(&signature.s).map_err(|e| e.to_jsval())?,
+        };
+
+        Ok(schnorr::verify(
+            &parameters(),
+            &public_key,
+            &msg_hash,
+            &signature,
+            hasher,
+        ))
+    }
+
+    /// the i-th signature is (rs[i], ss[i]), all arrays must have the same length
+    #[wasm_bindgen]
+    pub fn schnorr_batch_verify(
+        &self,
+        public_keys: js_sys::Array,
+        msg_hashes: js_sys::Array,
+        rs: js_sys::Array,
+        ss: js_sys::Array,
+        hasher: HashFunction,
+    ) -> Result<bool, JsValue> {
+        let public_keys = parse_felts(public_keys).map_err(|e| e.to_jsval())?;
+        let msg_hashes = parse_felts(msg_hashes).map_err(|e| e.to_jsval())?;
+        let rs: Vec<Fq> = parse_felts(rs).map_err(|e| e.to_jsval())?;
+        let ss: Vec<Fr> = parse_felts(ss).map_err(|e| e.to_jsval())?;
+        if rs.len() != ss.len() {
+            return Err(Error::IncorrectLenError.to_jsval());
+        }
+
+        let signatures: Vec<schnorr::SchnorrSignature> = rs
             .into_iter()
-            .map(|felt| felt.unwrap_throw().dyn_into::<Uint8Array>())
+            .zip(ss)
+            .map(|(r, s)| schnorr::SchnorrSignature { r, s })
             .collect();
 
-        let felts: Vec<Vec<u8>> = felts?.iter().map(|x| x.to_vec()).collect();
+        schnorr::batch_verify(
+            &parameters(),
+            &public_keys,
+            &msg_hashes,
+            &signatures,
+            hasher,
+            &mut OsRng,
+        )
+        .map_err(|e| e.to_jsval())
+    }
+
+    /// Linkable ring signature, the public key of the loaded private key must be in the ring
+    #[wasm_bindgen]
+    pub fn ring_sign(&self, ring: &Ring, msg_hash: Vec<u8>) -> Result<RingSignature, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let msg_hash = try_bytes_to_field(&msg_hash).map_err(|e| e.to_jsval())?;
+
+        let signature = ring::sign(
+            &parameters(),
+            &ring.to_inner()?,
+            private_key,
+            msg_hash,
+            &mut OsRng,
+        )
+        .map_err(|e| e.to_jsval())?;
+
+        Ok(RingSignature::from_inner(&signature))
+    }
+
+    #[wasm_bindgen]
+    pub fn ring_verify(
+        &self,
+        ring: &Ring,
+        msg_hash: Vec<u8>,
+        signature: &RingSignature,
+    ) -> Result<bool, JsValue> {
+        let msg_hash = try_bytes_to_field(&msg_hash).map_err(|e| e.to_jsval())?;
+
+        Ok(ring::verify(
+            &parameters(),
+            &ring.to_inner()?,
+            &msg_hash,
+            &signature.to_inner()?,
+        ))
+    }
+
+    /// Both signatures were made with the same private key, e.g. a double vote
+    #[wasm_bindgen]
+    pub fn ring_link(
+        &self,
+        first: &RingSignature,
+        second: &RingSignature,
+    ) -> Result<bool, JsValue> {
+        Ok(first.to_inner()?.is_linked(&second.to_inner()?))
+    }
+
+    /// Proof of knowledge of the private key, bound to the context felts,
+    /// returns [challenge, response] as bytes in LE representation
+    #[wasm_bindgen]
+    pub fn prove_key_ownership(&self, context: js_sys::Array) -> Result<js_sys::Array, JsValue> {
+        let parameters = parameters();
+
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+        let public_key = private_key_to_public_key(&parameters, private_key).into_affine();
+
+        let context: Vec<Fq> = parse_felts(context).map_err(|e| e.to_jsval())?;
+        let proof = sigma::prove::<sigma::SchnorrProof, _>(
+            &parameters,
+            &public_key,
+            &private_key,
+            &context,
+            &mut OsRng,
+        );
+
+        Ok([proof.challenge, proof.response]
+            .iter()
+            .map(|value| JsValue::from(Uint8Array::from(&value.into_repr().to_bytes_le()[..])))
+            .collect())
+    }
+
+    #[wasm_bindgen]
+    pub fn verify_key_ownership(
+        &self,
+        public_key: &PublicKey,
+        context: js_sys::Array,
+        proof: js_sys::Array,
+    ) -> Result<bool, JsValue> {
+        let context: Vec<Fq> = parse_felts(context).map_err(|e| e.to_jsval())?;
+        let proof: Vec<Fr> = parse_felts(proof).map_err(|e| e.to_jsval())?;
+        if proof.len() != 2 {
+            return Err(Error::IncorrectLenError.to_jsval());
+        }
+
+        Ok(sigma::verify::<sigma::SchnorrProof>(
+            &parameters(),
+            &public_key.to_inner()?,
+            &context,
+            &sigma::Proof {
+                challenge: proof[0],
+                response: proof[1],
+            },
+        ))
+    }
+
+    /// The owner's signature of the session authorization
+    #[wasm_bindgen]
+    pub fn sign_session(
+        &self,
+        session: &SessionAuthorization,
+        chain_id: Vec<u8>,
+        account: Vec<u8>,
+    ) -> Result<Signature, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let chain_id = try_bytes_to_field(&chain_id).map_err(|e| e.to_jsval())?;
+        let account = try_bytes_to_field(&account).map_err(|e| e.to_jsval())?;
+
+        let sig = session::authorize(
+            &parameters(),
+            private_key,
+            &session.to_inner()?,
+            &chain_id,
+            &account,
+        )
+        .map_err(|e| e.to_jsval())?;
+
+        Ok(Signature::new(
+            sig.r.into_repr().to_bytes_le(),
+            sig.s.into_repr().to_bytes_le(),
+        ))
+    }
+
+    /// The session key's signature of a transaction, the loaded private key must be the session key
+    #[wasm_bindgen]
+    pub fn sign_session_transaction(
+        &self,
+        session: &SessionAuthorization,
+        chain_id: Vec<u8>,
+        account: Vec<u8>,
+        transaction_hash: Vec<u8>,
+        cache_authorization: bool,
+    ) -> Result<Signature, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let chain_id = try_bytes_to_field(&chain_id).map_err(|e| e.to_jsval())?;
+        let account = try_bytes_to_field(&account).map_err(|e| e.to_jsval())?;
+        let transaction_hash = try_bytes_to_field(&transaction_hash).map_err(|e| e.to_jsval())?;
 
-        felts
+        let sig = session::sign_transaction(
+            &parameters(),
+            private_key,
+            &session.to_inner()?,
+            &chain_id,
+            &account,
+            &transaction_hash,
+            cache_authorization,
+        )
+        .map_err(|e| e.to_jsval())?;
+
+        Ok(Signature::new(
+            sig.r.into_repr().to_bytes_le(),
+            sig.s.into_repr().to_bytes_le(),
+        ))
+    }
+
+    /// SNIP-9 outside execution signed by the account owner, version is 1 or 2 and the caller
+    /// can be the short string ANY_CALLER. Returns the signature [r, s] that the account expects,
+    /// as bytes in LE representation
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn sign_outside_execution(
+        &self,
+        version: u8,
+        chain_id: Vec<u8>,
+        account: Vec<u8>,
+        caller: Vec<u8>,
+        nonce: Vec<u8>,
+        execute_after: u64,
+        execute_before: u64,
+        calls: &Calls,
+    ) -> Result<js_sys::Array, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let version = match version {
+            1 => outside_execution::Version::V1,
+            2 => outside_execution::Version::V2,
+            _ => return Err(Error::TypeError.to_jsval()),
+        };
+        let chain_id = try_bytes_to_field(&chain_id).map_err(|e| e.to_jsval())?;
+        let account = try_bytes_to_field(&account).map_err(|e| e.to_jsval())?;
+
+        let outside_execution = outside_execution::OutsideExecution {
+            caller: try_bytes_to_field(&caller).map_err(|e| e.to_jsval())?,
+            nonce: try_bytes_to_field(&nonce).map_err(|e| e.to_jsval())?,
+            execute_after,
+            execute_before,
+            calls: calls.calls.clone(),
+        };
+
+        let sig = outside_execution::sign(
+            &parameters(),
+            private_key,
+            &outside_execution,
+            version,
+            &chain_id,
+            &account,
+        )
+        .map_err(|e| e.to_jsval())?;
+
+        Ok([sig.r, sig.s]
             .iter()
-            .map(|felt_bytes| -> Result<Fq, Error> { try_bytes_to_field(felt_bytes) })
+            .map(|value| JsValue::from(Uint8Array::from(&value.into_repr().to_bytes_le()[..])))
+            .collect())
+    }
+
+    /// Builds the __execute__ calldata of the calls for a cairo_version 0 or 1 account,
+    /// hashes the invoke v1 transaction and signs it
+    #[wasm_bindgen]
+    pub fn sign_invoke_v1(
+        &self,
+        calls: &Calls,
+        cairo_version: u8,
+        sender_address: Vec<u8>,
+        chain_id: Vec<u8>,
+        nonce: Vec<u8>,
+        max_fee: Vec<u8>,
+    ) -> Result<SignedInvoke, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let calldata = calls::execute_calldata(&calls.calls, parse_cairo_version(cairo_version)?);
+        let transaction = calls::InvokeV1 {
+            sender_address: try_bytes_to_field(&sender_address).map_err(|e| e.to_jsval())?,
+            calldata,
+            max_fee: try_bytes_to_field(&max_fee).map_err(|e| e.to_jsval())?,
+            chain_id: try_bytes_to_field(&chain_id).map_err(|e| e.to_jsval())?,
+            nonce: try_bytes_to_field(&nonce).map_err(|e| e.to_jsval())?,
+        };
+
+        SignedInvoke::new(
+            &parameters(),
+            private_key,
+            &transaction.calldata,
+            transaction.hash(),
+        )
+    }
+
+    /// Builds the __execute__ calldata of the calls for a Cairo 1 account, hashes the invoke v3
+    /// transaction and signs it. Data availability is on L1, without paymaster nor deployment data
+    #[wasm_bindgen]
+    pub fn sign_invoke_v3(
+        &self,
+        calls: &Calls,
+        sender_address: Vec<u8>,
+        chain_id: Vec<u8>,
+        nonce: Vec<u8>,
+        resource_bounds: &ResourceBounds,
+        tip: u64,
+    ) -> Result<SignedInvoke, JsValue> {
+        let pk_bytes = self.private_key.clone().ok_or("No private key provided")?;
+        let private_key = Fr::from_le_bytes_mod_order(pk_bytes.as_slice());
+
+        let calldata = calls::execute_calldata(&calls.calls, calls::CairoVersion::Cairo1);
+        let transaction = calls::InvokeV3 {
+            sender_address: try_bytes_to_field(&sender_address).map_err(|e| e.to_jsval())?,
+            calldata,
+            chain_id: try_bytes_to_field(&chain_id).map_err(|e| e.to_jsval())?,
+            nonce: try_bytes_to_field(&nonce).map_err(|e| e.to_jsval())?,
+            tip,
+            resource_bounds: resource_bounds.bounds,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: calls::DataAvailabilityMode::L1,
+            fee_data_availability_mode: calls::DataAvailabilityMode::L1,
+        };
+
+        SignedInvoke::new(
+            &parameters(),
+            private_key,
+            &transaction.calldata,
+            transaction.hash(),
+        )
+    }
+}
+
+#[wasm_bindgen(js_name = PedersenHashState)]
+pub struct HashState {
+    state: pedersen::PedersenHashState,
+}
+
+impl Default for HashState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen(js_class = PedersenHashState)]
+impl HashState {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            state: pedersen::PedersenHashState::new(),
+        }
+    }
+
+    /// bytes are expected to be in LE representation
+    pub fn update(&mut self, felt: Vec<u8>) -> Result<(), JsValue> {
+        let felt = try_bytes_to_field(&felt).map_err(|e| e.to_jsval())?;
+        self.state.update(&felt);
+
+        Ok(())
+    }
+
+    pub fn update_many(&mut self, felts: js_sys::Array) -> Result<(), JsValue> {
+        let felts = parse_felts(felts).map_err(|e| e.to_jsval())?;
+        self.state.update_many(&felts);
+
+        Ok(())
+    }
+
+    pub fn finalize(&self) -> Vec<u8> {
+        self.state.finalize().into_repr().to_bytes_le()
+    }
+}
+
+#[wasm_bindgen(js_name = MerkleTree)]
+pub struct SortedMerkleTree {
+    tree: merkle::MerkleTree,
+}
+
+#[wasm_bindgen(js_class = MerkleTree)]
+impl SortedMerkleTree {
+    #[wasm_bindgen(constructor)]
+    pub fn new(leaves: js_sys::Array, hasher: HashFunction) -> Result<SortedMerkleTree, JsValue> {
+        let leaves = parse_felts(leaves).map_err(|e| e.to_jsval())?;
+        let tree = merkle::MerkleTree::new(&leaves, hasher).map_err(|e| e.to_jsval())?;
+
+        Ok(Self { tree })
+    }
+
+    pub fn root(&self) -> Vec<u8> {
+        self.tree.root().into_repr().to_bytes_le()
+    }
+
+    /// returns the sibling nodes from the leaf to the root
+    pub fn prove(&self, leaf: Vec<u8>) -> Result<js_sys::Array, JsValue> {
+        let leaf = try_bytes_to_field(&leaf).map_err(|e| e.to_jsval())?;
+        let proof = self.tree.prove(&leaf).ok_or("Leaf is not in the tree")?;
+
+        Ok(proof
+            .iter()
+            .map(|node| JsValue::from(Uint8Array::from(&node.into_repr().to_bytes_le()[..])))
+            .collect())
+    }
+
+    pub fn verify(
+        root: Vec<u8>,
+        leaf: Vec<u8>,
+        proof: js_sys::Array,
+        hasher: HashFunction,
+    ) -> Result<bool, JsValue> {
+        let root = try_bytes_to_field(&root).map_err(|e| e.to_jsval())?;
+        let leaf = try_bytes_to_field(&leaf).map_err(|e| e.to_jsval())?;
+        let proof = parse_felts(proof).map_err(|e| e.to_jsval())?;
+
+        Ok(verify_merkle_proof(&root, &leaf, &proof, hasher))
+    }
+}
+
+#[wasm_bindgen(js_name = Commitment)]
+pub struct PedersenCommitment {
+    // store bytes in LE endianness as a convention
+    x: Vec<u8>,
+    y: Vec<u8>,
+}
+
+#[wasm_bindgen(js_class = Commitment)]
+impl PedersenCommitment {
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: Vec<u8>, y: Vec<u8>) -> Self {
+        Self { x, y }
+    }
+
+    pub fn get_x(&self) -> Uint8Array {
+        Uint8Array::from(&self.x[..])
+    }
+
+    pub fn get_y(&self) -> Uint8Array {
+        Uint8Array::from(&self.y[..])
+    }
+
+    pub fn add(&self, other: &PedersenCommitment) -> Result<PedersenCommitment, JsValue> {
+        Ok(Self::from_inner(self.to_inner()? + other.to_inner()?))
+    }
+
+    pub fn sub(&self, other: &PedersenCommitment) -> Result<PedersenCommitment, JsValue> {
+        Ok(Self::from_inner(self.to_inner()? - other.to_inner()?))
+    }
+
+    pub fn verify(&self, opening: &CommitmentOpening) -> Result<bool, JsValue> {
+        Ok(commitment::verify(
+            &commitment::parameters(),
+            &self.to_inner()?,
+            &opening.to_inner()?,
+        ))
+    }
+
+    fn from_inner(commitment: commitment::Commitment) -> Self {
+        Self::new(
+            commitment.0.x.into_repr().to_bytes_le(),
+            commitment.0.y.into_repr().to_bytes_le(),
+        )
+    }
+
+    fn to_inner(&self) -> Result<commitment::Commitment, JsValue> {
+        let x = try_bytes_to_field(&self.x).map_err(|e| e.to_jsval())?;
+        let y = try_bytes_to_field(&self.y).map_err(|e| e.to_jsval())?;
+
+        let point = Affine::new(x, y, false);
+        if !point.is_on_curve() {
+            return Err(Error::InvalidPublicKey.to_jsval());
+        }
+
+        Ok(commitment::Commitment(point))
+    }
+}
+
+#[wasm_bindgen(js_name = Opening)]
+pub struct CommitmentOpening {
+    // store bytes in LE endianness as a convention
+    message: Vec<u8>,
+    blinding: Vec<u8>,
+}
+
+#[wasm_bindgen(js_class = Opening)]
+impl CommitmentOpening {
+    #[wasm_bindgen(constructor)]
+    pub fn new(message: Vec<u8>, blinding: Vec<u8>) -> Self {
+        Self { message, blinding }
+    }
+
+    /// Opening of the message with a random blinding factor
+    pub fn random(message: Vec<u8>) -> Result<CommitmentOpening, JsValue> {
+        let message = try_bytes_to_field(&message).map_err(|e| e.to_jsval())?;
+        Ok(Self::from_inner(commitment::Opening::random(
+            message, &mut OsRng,
+        )))
+    }
+
+    pub fn get_message(&self) -> Uint8Array {
+        Uint8Array::from(&self.message[..])
+    }
+
+    pub fn get_blinding(&self) -> Uint8Array {
+        Uint8Array::from(&self.blinding[..])
+    }
+
+    pub fn commit(&self) -> Result<PedersenCommitment, JsValue> {
+        let commitment = self.to_inner()?.commit(&commitment::parameters());
+        Ok(PedersenCommitment::from_inner(commitment))
+    }
+
+    pub fn add(&self, other: &CommitmentOpening) -> Result<CommitmentOpening, JsValue> {
+        Ok(Self::from_inner(self.to_inner()? + other.to_inner()?))
+    }
+
+    pub fn sub(&self, other: &CommitmentOpening) -> Result<CommitmentOpening, JsValue> {
+        Ok(Self::from_inner(self.to_inner()? - other.to_inner()?))
+    }
+
+    fn from_inner(opening: commitment::Opening) -> Self {
+        Self::new(
+            opening.message.into_repr().to_bytes_le(),
+            opening.blinding.into_repr().to_bytes_le(),
+        )
+    }
+
+    fn to_inner(&self) -> Result<commitment::Opening, JsValue> {
+        let message = try_bytes_to_field(&self.message).map_err(|e| e.to_jsval())?;
+        let blinding = try_bytes_to_field(&self.blinding).map_err(|e| e.to_jsval())?;
+
+        Ok(commitment::Opening::new(message, blinding))
+    }
+}
+
+/// Share of a private key, with the Feldman commitment that it's checked against
+#[wasm_bindgen]
+pub struct SecretShare {
+    index: u32,
+    // store bytes in LE endianness as a convention
+    value: Vec<u8>,
+    // x and y of every point of the commitment
+    commitment: Vec<Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl SecretShare {
+    /// commitment is the array [x0, y0, x1, y1, ...] of the points of the commitment
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        index: u32,
+        value: Vec<u8>,
+        commitment: js_sys::Array,
+    ) -> Result<SecretShare, JsValue> {
+        let commitment: Vec<Fq> = parse_felts(commitment).map_err(|e| e.to_jsval())?;
+        if commitment.is_empty() || !commitment.len().is_multiple_of(2) {
+            return Err(Error::IncorrectLenError.to_jsval());
+        }
+
+        Ok(Self {
+            index,
+            value,
+            commitment: commitment
+                .iter()
+                .map(|coordinate| coordinate.into_repr().to_bytes_le())
+                .collect(),
+        })
+    }
+
+    pub fn get_index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn get_value(&self) -> Uint8Array {
+        Uint8Array::from(&self.value[..])
+    }
+
+    pub fn get_commitment(&self) -> js_sys::Array {
+        self.commitment
+            .iter()
+            .map(|coordinate| JsValue::from(Uint8Array::from(&coordinate[..])))
+            .collect()
+    }
+
+    /// Public key of the shared private key, it must be the one of the account being recovered
+    pub fn get_public_key(&self) -> PublicKey {
+        PublicKey::new(self.commitment[0].clone(), self.commitment[1].clone())
+    }
+
+    pub fn verify(&self) -> Result<bool, JsValue> {
+        let share = shares::Share {
+            index: self.index as u64,
+            value: try_bytes_to_field(&self.value).map_err(|e| e.to_jsval())?,
+        };
+
+        let coordinates = self
+            .commitment
+            .iter()
+            .map(try_bytes_to_field)
+            .collect::<Result<Vec<Fq>, _>>()
+            .map_err(|e| e.to_jsval())?;
+        let points = parse_points(&coordinates).map_err(|e| e.to_jsval())?;
+
+        Ok(shares::FeldmanCommitment(points).verify_share(&parameters(), &share))
+    }
+
+    fn from_inner(share: &shares::Share, commitment: &shares::FeldmanCommitment) -> Self {
+        Self {
+            index: share.index as u32,
+            value: share.value.into_repr().to_bytes_le(),
+            commitment: commitment
+                .0
+                .iter()
+                .flat_map(|point| {
+                    [
+                        point.x.into_repr().to_bytes_le(),
+                        point.y.into_repr().to_bytes_le(),
+                    ]
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Public keys of a ring signature, the order matters
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Ring {
+    public_keys: Vec<PublicKey>,
+}
+
+#[wasm_bindgen]
+impl Ring {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            public_keys: vec![],
+        }
+    }
+
+    pub fn add(&mut self, public_key: &PublicKey) {
+        self.public_keys
+            .push(PublicKey::new(public_key.x.clone(), public_key.y.clone()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.public_keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.public_keys.is_empty()
+    }
+
+    fn to_inner(&self) -> Result<Vec<Affine>, JsValue> {
+        self.public_keys
+            .iter()
+            .map(|public_key| public_key.to_inner())
+            .collect()
+    }
+}
+
+#[wasm_bindgen]
+pub struct RingSignature {
+    // store bytes in LE endianness as a convention
+    c: Vec<u8>,
+    s: Vec<Vec<u8>>,
+    key_image: PublicKey,
+}
+
+#[wasm_bindgen]
+impl RingSignature {
+    /// s has one element for every public key of the ring
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        c: Vec<u8>,
+        s: js_sys::Array,
+        key_image_x: Vec<u8>,
+        key_image_y: Vec<u8>,
+    ) -> Result<RingSignature, JsValue> {
+        let s: Vec<Fr> = parse_felts(s).map_err(|e| e.to_jsval())?;
+
+        Ok(Self {
+            c,
+            s: s.iter().map(|s| s.into_repr().to_bytes_le()).collect(),
+            key_image: PublicKey::new(key_image_x, key_image_y),
+        })
+    }
+
+    pub fn get_c(&self) -> Uint8Array {
+        Uint8Array::from(&self.c[..])
+    }
+
+    pub fn get_s(&self) -> js_sys::Array {
+        self.s
+            .iter()
+            .map(|s| JsValue::from(Uint8Array::from(&s[..])))
+            .collect()
+    }
+
+    /// The key image is the same for all signatures of a private key
+    pub fn get_key_image_x(&self) -> Uint8Array {
+        self.key_image.get_x()
+    }
+
+    pub fn get_key_image_y(&self) -> Uint8Array {
+        self.key_image.get_y()
+    }
+
+    fn from_inner(signature: &ring::RingSignature) -> Self {
+        Self {
+            c: signature.c.into_repr().to_bytes_le(),
+            s: signature
+                .s
+                .iter()
+                .map(|s| s.into_repr().to_bytes_le())
+                .collect(),
+            key_image: PublicKey::from_inner(&signature.key_image),
+        }
+    }
+
+    fn to_inner(&self) -> Result<ring::RingSignature, JsValue> {
+        Ok(ring::RingSignature {
+            c: try_bytes_to_field(&self.c).map_err(|e| e.to_jsval())?,
+            s: self
+                .s
+                .iter()
+                .map(try_bytes_to_field)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_jsval())?,
+            key_image: self.key_image.to_inner()?,
+        })
+    }
+}
+
+/// Session authorized by the owner, the session key is the public key of another
+/// StarknetModule with a fresh private key from new_sk
+#[wasm_bindgen]
+pub struct SessionAuthorization {
+    // store bytes in LE endianness as a convention
+    expires_at: u64,
+    allowed_methods: Vec<(Vec<u8>, Vec<u8>)>,
+    project_id: String,
+    spending_limits: Vec<(Vec<u8>, Vec<u8>)>,
+    session_key: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl SessionAuthorization {
+    /// session_key is the x coordinate of the session public key
+    #[wasm_bindgen(constructor)]
+    pub fn new(expires_at: u64, session_key: Vec<u8>, project_id: String) -> Self {
+        Self {
+            expires_at,
+            allowed_methods: vec![],
+            project_id,
+            spending_limits: vec![],
+            session_key,
+        }
+    }
+
+    pub fn allow_method(
+        &mut self,
+        contract_address: Vec<u8>,
+        entry_point: &str,
+    ) -> Result<(), JsValue> {
+        let selector = get_selector_from_name(entry_point).map_err(|e| e.to_jsval())?;
+        self.allowed_methods
+            .push((contract_address, selector.into_repr().to_bytes_le()));
+
+        Ok(())
+    }
+
+    pub fn add_spending_limit(&mut self, token_address: Vec<u8>, max_amount: Vec<u8>) {
+        self.spending_limits.push((token_address, max_amount));
+    }
+
+    pub fn get_metadata(&self) -> Result<String, JsValue> {
+        Ok(self.to_inner()?.metadata())
+    }
+
+    /// SNIP-12 hash of the session that the owner signs, bytes are in LE representation
+    pub fn message_hash(&self, chain_id: Vec<u8>, account: Vec<u8>) -> Result<Vec<u8>, JsValue> {
+        let chain_id = try_bytes_to_field(&chain_id).map_err(|e| e.to_jsval())?;
+        let account = try_bytes_to_field(&account).map_err(|e| e.to_jsval())?;
+
+        let msg_hash = self
+            .to_inner()?
+            .message_hash(&chain_id, &account)
+            .map_err(|e| e.to_jsval())?;
+
+        Ok(msg_hash.into_repr().to_bytes_le())
+    }
+
+    fn to_inner(&self) -> Result<session::Session, JsValue> {
+        let allowed_methods = self
+            .allowed_methods
+            .iter()
+            .map(|(contract_address, selector)| -> Result<_, Error> {
+                Ok(session::AllowedMethod {
+                    contract_address: try_bytes_to_field(contract_address)?,
+                    selector: try_bytes_to_field(selector)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_jsval())?;
+
+        let spending_limits = self
+            .spending_limits
+            .iter()
+            .map(|(token_address, max_amount)| -> Result<_, Error> {
+                Ok(session::SpendingLimit {
+                    token_address: try_bytes_to_field(token_address)?,
+                    max_amount: try_bytes_to_field(max_amount)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_jsval())?;
+
+        Ok(session::Session {
+            expires_at: self.expires_at,
+            allowed_methods,
+            project_id: self.project_id.clone(),
+            spending_limits,
+            session_key: try_bytes_to_field(&self.session_key).map_err(|e| e.to_jsval())?,
+        })
+    }
+}
+
+/// Contract calls of a multicall or of an outside execution
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Calls {
+    calls: Vec<calls::Call>,
+}
+
+#[wasm_bindgen]
+impl Calls {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self { calls: vec![] }
+    }
+
+    /// bytes are expected to be in LE representation
+    pub fn add(
+        &mut self,
+        to: Vec<u8>,
+        selector: Vec<u8>,
+        calldata: js_sys::Array,
+    ) -> Result<(), JsValue> {
+        self.calls.push(calls::Call {
+            to: try_bytes_to_field(&to).map_err(|e| e.to_jsval())?,
+            selector: try_bytes_to_field(&selector).map_err(|e| e.to_jsval())?,
+            calldata: parse_felts(calldata).map_err(|e| e.to_jsval())?,
+        });
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Calldata of the account's __execute__ for a cairo_version 0 or 1 account
+    pub fn execute_calldata(&self, cairo_version: u8) -> Result<js_sys::Array, JsValue> {
+        let calldata = calls::execute_calldata(&self.calls, parse_cairo_version(cairo_version)?);
+        Ok(felts_to_array(&calldata))
+    }
+}
+
+/// Resource bounds of an invoke v3 transaction, max amounts and max prices per unit
+#[wasm_bindgen]
+pub struct ResourceBounds {
+    bounds: calls::ResourceBounds,
+}
+
+#[wasm_bindgen]
+impl ResourceBounds {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        l1_gas_max_amount: u64,
+        l1_gas_max_price: u64,
+        l2_gas_max_amount: u64,
+        l2_gas_max_price: u64,
+        l1_data_gas_max_amount: u64,
+        l1_data_gas_max_price: u64,
+    ) -> Self {
+        let bound = |max_amount, max_price: u64| calls::ResourceBound {
+            max_amount,
+            max_price_per_unit: max_price as u128,
+        };
+
+        Self {
+            bounds: calls::ResourceBounds {
+                l1_gas: bound(l1_gas_max_amount, l1_gas_max_price),
+                l2_gas: bound(l2_gas_max_amount, l2_gas_max_price),
+                l1_data_gas: bound(l1_data_gas_max_amount, l1_data_gas_max_price),
+            },
+        }
+    }
+}
+
+/// Invoke transaction signed by the account owner
+#[wasm_bindgen]
+pub struct SignedInvoke {
+    // store bytes in LE endianness as a convention
+    calldata: Vec<Vec<u8>>,
+    transaction_hash: Vec<u8>,
+    signature: Signature,
+}
+
+#[wasm_bindgen]
+impl SignedInvoke {
+    pub fn get_calldata(&self) -> js_sys::Array {
+        self.calldata
+            .iter()
+            .map(|felt| JsValue::from(Uint8Array::from(&felt[..])))
+            .collect()
+    }
+
+    pub fn get_transaction_hash(&self) -> Uint8Array {
+        Uint8Array::from(&self.transaction_hash[..])
+    }
+
+    /// The transaction signature [r, s]
+    pub fn get_signature(&self) -> js_sys::Array {
+        [self.signature.get_r(), self.signature.get_s()]
+            .iter()
+            .map(JsValue::from)
+            .collect()
+    }
+
+    fn new(
+        parameters: &SigningParameters,
+        private_key: Fr,
+        calldata: &[Fq],
+        transaction_hash: Fq,
+    ) -> Result<Self, JsValue> {
+        let sig = starknet_sign(parameters, private_key, transaction_hash, None)
+            .map_err(|e| e.to_jsval())?;
+
+        Ok(Self {
+            calldata: calldata
+                .iter()
+                .map(|felt| felt.into_repr().to_bytes_le())
+                .collect(),
+            transaction_hash: transaction_hash.into_repr().to_bytes_le(),
+            signature: Signature::new(
+                sig.r.into_repr().to_bytes_le(),
+                sig.s.into_repr().to_bytes_le(),
+            ),
+        })
+    }
+}
+
+fn parse_cairo_version(cairo_version: u8) -> Result<calls::CairoVersion, JsValue> {
+    match cairo_version {
+        0 => Ok(calls::CairoVersion::Cairo0),
+        1 => Ok(calls::CairoVersion::Cairo1),
+        _ => Err(Error::TypeError.to_jsval()),
+    }
+}
+
+fn felts_to_array(felts: &[Fq]) -> js_sys::Array {
+    felts
+        .iter()
+        .map(|felt| JsValue::from(Uint8Array::from(&felt.into_repr().to_bytes_le()[..])))
+        .collect()
+}
+
+/// Cairo 1 contract ABI, to encode JS values as calldata felts and to decode felts.
+/// Felts and bytes31 are Uint8Array in LE representation, integers can also be a number up to
+/// Number.MAX_SAFE_INTEGER, a BigInt or a decimal or 0x prefixed hex string, negative for signed
+/// integers, ByteArray is a string, Option is null for None, tuples are arrays, structs are
+/// objects with the member names and enums are objects with a single key, the variant name
+#[wasm_bindgen]
+pub struct CairoAbi {
+    abi: cairo_serde::Abi,
+}
+
+#[wasm_bindgen]
+impl CairoAbi {
+    /// abi is the parsed JSON ABI of the contract
+    #[wasm_bindgen(constructor)]
+    pub fn new(abi: js_sys::Array) -> Result<CairoAbi, JsValue> {
+        let entries = js_to_abi_entries(&abi).map_err(|e| e.to_jsval())?;
+        let mut cairo_abi = cairo_serde::Abi::new();
+        cairo_abi.add_entries(&entries).map_err(|e| e.to_jsval())?;
+
+        Ok(CairoAbi { abi: cairo_abi })
+    }
+
+    pub fn encode(&self, type_name: &str, value: JsValue) -> Result<js_sys::Array, JsValue> {
+        let ty = cairo_serde::Type::parse(type_name).map_err(|e| e.to_jsval())?;
+
+        let mut output = vec![];
+        let value = js_to_value(&self.abi, &ty, &value).map_err(|e| e.to_jsval())?;
+        self.abi
+            .serialize(&ty, &value, &mut output)
+            .map_err(|e| e.to_jsval())?;
+
+        Ok(felts_to_array(&output))
+    }
+
+    /// args is an object with the names of the function's inputs
+    pub fn encode_calldata(&self, function: &str, args: JsValue) -> Result<js_sys::Array, JsValue> {
+        let inputs = self
+            .abi
+            .function_inputs(function)
+            .ok_or_else(|| Error::TypeError.to_jsval())?;
+
+        let args = inputs
+            .iter()
+            .map(|(name, ty)| js_to_value(&self.abi, ty, &js_get(&args, name)?))
             .collect::<Result<Vec<_>, Error>>()
+            .map_err(|e| e.to_jsval())?;
+        let output = self
+            .abi
+            .serialize_calldata(function, &args)
+            .map_err(|e| e.to_jsval())?;
+
+        Ok(felts_to_array(&output))
+    }
+
+    /// felts must be exactly one value of the type
+    pub fn decode(&self, type_name: &str, felts: js_sys::Array) -> Result<JsValue, JsValue> {
+        let ty = cairo_serde::Type::parse(type_name).map_err(|e| e.to_jsval())?;
+        let felts: Vec<Fq> = parse_felts(felts).map_err(|e| e.to_jsval())?;
+
+        let mut offset = 0;
+        let value = self
+            .abi
+            .deserialize(&ty, &felts, &mut offset)
+            .map_err(|e| e.to_jsval())?;
+        if offset != felts.len() {
+            return Err(Error::IncorrectLenError.to_jsval());
+        }
+
+        value_to_js(&self.abi, &ty, &value).map_err(|e| e.to_jsval())
+    }
+}
+
+/// Entries of the parsed JSON ABI, they are added to the ABI by cairo_serde::Abi::add_entries
+fn js_to_abi_entries(entries: &js_sys::Array) -> Result<Vec<cairo_serde::AbiEntry>, Error> {
+    use cairo_serde::AbiEntry;
+
+    entries
+        .iter()
+        .map(|entry| {
+            let kind = js_get(&entry, "type")?
+                .as_string()
+                .ok_or(Error::TypeError)?;
+            let name = js_get(&entry, "name")?.as_string().unwrap_or_default();
+
+            let entry = match kind.as_str() {
+                "struct" => AbiEntry::Struct {
+                    name,
+                    members: js_to_named_types(&js_get(&entry, "members")?)?,
+                },
+                "enum" => AbiEntry::Enum {
+                    name,
+                    variants: js_to_named_types(&js_get(&entry, "variants")?)?,
+                },
+                "function" | "l1_handler" | "constructor" => AbiEntry::Function {
+                    name,
+                    inputs: js_to_named_types(&js_get(&entry, "inputs")?)?,
+                },
+                "interface" => AbiEntry::Interface {
+                    items: js_to_abi_entries(&js_get(&entry, "items")?.dyn_into()?)?,
+                },
+                _ => AbiEntry::Other,
+            };
+
+            Ok(entry)
+        })
+        .collect()
+}
+
+fn js_get(object: &JsValue, key: &str) -> Result<JsValue, Error> {
+    Ok(js_sys::Reflect::get(object, &JsValue::from_str(key))?)
+}
+
+/// [{ name, type }] of members, variants and inputs
+fn js_to_named_types(entries: &JsValue) -> Result<Vec<(String, String)>, Error> {
+    entries
+        .clone()
+        .dyn_into::<js_sys::Array>()?
+        .iter()
+        .map(|entry| {
+            let name = js_get(&entry, "name")?
+                .as_string()
+                .ok_or(Error::TypeError)?;
+            let ty = js_get(&entry, "type")?
+                .as_string()
+                .ok_or(Error::TypeError)?;
+
+            Ok((name, ty))
+        })
+        .collect()
+}
+
+/// Number, BigInt or decimal or 0x prefixed hex string, as a sign and a magnitude
+fn js_to_signed(value: &JsValue) -> Result<(bool, BigUint), Error> {
+    let string = if let Some(bigint) = value.dyn_ref::<js_sys::BigInt>() {
+        String::from(bigint.to_string(10).map_err(|_| Error::TypeError)?)
+    } else if let Some(number) = value.as_f64() {
+        if number.fract() != 0.0 || number.abs() > MAX_SAFE_INTEGER {
+            return Err(Error::TypeError);
+        }
+        (number as i64).to_string()
+    } else {
+        value.as_string().ok_or(Error::TypeError)?
+    };
+
+    let (negative, magnitude) = match string.strip_prefix('-') {
+        Some(magnitude) => (true, magnitude),
+        None => (false, string.as_str()),
+    };
+    let parsed = match magnitude.strip_prefix("0x") {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(magnitude.as_bytes(), 10),
+    };
+
+    Ok((negative, parsed.ok_or(Error::TypeError)?))
+}
+
+/// Uint8Array in LE representation, number, BigInt or decimal or 0x prefixed hex string
+fn js_to_biguint(value: &JsValue) -> Result<BigUint, Error> {
+    if let Some(bytes) = value.dyn_ref::<Uint8Array>() {
+        return Ok(BigUint::from_bytes_le(&bytes.to_vec()));
     }
+
+    match js_to_signed(value)? {
+        (false, value) => Ok(value),
+        (true, _) => Err(Error::TypeError),
+    }
+}
+
+fn js_to_i128(value: &JsValue) -> Result<i128, Error> {
+    let (negative, magnitude) = js_to_signed(value)?;
+    let magnitude = biguint_to_u128(&magnitude)?;
+
+    if !negative {
+        return i128::try_from(magnitude).map_err(|_| Error::OverflowError);
+    }
+    if magnitude > 1 << 127 {
+        return Err(Error::OverflowError);
+    }
+
+    // -2^127 is i128::MIN
+    Ok((magnitude as i128).wrapping_neg())
+}
+
+fn biguint_to_u128(value: &BigUint) -> Result<u128, Error> {
+    let bytes = value.to_bytes_le();
+    if bytes.len() > 16 {
+        return Err(Error::OverflowError);
+    }
+
+    let mut padded = [0u8; 16];
+    padded[..bytes.len()].copy_from_slice(&bytes);
+    Ok(u128::from_le_bytes(padded))
+}
+
+fn js_to_value(
+    abi: &cairo_serde::Abi,
+    ty: &cairo_serde::Type,
+    value: &JsValue,
+) -> Result<cairo_serde::Value, Error> {
+    use cairo_serde::{Type, Value};
+
+    let items = |value: &JsValue| -> Result<Vec<JsValue>, Error> {
+        Ok(value.clone().dyn_into::<js_sys::Array>()?.iter().collect())
+    };
+
+    let value = match ty {
+        Type::Felt | Type::Bytes31 => Value::Felt(cairo_serde::felt_from_be_bytes(
+            ty,
+            &js_to_biguint(value)?.to_bytes_be(),
+        )?),
+        Type::Bool => Value::Bool(value.as_bool().ok_or(Error::TypeError)?),
+        Type::Uint(_) => Value::Uint(biguint_to_u128(&js_to_biguint(value)?)?),
+        Type::Int(_) => Value::Int(js_to_i128(value)?),
+        Type::U256 => {
+            let value = js_to_biguint(value)?;
+            let high = value.clone() >> 128;
+            if high.bits() > 128 {
+                return Err(Error::OverflowError);
+            }
+
+            Value::U256(cairo_serde::U256 {
+                low: biguint_to_u128(&(value - (high.clone() << 128)))?,
+                high: biguint_to_u128(&high)?,
+            })
+        }
+        Type::ByteArray => Value::ByteArray(value.as_string().ok_or(Error::TypeError)?),
+        Type::Array(ty) => Value::Array(
+            items(value)?
+                .iter()
+                .map(|item| js_to_value(abi, ty, item))
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Type::Option(ty) => {
+            if value.is_null() || value.is_undefined() {
+                Value::Option(None)
+            } else {
+                Value::Option(Some(Box::new(js_to_value(abi, ty, value)?)))
+            }
+        }
+        // the unit type doesn't need a value
+        Type::Tuple(types) if types.is_empty() => Value::Tuple(vec![]),
+        Type::Tuple(types) => {
+            let items = items(value)?;
+            if items.len() != types.len() {
+                return Err(Error::IncorrectLenError);
+            }
+
+            Value::Tuple(
+                types
+                    .iter()
+                    .zip(items.iter())
+                    .map(|(ty, item)| js_to_value(abi, ty, item))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        }
+        Type::Named(name) => {
+            if let Some(members) = abi.struct_members(name) {
+                Value::Struct(
+                    members
+                        .iter()
+                        .map(|(name, ty)| js_to_value(abi, ty, &js_get(value, name)?))
+                        .collect::<Result<Vec<_>, _>>()?,
+                )
+            } else {
+                let variants = abi.enum_variants(name).ok_or(Error::TypeError)?;
+                let object = value.clone().dyn_into::<js_sys::Object>()?;
+                let keys = js_sys::Object::keys(&object);
+                if keys.length() != 1 {
+                    return Err(Error::TypeError);
+                }
+
+                let variant = keys.get(0).as_string().ok_or(Error::TypeError)?;
+                let (_, ty) = variants
+                    .iter()
+                    .find(|(name, _)| *name == variant)
+                    .ok_or(Error::TypeError)?;
+
+                Value::Enum(
+                    variant.clone(),
+                    Box::new(js_to_value(abi, ty, &js_get(value, &variant)?)?),
+                )
+            }
+        }
+    };
+
+    Ok(value)
+}
+
+fn value_to_js(
+    abi: &cairo_serde::Abi,
+    ty: &cairo_serde::Type,
+    value: &cairo_serde::Value,
+) -> Result<JsValue, Error> {
+    use cairo_serde::{Type, Value};
+
+    let bigint = |value: String| -> Result<JsValue, Error> {
+        let bigint =
+            js_sys::BigInt::new(&JsValue::from_str(&value)).map_err(|_| Error::TypeError)?;
+        Ok(bigint.into())
+    };
+    let object = |entries: Vec<(&str, JsValue)>| -> Result<JsValue, Error> {
+        let object = js_sys::Object::new();
+        for (key, value) in entries {
+            js_sys::Reflect::set(&object, &JsValue::from_str(key), &value)?;
+        }
+
+        Ok(object.into())
+    };
+
+    let js_value = match (ty, value) {
+        (Type::Felt | Type::Bytes31, Value::Felt(felt)) => {
+            Uint8Array::from(&felt.into_repr().to_bytes_le()[..]).into()
+        }
+        (Type::Bool, Value::Bool(value)) => JsValue::from_bool(*value),
+        (Type::Uint(_), Value::Uint(value)) => bigint(value.to_string())?,
+        (Type::Int(_), Value::Int(value)) => bigint(value.to_string())?,
+        (Type::U256, Value::U256(value)) => {
+            bigint(((BigUint::from(value.high) << 128) + BigUint::from(value.low)).to_string())?
+        }
+        (Type::ByteArray, Value::ByteArray(value)) => JsValue::from_str(value),
+        (Type::Array(ty), Value::Array(values)) => values
+            .iter()
+            .map(|value| value_to_js(abi, ty, value))
+            .collect::<Result<js_sys::Array, _>>()?
+            .into(),
+        (Type::Option(ty), Value::Option(value)) => match value {
+            Some(value) => value_to_js(abi, ty, value)?,
+            None => JsValue::NULL,
+        },
+        (Type::Tuple(types), Value::Tuple(values)) => types
+            .iter()
+            .zip(values.iter())
+            .map(|(ty, value)| value_to_js(abi, ty, value))
+            .collect::<Result<js_sys::Array, _>>()?
+            .into(),
+        (Type::Named(name), Value::Struct(values)) => {
+            let members = abi.struct_members(name).ok_or(Error::TypeError)?;
+            object(
+                members
+                    .iter()
+                    .zip(values.iter())
+                    .map(|((name, ty), value)| Ok((name.as_str(), value_to_js(abi, ty, value)?)))
+                    .collect::<Result<Vec<_>, Error>>()?,
+            )?
+        }
+        (Type::Named(name), Value::Enum(variant, value)) => {
+            let variants = abi.enum_variants(name).ok_or(Error::TypeError)?;
+            let (_, ty) = variants
+                .iter()
+                .find(|(name, _)| name == variant)
+                .ok_or(Error::TypeError)?;
+
+            object(vec![(variant.as_str(), value_to_js(abi, ty, value)?)])?
+        }
+        _ => return Err(Error::TypeError),
+    };
+
+    Ok(js_value)
+}
+
+/// felts are interpreted in le form since FromBytes expects LE representation
+fn parse_felts<F: PrimeField<BigInt = BigInteger256>>(
+    felts: js_sys::Array,
+) -> Result<Vec<F>, Error> {
+    let felts: Result<Vec<Uint8Array>, JsValue> = felts
+        .values()
+        .into_iter()
+        .map(|felt| felt.unwrap_throw().dyn_into::<Uint8Array>())
+        .collect();
+
+    let felts: Vec<Vec<u8>> = felts?.iter().map(|x| x.to_vec()).collect();
+
+    felts
+        .iter()
+        .map(|felt_bytes| -> Result<F, Error> { try_bytes_to_field(felt_bytes) })
+        .collect::<Result<Vec<_>, Error>>()
+}
+
+/// Points from the array [x0, y0, x1, y1, ...] of their coordinates
+fn parse_points(coordinates: &[Fq]) -> Result<Vec<Affine>, Error> {
+    if coordinates.is_empty() || !coordinates.len().is_multiple_of(2) {
+        return Err(Error::IncorrectLenError);
+    }
+
+    coordinates
+        .chunks(2)
+        .map(|point| {
+            let point = Affine::new(point[0], point[1], false);
+            if !point.is_on_curve() {
+                return Err(Error::InvalidPublicKey);
+            }
+
+            Ok(point)
+        })
+        .collect()
 }
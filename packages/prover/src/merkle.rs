@@ -0,0 +1,153 @@
+use crate::error::Error;
+use crate::hash::HashFunction;
+
+use ark_ff::PrimeField;
+use starknet_curve::Fq;
+
+/// Hashes a pair of nodes sorted by value, as the hashers of the OpenZeppelin merkle_tree package:
+/// Pedersen hashes h(h(h(0, a), b), 2) and Poseidon hashes poseidon_hash_many([a, b]).
+pub fn commutative_hash(hasher: HashFunction, a: &Fq, b: &Fq) -> Fq {
+    if a.into_repr() < b.into_repr() {
+        hasher.hash_many(&[*a, *b])
+    } else {
+        hasher.hash_many(&[*b, *a])
+    }
+}
+
+/// Complete binary tree stored as an array, with the same layout as @openzeppelin/merkle-tree:
+/// node i has children 2i + 1 and 2i + 2, the root is at index 0 and leaves are at the end.
+/// Leaves are sorted so that the root doesn't depend on the order in which they are given.
+pub struct MerkleTree {
+    hasher: HashFunction,
+    nodes: Vec<Fq>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: &[Fq], hasher: HashFunction) -> Result<Self, Error> {
+        if leaves.is_empty() {
+            return Err(Error::EmptyDataError);
+        }
+
+        let mut sorted = leaves.to_vec();
+        sorted.sort_by_key(|leaf| leaf.into_repr());
+
+        let n_nodes = 2 * sorted.len() - 1;
+        let mut nodes = vec![Fq::from(0u64); n_nodes];
+        for (i, leaf) in sorted.iter().enumerate() {
+            nodes[n_nodes - 1 - i] = *leaf;
+        }
+
+        for i in (0..n_nodes - sorted.len()).rev() {
+            nodes[i] = commutative_hash(hasher, &nodes[2 * i + 1], &nodes[2 * i + 2]);
+        }
+
+        Ok(Self { hasher, nodes })
+    }
+
+    pub fn root(&self) -> Fq {
+        self.nodes[0]
+    }
+
+    pub fn hasher(&self) -> HashFunction {
+        self.hasher
+    }
+
+    /// Sibling nodes from the leaf up to the root, None if the leaf is not in the tree
+    pub fn prove(&self, leaf: &Fq) -> Option<Vec<Fq>> {
+        let n_leaves = self.nodes.len().div_ceil(2);
+        let mut index = self.nodes.len() - n_leaves
+            + self.nodes[self.nodes.len() - n_leaves..]
+                .iter()
+                .position(|node| node == leaf)?;
+
+        let mut proof = vec![];
+        while index > 0 {
+            let sibling = if index % 2 == 1 { index + 1 } else { index - 1 };
+            proof.push(self.nodes[sibling]);
+            index = (index - 1) / 2;
+        }
+
+        Some(proof)
+    }
+}
+
+/// Same as `verify` of the OpenZeppelin merkle_proof cairo module
+pub fn verify_merkle_proof(root: &Fq, leaf: &Fq, proof: &[Fq], hasher: HashFunction) -> bool {
+    let computed = proof.iter().fold(*leaf, |node, sibling| {
+        commutative_hash(hasher, &node, sibling)
+    });
+
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commutative_hash, verify_merkle_proof, MerkleTree};
+    use crate::error::Error;
+    use crate::hash::HashFunction;
+    use crate::pedersen::compute_hash_on_elements;
+    use crate::poseidon::poseidon_hash_many;
+    use starknet_curve::Fq;
+
+    #[test]
+    fn test_commutative_hash() {
+        let a = Fq::from(17u64);
+        let b = Fq::from(71u64);
+
+        assert_eq!(
+            compute_hash_on_elements(&[a, b]),
+            commutative_hash(HashFunction::Pedersen, &b, &a)
+        );
+        assert_eq!(
+            poseidon_hash_many(&[a, b]),
+            commutative_hash(HashFunction::Poseidon, &b, &a)
+        );
+    }
+
+    #[test]
+    fn test_proofs() {
+        for hasher in [HashFunction::Pedersen, HashFunction::Poseidon] {
+            for n_leaves in 1..=7u64 {
+                let leaves: Vec<Fq> = (0..n_leaves).map(|i| Fq::from(100 - i)).collect();
+                let tree = MerkleTree::new(&leaves, hasher).unwrap();
+                let root = tree.root();
+
+                for leaf in leaves.iter() {
+                    let proof = tree.prove(leaf).unwrap();
+                    assert!(verify_merkle_proof(&root, leaf, &proof, hasher));
+                    assert!(!verify_merkle_proof(&root, &Fq::from(1u64), &proof, hasher));
+                }
+
+                // the order of the leaves doesn't change the root
+                let reversed: Vec<Fq> = leaves.iter().rev().copied().collect();
+                assert_eq!(root, MerkleTree::new(&reversed, hasher).unwrap().root());
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_trees() {
+        let a = Fq::from(1u64);
+        let b = Fq::from(2u64);
+        let c = Fq::from(3u64);
+
+        let tree = MerkleTree::new(&[a], HashFunction::Pedersen).unwrap();
+        assert_eq!(a, tree.root());
+        assert_eq!(Some(vec![]), tree.prove(&a));
+        assert_eq!(None, tree.prove(&b));
+
+        // leaves are sorted and stored from the end of the array: [root, h(b, a), c, b, a]
+        let tree = MerkleTree::new(&[c, a, b], HashFunction::Poseidon).unwrap();
+        let expected = commutative_hash(
+            HashFunction::Poseidon,
+            &commutative_hash(HashFunction::Poseidon, &b, &a),
+            &c,
+        );
+        assert_eq!(expected, tree.root());
+
+        assert_eq!(
+            Some(Error::EmptyDataError),
+            MerkleTree::new(&[], HashFunction::Poseidon).err()
+        );
+    }
+}
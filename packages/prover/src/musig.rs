@@ -0,0 +1,493 @@
+use crate::{
+    error::Error,
+    hash::HashFunction,
+    poseidon::poseidon_hash_many,
+    schnorr::{challenge, lift_x, SchnorrSignature},
+    signature::{private_key_to_public_key, SigningParameters},
+    util::short_string_to_field,
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr, Projective};
+use zeroize::Zeroize;
+
+/*
+MuSig2 (https://eprint.iacr.org/2020/1261) following BIP327, producing signatures of the schnorr module.
+Public keys are stark keys, the aggregate key is the x coordinate of Q = sum(a_i * P_i)
+
+Round 1: every signer generates a secret nonce (k1, k2) and broadcasts the public nonce (k1*G, k2*G)
+Round 2: public nonces are summed into (R1, R2), then every signer computes R = R1 + b*R2 and
+         broadcasts s_i = k1 + b*k2 + e*a_i*x_i, with the signs adjusted for even y coordinates.
+The signature is (R.x, sum(s_i)).
+
+Protocol messages are serialized with ark-serialize. Coefficient hashes always use Poseidon, the
+challenge hash is chosen per session since it's the one checked by the verifier.
+*/
+
+const KEY_AGG_LIST_TAG: &str = "STARK_MUSIG_KEYAGG_LIST";
+const KEY_AGG_COEFFICIENT_TAG: &str = "STARK_MUSIG_KEYAGG_COEF";
+const NONCE_COEFFICIENT_TAG: &str = "STARK_MUSIG_NONCE_COEF";
+
+fn tagged_hash(tag: &str, data: &[Fq]) -> Fq {
+    // it's safe to unwrap, tags are short ascii strings
+    let mut input = vec![short_string_to_field(tag).unwrap()];
+    input.extend_from_slice(data);
+
+    poseidon_hash_many(&input)
+}
+
+fn to_scalar(element: &Fq) -> Fr {
+    Fr::from_le_bytes_mod_order(&element.into_repr().to_bytes_le())
+}
+
+fn is_odd(point: &Affine) -> bool {
+    point.y.into_repr().is_odd()
+}
+
+/// Public keys of the signers and their coefficients, the order of the keys matters
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyAggContext {
+    pub_keys: Vec<Fq>,
+    coefficients: Vec<Fr>,
+    aggregate: Affine,
+}
+
+impl KeyAggContext {
+    pub fn new(pub_keys: &[Fq]) -> Result<Self, Error> {
+        if pub_keys.is_empty() {
+            return Err(Error::EmptyDataError);
+        }
+
+        let list_hash = tagged_hash(KEY_AGG_LIST_TAG, pub_keys);
+
+        let mut coefficients = Vec::with_capacity(pub_keys.len());
+        let mut aggregate = Projective::zero();
+        for pub_key in pub_keys {
+            let point = lift_x(pub_key).ok_or(Error::InvalidPublicKey)?;
+            let coefficient = to_scalar(&tagged_hash(
+                KEY_AGG_COEFFICIENT_TAG,
+                &[list_hash, *pub_key],
+            ));
+
+            aggregate += point.mul(coefficient.into_repr());
+            coefficients.push(coefficient);
+        }
+
+        let aggregate = aggregate.into_affine();
+        if aggregate.is_zero() {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        Ok(Self {
+            pub_keys: pub_keys.to_vec(),
+            coefficients,
+            aggregate,
+        })
+    }
+
+    pub fn pub_keys(&self) -> &[Fq] {
+        &self.pub_keys
+    }
+
+    /// The stark key that verifies the aggregated signatures
+    pub fn aggregate_key(&self) -> Fq {
+        self.aggregate.x
+    }
+
+    pub fn coefficient(&self, pub_key: &Fq) -> Option<Fr> {
+        let index = self.pub_keys.iter().position(|key| key == pub_key)?;
+        Some(self.coefficients[index])
+    }
+}
+
+/// Must be used for a single signature and never be shared, reusing it leaks the private key.
+/// It can't be serialized, partial_sign consumes it and it's zeroized when dropped.
+pub struct SecretNonce {
+    k1: Fr,
+    k2: Fr,
+}
+
+impl Drop for SecretNonce {
+    fn drop(&mut self) {
+        self.k1.zeroize();
+        self.k2.zeroize();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PublicNonce {
+    pub r1: Affine,
+    pub r2: Affine,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PartialSignature(pub Fr);
+
+/// Nonces are random, unlike schnorr::sign a deterministic nonce isn't safe with several signers
+pub fn nonce_gen<R: Rng>(
+    parameters: &SigningParameters,
+    rng: &mut R,
+) -> (SecretNonce, PublicNonce) {
+    let secret = SecretNonce {
+        k1: Fr::rand(rng),
+        k2: Fr::rand(rng),
+    };
+    let public = PublicNonce {
+        r1: parameters
+            .generator
+            .mul(secret.k1.into_repr())
+            .into_affine(),
+        r2: parameters
+            .generator
+            .mul(secret.k2.into_repr())
+            .into_affine(),
+    };
+
+    (secret, public)
+}
+
+/// Sums the public nonces of all the signers, it can be done by any party since nothing is secret
+pub fn aggregate_nonces(nonces: &[PublicNonce]) -> Result<PublicNonce, Error> {
+    if nonces.is_empty() {
+        return Err(Error::EmptyDataError);
+    }
+
+    let (r1, r2) = nonces.iter().fold(
+        (Projective::zero(), Projective::zero()),
+        |(r1, r2), nonce| (r1.add_mixed(&nonce.r1), r2.add_mixed(&nonce.r2)),
+    );
+
+    Ok(PublicNonce {
+        r1: r1.into_affine(),
+        r2: r2.into_affine(),
+    })
+}
+
+/// Everything that the signers agree on before the second round
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    key_agg: KeyAggContext,
+    aggregate_nonce: PublicNonce,
+    msg_hash: Fq,
+    hasher: HashFunction,
+    // derived values
+    b: Fr,
+    nonce_point: Affine,
+    e: Fr,
+}
+
+impl Session {
+    pub fn new(
+        parameters: &SigningParameters,
+        key_agg: KeyAggContext,
+        aggregate_nonce: PublicNonce,
+        msg_hash: Fq,
+        hasher: HashFunction,
+    ) -> Self {
+        let PublicNonce { r1, r2 } = aggregate_nonce;
+        let b = to_scalar(&tagged_hash(
+            NONCE_COEFFICIENT_TAG,
+            &[r1.x, r1.y, r2.x, r2.y, key_agg.aggregate_key(), msg_hash],
+        ));
+
+        let mut nonce_point = (r1.into_projective() + r2.mul(b.into_repr())).into_affine();
+        // as in BIP327, this only happens if the nonces are adversarial
+        if nonce_point.is_zero() {
+            nonce_point = parameters.generator.into_affine();
+        }
+
+        let e = challenge(hasher, &nonce_point.x, &key_agg.aggregate_key(), &msg_hash);
+
+        Self {
+            key_agg,
+            aggregate_nonce,
+            msg_hash,
+            hasher,
+            b,
+            nonce_point,
+            e,
+        }
+    }
+
+    pub fn key_agg(&self) -> &KeyAggContext {
+        &self.key_agg
+    }
+
+    pub fn msg_hash(&self) -> Fq {
+        self.msg_hash
+    }
+
+    pub fn hasher(&self) -> HashFunction {
+        self.hasher
+    }
+
+    /// -1 if the aggregate key or the nonce point has an odd y coordinate, 1 otherwise
+    fn signs(&self) -> (Fr, Fr) {
+        let sign = |point: &Affine| {
+            if is_odd(point) {
+                -Fr::from(1u64)
+            } else {
+                Fr::from(1u64)
+            }
+        };
+
+        (sign(&self.key_agg.aggregate), sign(&self.nonce_point))
+    }
+
+    /// Serializes the public keys, the aggregate nonce, the message hash and the hash function
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![];
+        (
+            self.key_agg.pub_keys.clone(),
+            self.aggregate_nonce,
+            self.msg_hash,
+            self.hasher,
+        )
+            .serialize(&mut bytes)
+            .map_err(|_| Error::IOError)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(parameters: &SigningParameters, bytes: &[u8]) -> Result<Self, Error> {
+        let (pub_keys, aggregate_nonce, msg_hash, hasher) =
+            <(Vec<Fq>, PublicNonce, Fq, HashFunction)>::deserialize(bytes)
+                .map_err(|_| Error::IOError)?;
+
+        Ok(Self::new(
+            parameters,
+            KeyAggContext::new(&pub_keys)?,
+            aggregate_nonce,
+            msg_hash,
+            hasher,
+        ))
+    }
+}
+
+/// Second round, the secret nonce is consumed so that it can't be used twice
+pub fn partial_sign(
+    parameters: &SigningParameters,
+    session: &Session,
+    priv_key: Fr,
+    secret_nonce: SecretNonce,
+) -> Result<PartialSignature, Error> {
+    let pub_key = private_key_to_public_key(parameters, priv_key).into_affine();
+    let coefficient = session
+        .key_agg
+        .coefficient(&pub_key.x)
+        .ok_or(Error::InvalidPrivateKey)?;
+
+    // the key of the signer is lifted to the point with an even y coordinate
+    let priv_key = if is_odd(&pub_key) {
+        -priv_key
+    } else {
+        priv_key
+    };
+
+    let (key_sign, nonce_sign) = session.signs();
+    let k = nonce_sign * (secret_nonce.k1 + session.b * secret_nonce.k2);
+
+    Ok(PartialSignature(
+        k + session.e * coefficient * key_sign * priv_key,
+    ))
+}
+
+/// Checks s_i*G = R1_i + b*R2_i + e*a_i*P_i, with the same sign adjustments as partial_sign
+pub fn partial_verify(
+    parameters: &SigningParameters,
+    session: &Session,
+    pub_key: &Fq,
+    public_nonce: &PublicNonce,
+    partial_signature: &PartialSignature,
+) -> bool {
+    let (point, coefficient) = match (lift_x(pub_key), session.key_agg.coefficient(pub_key)) {
+        (Some(point), Some(coefficient)) => (point, coefficient),
+        _ => return false,
+    };
+
+    let (key_sign, nonce_sign) = session.signs();
+    let nonce_point =
+        public_nonce.r1.into_projective() + public_nonce.r2.mul(session.b.into_repr());
+    let expected = nonce_point.mul(nonce_sign.into_repr())
+        + point.mul((session.e * coefficient * key_sign).into_repr());
+
+    parameters.generator.mul(partial_signature.0.into_repr()) == expected
+}
+
+/// Sums the partial signatures into a signature verified by schnorr::verify with the aggregate key
+pub fn aggregate(session: &Session, partial_signatures: &[PartialSignature]) -> SchnorrSignature {
+    SchnorrSignature {
+        r: session.nonce_point.x,
+        s: partial_signatures
+            .iter()
+            .fold(Fr::zero(), |s, partial_signature| s + partial_signature.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        aggregate, aggregate_nonces, nonce_gen, partial_sign, partial_verify, KeyAggContext,
+        PartialSignature, PublicNonce, SecretNonce, Session,
+    };
+    use crate::error::Error;
+    use crate::hash::HashFunction;
+    use crate::schnorr::verify;
+    use crate::signature::{parameters, private_key_to_public_key};
+    use ark_ec::ProjectiveCurve;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    #[test]
+    fn test_musig() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        for hasher in [HashFunction::Pedersen, HashFunction::Poseidon] {
+            for n_signers in 1..=4 {
+                let private_keys: Vec<Fr> = (0..n_signers).map(|_| Fr::rand(rng)).collect();
+                let pub_keys: Vec<Fq> = private_keys
+                    .iter()
+                    .map(|key| private_key_to_public_key(&parameters, *key).into_affine().x)
+                    .collect();
+                let msg_hash = Fq::rand(rng);
+
+                let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+                let aggregate_key = key_agg.aggregate_key();
+
+                let (secret_nonces, public_nonces): (Vec<SecretNonce>, Vec<PublicNonce>) =
+                    (0..n_signers).map(|_| nonce_gen(&parameters, rng)).unzip();
+                let aggregate_nonce = aggregate_nonces(&public_nonces).unwrap();
+
+                let session = Session::new(&parameters, key_agg, aggregate_nonce, msg_hash, hasher);
+
+                let partial_signatures: Vec<PartialSignature> = private_keys
+                    .iter()
+                    .zip(secret_nonces)
+                    .map(|(key, nonce)| partial_sign(&parameters, &session, *key, nonce).unwrap())
+                    .collect();
+
+                for i in 0..n_signers {
+                    assert!(partial_verify(
+                        &parameters,
+                        &session,
+                        &pub_keys[i],
+                        &public_nonces[i],
+                        &partial_signatures[i]
+                    ));
+                    assert!(!partial_verify(
+                        &parameters,
+                        &session,
+                        &pub_keys[i],
+                        &public_nonces[i],
+                        &PartialSignature(partial_signatures[i].0 + Fr::from(1u64))
+                    ));
+                }
+
+                let sig = aggregate(&session, &partial_signatures);
+                assert!(verify(&parameters, &aggregate_key, &msg_hash, &sig, hasher));
+
+                // a missing partial signature gives an invalid signature
+                if n_signers > 1 {
+                    let sig = aggregate(&session, &partial_signatures[1..]);
+                    assert!(!verify(
+                        &parameters,
+                        &aggregate_key,
+                        &msg_hash,
+                        &sig,
+                        hasher
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_aggregation() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let pub_keys: Vec<Fq> = (0..3)
+            .map(|_| {
+                private_key_to_public_key(&parameters, Fr::rand(rng))
+                    .into_affine()
+                    .x
+            })
+            .collect();
+
+        // the order of the keys changes the aggregate key
+        let key_agg = KeyAggContext::new(&pub_keys).unwrap();
+        let reversed: Vec<Fq> = pub_keys.iter().rev().copied().collect();
+        assert_ne!(
+            key_agg.aggregate_key(),
+            KeyAggContext::new(&reversed).unwrap().aggregate_key()
+        );
+
+        assert_eq!(None, key_agg.coefficient(&Fq::from(1u64)));
+        assert_eq!(Some(Error::EmptyDataError), KeyAggContext::new(&[]).err());
+
+        // a signer that is not in the list can't sign
+        let (secret_nonce, public_nonce) = nonce_gen(&parameters, rng);
+        let session = Session::new(
+            &parameters,
+            key_agg,
+            public_nonce,
+            Fq::rand(rng),
+            HashFunction::Poseidon,
+        );
+        assert_eq!(
+            Some(Error::InvalidPrivateKey),
+            partial_sign(&parameters, &session, Fr::rand(rng), secret_nonce).err()
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let pub_key = private_key_to_public_key(&parameters, private_key)
+            .into_affine()
+            .x;
+        let key_agg = KeyAggContext::new(&[pub_key]).unwrap();
+
+        let (secret_nonce, public_nonce) = nonce_gen(&parameters, rng);
+        let mut bytes = vec![];
+        public_nonce.serialize(&mut bytes).unwrap();
+        assert_eq!(
+            public_nonce,
+            PublicNonce::deserialize(bytes.as_slice()).unwrap()
+        );
+
+        let session = Session::new(
+            &parameters,
+            key_agg,
+            public_nonce,
+            Fq::rand(rng),
+            HashFunction::Pedersen,
+        );
+        let bytes = session.to_bytes().unwrap();
+        let deserialized = Session::from_bytes(&parameters, &bytes).unwrap();
+        assert_eq!(session, deserialized);
+        assert_eq!(
+            Some(Error::IOError),
+            Session::from_bytes(&parameters, &bytes[..bytes.len() - 1]).err()
+        );
+
+        let partial_signature =
+            partial_sign(&parameters, &deserialized, private_key, secret_nonce).unwrap();
+        assert!(partial_verify(
+            &parameters,
+            &session,
+            &pub_key,
+            &public_nonce,
+            &partial_signature
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    calls::Call,
+    error::Error,
+    signature::{sign as starknet_sign, Signature, SigningParameters},
+    typed_data::{struct_hash, type_hash, Revision, StarknetDomain},
+    util::short_string_to_field,
+};
+
+use starknet_curve::{Fq, Fr};
+
+/*
+Outside execution (SNIP-9, https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-9.md), calls
+signed by the account owner that anyone, e.g. a relayer, submits through execute_from_outside.
+
+Version 1 is a SNIP-12 revision 0 message hashed with Pedersen and version 2 a revision 1 message
+hashed with Poseidon, under the domain "Account.execute_from_outside" with the version as felt.
+In both versions the calldata and the calls are hashed as arrays:
+    call_hash = hash([CALL_TYPE_HASH, to, selector, (calldata_len,) hash(calldata)])
+    outside_execution_hash = hash([TYPE_HASH, caller, nonce, execute_after, execute_before,
+                                   (calls_len,) hash(call hashes)])
+where the lengths are only part of version 1.
+*/
+
+/// Caller of an outside execution that anyone can submit
+pub const ANY_CALLER: &str = "ANY_CALLER";
+
+const DOMAIN_NAME: &str = "Account.execute_from_outside";
+
+const OUTSIDE_EXECUTION_TYPE_V1: &str = "OutsideExecution(caller:felt,nonce:felt,execute_after:felt,execute_before:felt,calls_len:felt,calls:OutsideCall*)OutsideCall(to:felt,selector:felt,calldata_len:felt,calldata:felt*)";
+const CALL_TYPE_V1: &str = "OutsideCall(to:felt,selector:felt,calldata_len:felt,calldata:felt*)";
+
+const OUTSIDE_EXECUTION_TYPE_V2: &str = "\"OutsideExecution\"(\"Caller\":\"ContractAddress\",\"Nonce\":\"felt\",\"Execute After\":\"u128\",\"Execute Before\":\"u128\",\"Calls\":\"Call*\")\"Call\"(\"To\":\"ContractAddress\",\"Selector\":\"selector\",\"Calldata\":\"felt*\")";
+const CALL_TYPE_V2: &str =
+    "\"Call\"(\"To\":\"ContractAddress\",\"Selector\":\"selector\",\"Calldata\":\"felt*\")";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Version {
+    V1,
+    V2,
+}
+
+impl Version {
+    fn revision(&self) -> Revision {
+        match self {
+            Self::V1 => Revision::V0,
+            Self::V2 => Revision::V1,
+        }
+    }
+
+    fn domain(&self, chain_id: &Fq) -> StarknetDomain {
+        let version = match self {
+            Self::V1 => 1u64,
+            Self::V2 => 2u64,
+        };
+
+        StarknetDomain {
+            // it's safe to unwrap, the name is a short ascii string
+            name: short_string_to_field(DOMAIN_NAME).unwrap(),
+            version: Fq::from(version),
+            chain_id: *chain_id,
+            revision: self.revision(),
+        }
+    }
+}
+
+fn call_hash(call: &Call, version: Version) -> Fq {
+    let calldata_hash = version.revision().hasher().hash_many(&call.calldata);
+
+    match version {
+        Version::V1 => struct_hash(
+            version.revision(),
+            &type_hash(CALL_TYPE_V1),
+            &[
+                call.to,
+                call.selector,
+                Fq::from(call.calldata.len() as u64),
+                calldata_hash,
+            ],
+        ),
+        Version::V2 => struct_hash(
+            version.revision(),
+            &type_hash(CALL_TYPE_V2),
+            &[call.to, call.selector, calldata_hash],
+        ),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutsideExecution {
+    /// Address allowed to submit the calls, or the short string ANY_CALLER
+    pub caller: Fq,
+    pub nonce: Fq,
+    /// Unix timestamps in seconds, the calls are valid strictly between them
+    pub execute_after: u64,
+    pub execute_before: u64,
+    pub calls: Vec<Call>,
+}
+
+impl OutsideExecution {
+    pub fn hash(&self, version: Version) -> Fq {
+        let revision = version.revision();
+        let call_hashes: Vec<Fq> = self
+            .calls
+            .iter()
+            .map(|call| call_hash(call, version))
+            .collect();
+        let calls_hash = revision.hasher().hash_many(&call_hashes);
+
+        let mut fields = vec![
+            self.caller,
+            self.nonce,
+            Fq::from(self.execute_after),
+            Fq::from(self.execute_before),
+        ];
+        let type_hash = match version {
+            Version::V1 => {
+                fields.push(Fq::from(self.calls.len() as u64));
+                type_hash(OUTSIDE_EXECUTION_TYPE_V1)
+            }
+            Version::V2 => type_hash(OUTSIDE_EXECUTION_TYPE_V2),
+        };
+        fields.push(calls_hash);
+
+        struct_hash(revision, &type_hash, &fields)
+    }
+
+    /// The SNIP-12 message that the account owner signs
+    pub fn message_hash(&self, version: Version, chain_id: &Fq, account: &Fq) -> Fq {
+        version
+            .domain(chain_id)
+            .message_hash(account, &self.hash(version))
+    }
+}
+
+/// Signature of the owner of a single signer account, which expects it as [r, s]
+pub fn sign(
+    parameters: &SigningParameters,
+    priv_key: Fr,
+    outside_execution: &OutsideExecution,
+    version: Version,
+    chain_id: &Fq,
+    account: &Fq,
+) -> Result<Signature, Error> {
+    let msg_hash = outside_execution.message_hash(version, chain_id, account);
+    starknet_sign(parameters, priv_key, msg_hash, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        call_hash, sign, type_hash, OutsideExecution, Version, ANY_CALLER, CALL_TYPE_V1,
+        CALL_TYPE_V2, OUTSIDE_EXECUTION_TYPE_V1, OUTSIDE_EXECUTION_TYPE_V2,
+    };
+    use crate::calls::Call;
+    use crate::pedersen::compute_hash_on_elements;
+    use crate::poseidon::poseidon_hash_many;
+    use crate::signature::{parameters, private_key_to_public_key, verify};
+    use crate::util::{hex_to_field, short_string_to_field};
+    use ark_ec::ProjectiveCurve;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    fn outside_execution() -> OutsideExecution {
+        OutsideExecution {
+            caller: short_string_to_field(ANY_CALLER).unwrap(),
+            nonce: Fq::from(1u64),
+            execute_after: 0,
+            execute_before: 1_700_000_000,
+            calls: vec![
+                Call {
+                    to: Fq::from(0x1234u64),
+                    selector: Fq::from(0x5678u64),
+                    calldata: vec![Fq::from(1u64), Fq::from(2u64)],
+                },
+                Call {
+                    to: Fq::from(0x4321u64),
+                    selector: Fq::from(0x8765u64),
+                    calldata: vec![],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_hash() {
+        let outside_execution = outside_execution();
+        let call = &outside_execution.calls[0];
+
+        // the lengths of the arrays are only hashed in version 1
+        let v1_hash = call_hash(call, Version::V1);
+        let v2_hash = call_hash(call, Version::V2);
+        let calldata_v1 = compute_hash_on_elements(&call.calldata);
+        let calldata_v2 = poseidon_hash_many(&call.calldata);
+        assert_eq!(
+            v1_hash,
+            compute_hash_on_elements(&[
+                type_hash(CALL_TYPE_V1),
+                call.to,
+                call.selector,
+                Fq::from(2u64),
+                calldata_v1
+            ])
+        );
+        assert_eq!(
+            v2_hash,
+            poseidon_hash_many(&[type_hash(CALL_TYPE_V2), call.to, call.selector, calldata_v2])
+        );
+
+        let calls_v1: Vec<Fq> = outside_execution
+            .calls
+            .iter()
+            .map(|call| call_hash(call, Version::V1))
+            .collect();
+        assert_eq!(
+            compute_hash_on_elements(&[
+                type_hash(OUTSIDE_EXECUTION_TYPE_V1),
+                outside_execution.caller,
+                Fq::from(1u64),
+                Fq::from(0u64),
+                Fq::from(1_700_000_000u64),
+                Fq::from(2u64),
+                compute_hash_on_elements(&calls_v1)
+            ]),
+            outside_execution.hash(Version::V1)
+        );
+        let calls_v2: Vec<Fq> = outside_execution
+            .calls
+            .iter()
+            .map(|call| call_hash(call, Version::V2))
+            .collect();
+        assert_eq!(
+            poseidon_hash_many(&[
+                type_hash(OUTSIDE_EXECUTION_TYPE_V2),
+                outside_execution.caller,
+                Fq::from(1u64),
+                Fq::from(0u64),
+                Fq::from(1_700_000_000u64),
+                poseidon_hash_many(&calls_v2)
+            ]),
+            outside_execution.hash(Version::V2)
+        );
+
+        let chain_id = short_string_to_field("SN_MAIN").unwrap();
+        let account = Fq::from(0xaccu64);
+        assert_ne!(
+            outside_execution.message_hash(Version::V1, &chain_id, &account),
+            outside_execution.message_hash(Version::V2, &chain_id, &account)
+        );
+
+        let mut other = outside_execution.clone();
+        other.nonce = Fq::from(2u64);
+        assert_ne!(outside_execution.hash(Version::V2), other.hash(Version::V2));
+    }
+
+    #[test]
+    fn test_type_hashes() {
+        let felt = |hex: &str| hex_to_field::<Fq>(hex).unwrap();
+
+        // type hashes of SNIP-9
+        assert_eq!(
+            felt("0x11ff76fe3f640fa6f3d60bbd94a3b9d47141a2c96f87fdcfbeb2af1d03f7050"),
+            type_hash(OUTSIDE_EXECUTION_TYPE_V1)
+        );
+        assert_eq!(
+            felt("0xf00de1fccbb286f9a020ba8821ee936b1deea42a5c485c11ccdc82c8bebb3a"),
+            type_hash(CALL_TYPE_V1)
+        );
+        assert_eq!(
+            felt("0x312b56c05a7965066ddbda31c016d8d05afc305071c0ca3cdc2192c3c2f1f0f"),
+            type_hash(OUTSIDE_EXECUTION_TYPE_V2)
+        );
+        assert_eq!(
+            felt("0x3635c7f2a7ba93844c0d064e18e487f35ab90f7c39d00f186a781fc3f0c2ca9"),
+            type_hash(CALL_TYPE_V2)
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let public_key = private_key_to_public_key(&parameters, private_key).into_affine();
+        let chain_id = short_string_to_field("SN_SEPOLIA").unwrap();
+        let account = Fq::from(0xaccu64);
+
+        let outside_execution = outside_execution();
+        for version in [Version::V1, Version::V2] {
+            let signature = sign(
+                &parameters,
+                private_key,
+                &outside_execution,
+                version,
+                &chain_id,
+                &account,
+            )
+            .unwrap();
+
+            let msg_hash = outside_execution.message_hash(version, &chain_id, &account);
+            assert!(verify(&parameters, &public_key, &msg_hash, &signature));
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use num_bigint_dig::{BigUint, ModInverse, RandBigInt, RandPrime};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/*
+Paillier encryption with g = n + 1, additively homomorphic:
+Enc(m1) * Enc(m2) = Enc(m1 + m2) and Enc(m)^k = Enc(k * m), modulo n
+
+The owner of a key proves that gcd(n, phi(n)) = 1, i.e. that encryption is a bijection, with the
+non-interactive proof of https://eprint.iacr.org/2018/057: n has no prime factor below ALPHA and
+the owner gives the n-th roots of ROUNDS values derived from n, which only exist for all of them
+with probability at most ALPHA^-ROUNDS when the gcd isn't 1.
+*/
+
+const CORRECT_KEY_TAG: &[u8] = b"STARK_PAILLIER_CORRECT_KEY";
+// 128 bits of security
+const CORRECT_KEY_ALPHA: u32 = 6370;
+const CORRECT_KEY_ROUNDS: usize = 11;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptionKey {
+    pub n: BigUint,
+    nn: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecryptionKey {
+    pub encryption_key: EncryptionKey,
+    lambda: BigUint,
+    mu: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ciphertext(pub BigUint);
+
+impl EncryptionKey {
+    pub fn new(n: BigUint) -> Self {
+        let nn = &n * &n;
+        Self { n, nn }
+    }
+
+    pub fn encrypt<R: Rng>(&self, message: &BigUint, rng: &mut R) -> Ciphertext {
+        self.encrypt_with(message, &self.randomness(rng))
+    }
+
+    /// Randomness of an encryption, coprime with n with overwhelming probability
+    pub fn randomness<R: Rng>(&self, rng: &mut R) -> BigUint {
+        rng.gen_biguint_range(&BigUint::one(), &self.n)
+    }
+
+    /// Encryption with a given randomness, which proofs about the ciphertext need
+    pub fn encrypt_with(&self, message: &BigUint, randomness: &BigUint) -> Ciphertext {
+        let gm = (BigUint::one() + message * &self.n) % &self.nn;
+
+        Ciphertext((gm * randomness.modpow(&self.n, &self.nn)) % &self.nn)
+    }
+
+    pub fn add(&self, c1: &Ciphertext, c2: &Ciphertext) -> Ciphertext {
+        Ciphertext((&c1.0 * &c2.0) % &self.nn)
+    }
+
+    pub fn mul(&self, c: &Ciphertext, k: &BigUint) -> Ciphertext {
+        Ciphertext(c.0.modpow(k, &self.nn))
+    }
+
+    pub fn is_valid(&self, c: &Ciphertext) -> bool {
+        !c.0.is_zero() && c.0 < self.nn
+    }
+
+    pub fn verify_correct_key(&self, proof: &CorrectKeyProof) -> bool {
+        let n = &self.n;
+        if proof.roots.len() != CORRECT_KEY_ROUNDS
+            || small_primes(CORRECT_KEY_ALPHA)
+                .iter()
+                .any(|prime| (n % prime).is_zero())
+        {
+            return false;
+        }
+
+        proof
+            .roots
+            .iter()
+            .enumerate()
+            .all(|(i, root)| root < n && root.modpow(n, n) == correct_key_challenge(n, i))
+    }
+}
+
+impl DecryptionKey {
+    /// n has `bits` bits, it's the product of two primes of the same size
+    pub fn generate<R: Rng>(bits: usize, rng: &mut R) -> Self {
+        loop {
+            let p: BigUint = rng.gen_prime(bits / 2);
+            let q: BigUint = rng.gen_prime(bits / 2);
+            if p == q {
+                continue;
+            }
+
+            let lambda = (&p - 1u32).lcm(&(&q - 1u32));
+            if let Some(key) = Self::from_lambda(&p * &q, lambda) {
+                return key;
+            }
+        }
+    }
+
+    fn from_lambda(n: BigUint, lambda: BigUint) -> Option<Self> {
+        // with g = n + 1, mu is the inverse of lambda modulo n
+        let mu = (&lambda).mod_inverse(&n).and_then(|mu| mu.to_biguint())?;
+
+        Some(Self {
+            encryption_key: EncryptionKey::new(n),
+            lambda,
+            mu,
+        })
+    }
+
+    pub fn decrypt(&self, c: &Ciphertext) -> BigUint {
+        let EncryptionKey { n, nn } = &self.encryption_key;
+        // L(x) = (x - 1) / n
+        let l = (c.0.modpow(&self.lambda, nn) - 1u32) / n;
+
+        (l * &self.mu) % n
+    }
+
+    pub fn correct_key_proof(&self) -> CorrectKeyProof {
+        let n = &self.encryption_key.n;
+        // x -> x^n is a bijection of Z_n* whose inverse is x -> x^(n^-1 mod lambda), n is coprime
+        // with lambda since mu exists so it's safe to unwrap
+        let exponent = n
+            .mod_inverse(&self.lambda)
+            .and_then(|exponent| exponent.to_biguint())
+            .unwrap();
+
+        CorrectKeyProof {
+            roots: (0..CORRECT_KEY_ROUNDS)
+                .map(|i| correct_key_challenge(n, i).modpow(&exponent, n))
+                .collect(),
+        }
+    }
+}
+
+/// Proof that gcd(n, phi(n)) = 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorrectKeyProof {
+    pub roots: Vec<BigUint>,
+}
+
+fn correct_key_challenge(n: &BigUint, i: usize) -> BigUint {
+    hash_to_biguint(CORRECT_KEY_TAG, &[n, &BigUint::from(i)], n)
+}
+
+fn small_primes(bound: u32) -> Vec<u32> {
+    let mut is_prime = vec![true; bound as usize];
+    let mut primes = vec![];
+    for i in 2..bound as usize {
+        if is_prime[i] {
+            primes.push(i as u32);
+            (i * i..bound as usize)
+                .step_by(i)
+                .for_each(|multiple| is_prime[multiple] = false);
+        }
+    }
+
+    primes
+}
+
+/// Sha256 based hash of the values to [0, modulus), with 128 more bits than the modulus before the
+/// reduction so that the result is close to uniform
+pub(crate) fn hash_to_biguint(tag: &[u8], values: &[&BigUint], modulus: &BigUint) -> BigUint {
+    let mut hasher = Sha256::new_with_prefix(tag);
+    for value in values {
+        let bytes = value.to_bytes_le();
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    let seed = hasher.finalize();
+
+    let len = (modulus.bits() + 128).div_ceil(8);
+    let mut bytes = vec![];
+    let mut counter = 0u32;
+    while bytes.len() < len {
+        bytes.extend(
+            Sha256::new_with_prefix(seed)
+                .chain_update(counter.to_le_bytes())
+                .finalize(),
+        );
+        counter += 1;
+    }
+    bytes.truncate(len);
+
+    BigUint::from_bytes_le(&bytes) % modulus
+}
+
+fn serialize_biguint<W: Write>(value: &BigUint, writer: W) -> Result<(), SerializationError> {
+    value.to_bytes_le().serialize(writer)
+}
+
+fn deserialize_biguint<R: Read>(reader: R) -> Result<BigUint, SerializationError> {
+    Ok(BigUint::from_bytes_le(&Vec::<u8>::deserialize(reader)?))
+}
+
+impl CanonicalSerialize for EncryptionKey {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        serialize_biguint(&self.n, writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.n.to_bytes_le().serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for EncryptionKey {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self::new(deserialize_biguint(reader)?))
+    }
+}
+
+/// Only n and lambda are serialized, mu is derived from them
+impl CanonicalSerialize for DecryptionKey {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        serialize_biguint(&self.encryption_key.n, &mut writer)?;
+        serialize_biguint(&self.lambda, writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.encryption_key.n.to_bytes_le().serialized_size()
+            + self.lambda.to_bytes_le().serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for DecryptionKey {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let n = deserialize_biguint(&mut reader)?;
+        let lambda = deserialize_biguint(reader)?;
+
+        Self::from_lambda(n, lambda).ok_or(SerializationError::InvalidData)
+    }
+}
+
+impl CanonicalSerialize for CorrectKeyProof {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        biguints_to_bytes(&self.roots).serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        biguints_to_bytes(&self.roots).serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for CorrectKeyProof {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self {
+            roots: Vec::<Vec<u8>>::deserialize(reader)?
+                .iter()
+                .map(|bytes| BigUint::from_bytes_le(bytes))
+                .collect(),
+        })
+    }
+}
+
+pub(crate) fn biguints_to_bytes(values: &[BigUint]) -> Vec<Vec<u8>> {
+    values.iter().map(|value| value.to_bytes_le()).collect()
+}
+
+impl CanonicalSerialize for Ciphertext {
+    fn serialize<W: Write>(&self, writer: W) -> Result<(), SerializationError> {
+        serialize_biguint(&self.0, writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        self.0.to_bytes_le().serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for Ciphertext {
+    fn deserialize<R: Read>(reader: R) -> Result<Self, SerializationError> {
+        Ok(Self(deserialize_biguint(reader)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ciphertext, CorrectKeyProof, DecryptionKey, EncryptionKey};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use num_bigint_dig::{BigUint, RandBigInt};
+    use rand::thread_rng;
+
+    #[test]
+    fn test_homomorphic_operations() {
+        let rng = &mut thread_rng();
+        let key = DecryptionKey::generate(512, rng);
+        let ek = &key.encryption_key;
+
+        let m1 = rng.gen_biguint_below(&ek.n);
+        let m2 = rng.gen_biguint_below(&ek.n);
+        let k = rng.gen_biguint(128);
+
+        let c1 = ek.encrypt(&m1, rng);
+        let c2 = ek.encrypt(&m2, rng);
+        assert_eq!(m1, key.decrypt(&c1));
+        assert_eq!((&m1 + &m2) % &ek.n, key.decrypt(&ek.add(&c1, &c2)));
+        assert_eq!((&m1 * &k) % &ek.n, key.decrypt(&ek.mul(&c1, &k)));
+
+        // encryption is randomized
+        assert_ne!(c1, ek.encrypt(&m1, rng));
+
+        let mut bytes = vec![];
+        ek.serialize(&mut bytes).unwrap();
+        assert_eq!(*ek, EncryptionKey::deserialize(bytes.as_slice()).unwrap());
+
+        let mut bytes = vec![];
+        c2.serialize(&mut bytes).unwrap();
+        assert_eq!(c2, Ciphertext::deserialize(bytes.as_slice()).unwrap());
+        assert_eq!(
+            BigUint::from(0u32),
+            key.decrypt(&ek.encrypt(&BigUint::from(0u32), rng))
+        );
+    }
+
+    #[test]
+    fn test_correct_key_proof() {
+        let rng = &mut thread_rng();
+        let key = DecryptionKey::generate(512, rng);
+        let ek = &key.encryption_key;
+
+        let proof = key.correct_key_proof();
+        assert!(ek.verify_correct_key(&proof));
+
+        let mut bytes = vec![];
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(
+            proof,
+            CorrectKeyProof::deserialize(bytes.as_slice()).unwrap()
+        );
+
+        let mut bytes = vec![];
+        key.serialize(&mut bytes).unwrap();
+        assert_eq!(key, DecryptionKey::deserialize(bytes.as_slice()).unwrap());
+
+        // the proof is bound to the modulus
+        let other = DecryptionKey::generate(512, rng);
+        assert!(!other.encryption_key.verify_correct_key(&proof));
+
+        let mut tampered = proof.clone();
+        tampered.roots.pop();
+        assert!(!ek.verify_correct_key(&tampered));
+
+        // as is a modulus with a small factor
+        let with_small_factor = EncryptionKey::new(&ek.n * BigUint::from(3u32));
+        assert!(!with_small_factor.verify_correct_key(&proof));
+    }
+}
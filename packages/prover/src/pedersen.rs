@@ -56,7 +56,7 @@ fn process_single_element(element: Fq, p1: Affine, p2: Affine) -> Affine {
 /// shift_point + x_low * P_0 + x_high * P1 + y_low * P2  + y_high * P3
 /// where x_low is the 248 low bits of x, x_high is the 4 high bits of x and similarly for y.
 /// shift_point, P_0, P_1, P_2, P_3 are constant points generated from the digits of pi.
-fn pedersen_hash(x: &Fq, y: &Fq) -> Fq {
+pub fn pedersen_hash(x: &Fq, y: &Fq) -> Fq {
     let point = CONSTANTS.hash_shift_point
         + process_single_element(*x, CONSTANTS.p0, CONSTANTS.p1)
         + process_single_element(*y, CONSTANTS.p2, CONSTANTS.p3);
@@ -64,28 +64,121 @@ fn pedersen_hash(x: &Fq, y: &Fq) -> Fq {
     point.x
 }
 
+/// Incremental version of compute_hash_on_elements, mirrors the hash_state module of cairo:
+/// new -> hash_init, update/update_many -> hash_update, finalize -> hash_finalize.
+/// This allows hashing data that is received in chunks without keeping it in memory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PedersenHashState {
+    current_hash: Fq,
+    n_words: u64,
+}
+
+impl PedersenHashState {
+    pub fn new() -> Self {
+        Self::with_initial(Fq::zero())
+    }
+
+    /// Starts the chain from a custom value instead of 0
+    pub fn with_initial(initial: Fq) -> Self {
+        Self {
+            current_hash: initial,
+            n_words: 0,
+        }
+    }
+
+    pub fn update(&mut self, felt: &Fq) {
+        self.current_hash = pedersen_hash(&self.current_hash, felt);
+        self.n_words += 1;
+    }
+
+    pub fn update_many(&mut self, felts: &[Fq]) {
+        for felt in felts {
+            self.update(felt);
+        }
+    }
+
+    /// Appends the number of absorbed elements, the state itself is left unchanged
+    pub fn finalize(&self) -> Fq {
+        pedersen_hash(&self.current_hash, &Fq::from(self.n_words))
+    }
+
+    /// Current value of the chain, without the length appended
+    pub fn current_hash(&self) -> Fq {
+        self.current_hash
+    }
+}
+
+impl Default for PedersenHashState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Computes a hash chain over the data, in the following order:
 ///     h(h(h(h(0, data[0]), data[1]), ...), data[n-1]), n).
 /// The hash is initialized with 0 and ends with the data length appended.
 /// The length is appended in order to avoid collisions of the following kind:
 /// H([x,y,z]) = h(h(x,y),z) = H([w, z]) where w = h(x,y).
-pub fn compute_hash_on_elements(data: &Vec<Fq>) -> Result<Fq, Error> {
-    if data.len() == 0 {
+/// As in cairo, hashing empty data is well defined and results in h(0, 0).
+pub fn compute_hash_on_elements(data: &[Fq]) -> Fq {
+    let mut state = PedersenHashState::new();
+    state.update_many(data);
+
+    state.finalize()
+}
+
+/// Same as compute_hash_on_elements but rejects empty data
+pub fn compute_hash_on_elements_strict(data: &[Fq]) -> Result<Fq, Error> {
+    if data.is_empty() {
         return Err(Error::EmptyDataError);
     }
 
-    let mut acc = Fq::zero();
+    Ok(compute_hash_on_elements(data))
+}
+
+/// Same as compute_hash_on_elements but the chain starts from initial instead of 0:
+///     h(h(h(h(initial, data[0]), data[1]), ...), data[n-1]), n).
+pub fn compute_hash_on_elements_with_initial(initial: &Fq, data: &[Fq]) -> Fq {
+    let mut state = PedersenHashState::with_initial(*initial);
+    state.update_many(data);
+
+    state.finalize()
+}
+
+/// Computes the hash chain without appending the length:
+///     h(h(h(initial, data[0]), data[1]), ...), data[n-1]).
+/// Note that this is not collision resistant for data of different lengths.
+pub fn compute_hash_on_elements_without_length(initial: &Fq, data: &[Fq]) -> Fq {
+    let mut state = PedersenHashState::with_initial(*initial);
+    state.update_many(data);
+
+    state.current_hash()
+}
+
+/// Computes the hash chain of the data with its length prefixed, in the following order:
+///     h(n, h(data[0], h(data[1], ..., h(data[n-2], data[n-1])))).
+/// This is compute_hash_chain([n] + data) from cairo-lang, which is what the hash_chain cairo
+/// function expects.
+pub fn compute_hash_chain_with_length_prefix(data: &[Fq]) -> Fq {
     let data_len = Fq::from(data.len() as u64);
-    for y in data.iter().chain(std::iter::once(&data_len)) {
-        acc = pedersen_hash(&acc, y);
-    }
 
-    Ok(acc)
+    std::iter::once(&data_len)
+        .chain(data.iter())
+        .rev()
+        .copied()
+        .reduce(|acc, x| pedersen_hash(&x, &acc))
+        // the chain has at least the length element
+        .unwrap()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{compute_hash_on_elements, pedersen_hash};
+    use super::{
+        compute_hash_chain_with_length_prefix, compute_hash_on_elements,
+        compute_hash_on_elements_strict, compute_hash_on_elements_with_initial,
+        compute_hash_on_elements_without_length, pedersen_hash, PedersenHashState,
+    };
+    use crate::error::Error;
     use ark_ff::field_new;
     use starknet_curve::Fq;
 
@@ -120,7 +213,7 @@ mod tests {
             "2811736568068244484902543134224269103996353337662770485859146392457932405098"
         );
 
-        let pedersen_h = compute_hash_on_elements(&data).unwrap();
+        let pedersen_h = compute_hash_on_elements(&data);
         assert_eq!(expected, pedersen_h)
     }
 
@@ -139,7 +232,97 @@ mod tests {
             "1129304730557028657400116705980657835953282252153007670503174589192316695406"
         );
 
-        let pedersen_h = compute_hash_on_elements(&data).unwrap();
+        let pedersen_h = compute_hash_on_elements(&data);
         assert_eq!(expected, pedersen_h)
     }
+
+    #[test]
+    fn test_hash_on_empty_data_with_cairo() {
+        // CAIRO: compute_hash_on_elements([]) -> 2089986280348253421170679821480865132823066470938446095505822317253594081284
+
+        let expected = field_new!(
+            Fq,
+            "2089986280348253421170679821480865132823066470938446095505822317253594081284"
+        );
+
+        assert_eq!(expected, compute_hash_on_elements(&[]));
+        assert_eq!(expected, pedersen_hash(&Fq::from(0u64), &Fq::from(0u64)));
+        assert_eq!(
+            Err(Error::EmptyDataError),
+            compute_hash_on_elements_strict(&[])
+        );
+    }
+
+    #[test]
+    fn test_hash_state_in_chunks() {
+        let data: Vec<Fq> = (1..=10u64).map(Fq::from).collect();
+
+        let mut state = PedersenHashState::new();
+        for chunk in data.chunks(3) {
+            state.update_many(chunk);
+        }
+
+        assert_eq!(compute_hash_on_elements(&data), state.finalize());
+    }
+
+    #[test]
+    fn test_hash_state_with_cairo() {
+        // same data as in test_hash_on_long_data, fed one by one
+        let mut state = PedersenHashState::new();
+        for x in [2u64, 4, 8, 16, 32] {
+            state.update(&Fq::from(x));
+        }
+
+        let expected = field_new!(
+            Fq,
+            "2811736568068244484902543134224269103996353337662770485859146392457932405098"
+        );
+
+        assert_eq!(expected, state.finalize())
+    }
+
+    #[test]
+    fn test_pedersen_hash_of_one_and_two() {
+        // CAIRO: pedersen_hash(1, 2) -> 0x5bb9440e27889a364bcb678b1f679ecd1347acdedcbf36e83494f857cc58026
+
+        let expected = field_new!(
+            Fq,
+            "2592987851775965742543459319508348457290966253241455514226127639100457844774"
+        );
+
+        assert_eq!(expected, pedersen_hash(&Fq::from(1u64), &Fq::from(2u64)))
+    }
+
+    #[test]
+    fn test_chain_variants() {
+        let data = vec![Fq::from(2u64), Fq::from(4u64), Fq::from(8u64)];
+        let initial = Fq::from(17u64);
+
+        assert_eq!(
+            compute_hash_on_elements(&data),
+            compute_hash_on_elements_with_initial(&Fq::from(0u64), &data)
+        );
+
+        let without_length = compute_hash_on_elements_without_length(&initial, &data);
+        assert_eq!(
+            pedersen_hash(
+                &pedersen_hash(&pedersen_hash(&initial, &data[0]), &data[1]),
+                &data[2]
+            ),
+            without_length
+        );
+        assert_eq!(
+            pedersen_hash(&without_length, &Fq::from(3u64)),
+            compute_hash_on_elements_with_initial(&initial, &data)
+        );
+
+        assert_eq!(
+            pedersen_hash(
+                &Fq::from(3u64),
+                &pedersen_hash(&data[0], &pedersen_hash(&data[1], &data[2]))
+            ),
+            compute_hash_chain_with_length_prefix(&data)
+        );
+        assert_eq!(Fq::from(0u64), compute_hash_chain_with_length_prefix(&[]));
+    }
 }
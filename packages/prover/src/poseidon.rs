@@ -0,0 +1,144 @@
+use crate::constants::{POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS, POSEIDON_ROUND_KEYS};
+
+use ark_ff::{Field, One, Zero};
+use starknet_curve::Fq;
+
+/// Applies the MDS matrix used by Starknet:
+/// ((3, 1, 1), (1, -1, 1), (1, 1, -2))
+fn mix(state: &mut [Fq; 3]) {
+    let t = state[0] + state[1] + state[2];
+    state[0] = t + state[0].double();
+    state[1] = t - state[1].double();
+    state[2] = t - Fq::from(3u64) * state[2];
+}
+
+fn round(state: &mut [Fq; 3], round_keys: &[Fq; 3], full: bool) {
+    for (s, k) in state.iter_mut().zip(round_keys.iter()) {
+        *s += k;
+    }
+
+    // in partial rounds the s-box (x^3) is only applied to the last element
+    let sboxed = if full { 0 } else { 2 };
+    for s in state.iter_mut().skip(sboxed) {
+        *s = *s * *s * *s;
+    }
+
+    mix(state);
+}
+
+/// Hades permutation with width 3, 8 full rounds and 83 partial rounds
+/// as used by the Poseidon hash in Starknet.
+pub fn poseidon_permute(state: &mut [Fq; 3]) {
+    let half_full = POSEIDON_FULL_ROUNDS / 2;
+    let partial_end = half_full + POSEIDON_PARTIAL_ROUNDS;
+
+    for (i, round_keys) in POSEIDON_ROUND_KEYS.iter().enumerate() {
+        round(state, round_keys, i < half_full || i >= partial_end);
+    }
+}
+
+/// Computes the Starknet Poseidon hash of x and y.
+pub fn poseidon_hash(x: &Fq, y: &Fq) -> Fq {
+    let mut state = [*x, *y, Fq::from(2u64)];
+    poseidon_permute(&mut state);
+
+    state[0]
+}
+
+/// Computes the Starknet Poseidon hash of a single element.
+pub fn poseidon_hash_single(x: &Fq) -> Fq {
+    let mut state = [*x, Fq::zero(), Fq::one()];
+    poseidon_permute(&mut state);
+
+    state[0]
+}
+
+/// Computes the Starknet Poseidon hash of an arbitrary number of elements.
+/// The data is padded with 1 (and a 0 if needed) to a multiple of the rate (2)
+/// and absorbed in pairs, as in cairo-lang `poseidon_hash_many`.
+pub fn poseidon_hash_many(data: &[Fq]) -> Fq {
+    let mut state = [Fq::zero(); 3];
+    let mut chunks = data.chunks_exact(2);
+
+    for chunk in chunks.by_ref() {
+        state[0] += chunk[0];
+        state[1] += chunk[1];
+        poseidon_permute(&mut state);
+    }
+
+    let remainder = chunks.remainder();
+    if let Some(last) = remainder.first() {
+        state[0] += last;
+    }
+    state[remainder.len()] += Fq::one();
+    poseidon_permute(&mut state);
+
+    state[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{poseidon_hash, poseidon_hash_many, poseidon_hash_single};
+    use ark_ff::field_new;
+    use starknet_curve::Fq;
+
+    #[test]
+    fn test_poseidon_hash_with_cairo() {
+        // CAIRO: poseidon_hash(x, y) with values generated from cairo-lang v0.11.0
+        let x = field_new!(
+            Fq,
+            "20140578132262368862000200556100848058304535795501751794007564626976013246"
+        );
+        let y = field_new!(
+            Fq,
+            "56341294147480832855401895867265471615027720793558289370730438999346154474"
+        );
+
+        let expected = field_new!(
+            Fq,
+            "3316817166671974741821520388381754819442969945697309900446133225793489435265"
+        );
+
+        assert_eq!(expected, poseidon_hash(&x, &y))
+    }
+
+    #[test]
+    fn test_poseidon_hash_single_with_cairo() {
+        let x = field_new!(
+            Fq,
+            "278591510285887603159181793564350634833177938525624845163565026004707200982"
+        );
+
+        let expected = field_new!(
+            Fq,
+            "484354348329425969185098251694037957896755481452651490986126884370226820181"
+        );
+
+        assert_eq!(expected, poseidon_hash_single(&x))
+    }
+
+    #[test]
+    fn test_poseidon_hash_many_with_cairo() {
+        let data = vec![
+            field_new!(
+                Fq,
+                "275553193911298658432997273666878041363444178246812643008696871314965068871"
+            ),
+            field_new!(
+                Fq,
+                "113411257837949962731516387807982602081163934831205381748096971248452002496"
+            ),
+            field_new!(
+                Fq,
+                "123958338326956447432561268205192637142644288135402780028800054817940537450"
+            ),
+        ];
+
+        let expected = field_new!(
+            Fq,
+            "869680094428371581891248672938089301918937027851598225485772221279358215415"
+        );
+
+        assert_eq!(expected, poseidon_hash_many(&data))
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    error::Error,
+    poseidon::poseidon_hash_many,
+    signature::{private_key_to_public_key, SigningParameters},
+    util::short_string_to_field,
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand, Zero};
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr};
+
+/*
+Linkable ring signatures (LSAG, https://eprint.iacr.org/2004/027) with Poseidon as the hash.
+
+A signature proves that the signer owns one of the public keys of the ring without telling which
+one. It carries the key image I = x * H_p(P), which only depends on the signer's key, so two
+signatures from the same key are linked, e.g. to reject a second vote.
+
+c_(i+1) = H(prefix, s_i*G + c_i*P_i, s_i*H_p(P_i) + c_i*I) around the ring, where the prefix is
+the hash of the ring, the key image and the message, and the signature is (c_0, s_0..s_(n-1), I).
+*/
+
+const HASH_TO_POINT_TAG: &str = "STARK_LSAG_HASH_TO_POINT";
+const PREFIX_TAG: &str = "STARK_LSAG_PREFIX";
+const CHALLENGE_TAG: &str = "STARK_LSAG_CHALLENGE";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingSignature {
+    pub c: Fr,
+    pub s: Vec<Fr>,
+    pub key_image: Affine,
+}
+
+impl RingSignature {
+    /// Both signatures were made with the same private key
+    pub fn is_linked(&self, other: &RingSignature) -> bool {
+        self.key_image == other.key_image
+    }
+}
+
+fn tagged_hash(tag: &str, data: &[Fq]) -> Fq {
+    // it's safe to unwrap, tags are short ascii strings
+    let mut input = vec![short_string_to_field(tag).unwrap()];
+    input.extend_from_slice(data);
+
+    poseidon_hash_many(&input)
+}
+
+/// H_p, hashes the point together with a counter until the result is the x coordinate of a point
+pub fn hash_to_point(point: &Affine) -> Affine {
+    let mut counter = 0u64;
+    loop {
+        let x = tagged_hash(HASH_TO_POINT_TAG, &[point.x, point.y, Fq::from(counter)]);
+        if let Some(point) = Affine::get_point_from_x(x, false) {
+            break point;
+        }
+
+        counter += 1;
+    }
+}
+
+pub fn key_image(parameters: &SigningParameters, priv_key: &Fr) -> Affine {
+    let pub_key = private_key_to_public_key(parameters, *priv_key).into_affine();
+    hash_to_point(&pub_key)
+        .mul(priv_key.into_repr())
+        .into_affine()
+}
+
+fn prefix(ring: &[Affine], key_image: &Affine, msg_hash: &Fq) -> Fq {
+    let mut data: Vec<Fq> = ring.iter().flat_map(|point| [point.x, point.y]).collect();
+    data.extend_from_slice(&[key_image.x, key_image.y, *msg_hash]);
+
+    tagged_hash(PREFIX_TAG, &data)
+}
+
+fn challenge(prefix: &Fq, l: &Affine, r: &Affine) -> Fr {
+    let c = tagged_hash(CHALLENGE_TAG, &[*prefix, l.x, l.y, r.x, r.y]);
+    Fr::from_le_bytes_mod_order(&c.into_repr().to_bytes_le())
+}
+
+/// The signer's public key must be in the ring
+pub fn sign<R: Rng>(
+    parameters: &SigningParameters,
+    ring: &[Affine],
+    priv_key: Fr,
+    msg_hash: Fq,
+    rng: &mut R,
+) -> Result<RingSignature, Error> {
+    if ring.is_empty() {
+        return Err(Error::EmptyDataError);
+    }
+    if priv_key.is_zero() {
+        return Err(Error::InvalidPrivateKey);
+    }
+
+    let pub_key = private_key_to_public_key(parameters, priv_key).into_affine();
+    let index = ring
+        .iter()
+        .position(|point| *point == pub_key)
+        .ok_or(Error::InvalidPublicKey)?;
+
+    let n = ring.len();
+    let key_image = key_image(parameters, &priv_key);
+    let prefix = prefix(ring, &key_image, &msg_hash);
+
+    let alpha = Fr::rand(rng);
+    let mut c = vec![Fr::zero(); n];
+    let mut s: Vec<Fr> = (0..n).map(|_| Fr::rand(rng)).collect();
+
+    c[(index + 1) % n] = challenge(
+        &prefix,
+        &parameters.generator.mul(alpha.into_repr()).into_affine(),
+        &hash_to_point(&pub_key).mul(alpha.into_repr()).into_affine(),
+    );
+
+    for offset in 1..n {
+        let i = (index + offset) % n;
+        let (l, r) = commitments(parameters, &ring[i], &key_image, &c[i], &s[i]);
+        c[(i + 1) % n] = challenge(&prefix, &l, &r);
+    }
+
+    s[index] = alpha - c[index] * priv_key;
+
+    Ok(RingSignature {
+        c: c[0],
+        s,
+        key_image,
+    })
+}
+
+/// s*G + c*P and s*H_p(P) + c*I
+fn commitments(
+    parameters: &SigningParameters,
+    point: &Affine,
+    key_image: &Affine,
+    c: &Fr,
+    s: &Fr,
+) -> (Affine, Affine) {
+    let l = parameters.generator.mul(s.into_repr()) + point.mul(c.into_repr());
+    let r = hash_to_point(point).mul(s.into_repr()) + key_image.mul(c.into_repr());
+
+    (l.into_affine(), r.into_affine())
+}
+
+pub fn verify(
+    parameters: &SigningParameters,
+    ring: &[Affine],
+    msg_hash: &Fq,
+    signature: &RingSignature,
+) -> bool {
+    if ring.is_empty() || ring.len() != signature.s.len() {
+        return false;
+    }
+    if ring
+        .iter()
+        .any(|point| point.is_zero() || !point.is_on_curve())
+    {
+        return false;
+    }
+    let key_image = &signature.key_image;
+    if key_image.is_zero() || !key_image.is_on_curve() {
+        return false;
+    }
+
+    let prefix = prefix(ring, key_image, msg_hash);
+    let c = ring
+        .iter()
+        .zip(signature.s.iter())
+        .fold(signature.c, |c, (point, s)| {
+            let (l, r) = commitments(parameters, point, key_image, &c, s);
+            challenge(&prefix, &l, &r)
+        });
+
+    c == signature.c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{key_image, sign, verify};
+    use crate::error::Error;
+    use crate::signature::{parameters, private_key_to_public_key};
+    use ark_ec::ProjectiveCurve;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Affine, Fq, Fr};
+
+    #[test]
+    fn test_sign_and_verify() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_keys: Vec<Fr> = (0..5).map(|_| Fr::rand(rng)).collect();
+        let ring: Vec<Affine> = private_keys
+            .iter()
+            .map(|key| private_key_to_public_key(&parameters, *key).into_affine())
+            .collect();
+        let msg_hash = Fq::from(1u64);
+
+        for private_key in private_keys.iter() {
+            let signature = sign(&parameters, &ring, *private_key, msg_hash, rng).unwrap();
+            assert!(verify(&parameters, &ring, &msg_hash, &signature));
+            assert_eq!(key_image(&parameters, private_key), signature.key_image);
+
+            assert!(!verify(&parameters, &ring, &Fq::from(2u64), &signature));
+            assert!(!verify(&parameters, &ring[1..], &msg_hash, &signature));
+
+            let mut other_ring = ring.clone();
+            other_ring.swap(0, 1);
+            assert!(!verify(&parameters, &other_ring, &msg_hash, &signature));
+        }
+
+        // a ring of one key is a signature
+        let signature = sign(&parameters, &ring[..1], private_keys[0], msg_hash, rng).unwrap();
+        assert!(verify(&parameters, &ring[..1], &msg_hash, &signature));
+
+        assert_eq!(
+            Some(Error::InvalidPublicKey),
+            sign(&parameters, &ring[1..], private_keys[0], msg_hash, rng).err()
+        );
+    }
+
+    #[test]
+    fn test_linkability() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_keys: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+        let ring: Vec<Affine> = private_keys
+            .iter()
+            .map(|key| private_key_to_public_key(&parameters, *key).into_affine())
+            .collect();
+
+        let first = sign(&parameters, &ring, private_keys[1], Fq::from(1u64), rng).unwrap();
+        let second = sign(&parameters, &ring, private_keys[1], Fq::from(2u64), rng).unwrap();
+        let other = sign(&parameters, &ring, private_keys[2], Fq::from(1u64), rng).unwrap();
+
+        assert!(first.is_linked(&second));
+        assert!(!first.is_linked(&other));
+
+        // signatures are randomized, only the key image is the same
+        assert_ne!(
+            first,
+            sign(&parameters, &ring, private_keys[1], Fq::from(1u64), rng).unwrap()
+        );
+    }
+}
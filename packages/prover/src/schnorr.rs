@@ -0,0 +1,307 @@
+use crate::{
+    error::Error,
+    hash::HashFunction,
+    rfc6979::generate_k_rfc6979,
+    signature::{private_key_to_public_key, SigningParameters},
+    util::short_string_to_field,
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, FpParameters, PrimeField, UniformRand, Zero};
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr, Projective};
+
+/*
+BIP340 style Schnorr signatures with x only public keys, so that the stark key is the public key
+
+P = x*G and R = k*G are taken with an even y coordinate, negating x or k when needed
+e = H(R.x, P.x, msg_hash) mod EC_ORDER, where H is compute_hash_on_elements or poseidon_hash_many
+s = k + e*x
+
+A signature (r, s) is valid if R' = s*G - e*P is not the point at infinity, R'.y is even and R'.x = r
+*/
+
+// domain separator of the nonces, so that they never collide with the ECDSA ones of the same message
+const NONCE_TAG: &str = "STARK_SCHNORR_NONCE";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SchnorrSignature {
+    pub r: Fq,
+    pub s: Fr,
+}
+
+/// The point with x coordinate `x` and an even y coordinate, if there is one
+pub fn lift_x(x: &Fq) -> Option<Affine> {
+    let point = Affine::get_point_from_x(*x, false)?;
+    if point.y.into_repr().is_odd() {
+        Some(-point)
+    } else {
+        Some(point)
+    }
+}
+
+pub fn challenge(hasher: HashFunction, r: &Fq, pub_key: &Fq, msg_hash: &Fq) -> Fr {
+    let e = hasher.hash_many(&[*r, *pub_key, *msg_hash]);
+    // Fq is bigger than Fr, cairo's ec_mul reduces the scalar the same way
+    Fr::from_le_bytes_mod_order(&e.into_repr().to_bytes_le())
+}
+
+pub fn sign(
+    parameters: &SigningParameters,
+    priv_key: Fr,
+    msg_hash: Fq,
+    hasher: HashFunction,
+) -> Result<SchnorrSignature, Error> {
+    if priv_key.is_zero() {
+        return Err(Error::InvalidPrivateKey);
+    }
+
+    let pub_key = private_key_to_public_key(parameters, priv_key).into_affine();
+    let priv_key = if pub_key.y.into_repr().is_odd() {
+        -priv_key
+    } else {
+        priv_key
+    };
+
+    // it's safe to unwrap, the tag is a short ascii string
+    let tag: Fq = short_string_to_field(NONCE_TAG).unwrap();
+    let nonce_msg = hasher.hash_many(&[tag, msg_hash]);
+    let k = generate_k_rfc6979(
+        &starknet_curve::FrParameters::MODULUS,
+        &priv_key,
+        &Fr::from_le_bytes_mod_order(&nonce_msg.into_repr().to_bytes_le()),
+        None,
+    );
+
+    // k is never zero so R is never the point at infinity
+    let nonce_point = parameters.generator.mul(k.into_repr()).into_affine();
+    let k = if nonce_point.y.into_repr().is_odd() {
+        -k
+    } else {
+        k
+    };
+
+    let e = challenge(hasher, &nonce_point.x, &pub_key.x, &msg_hash);
+
+    Ok(SchnorrSignature {
+        r: nonce_point.x,
+        s: k + e * priv_key,
+    })
+}
+
+/// Verifies the signature of msg_hash against the stark key, that is the x coordinate of the public key
+pub fn verify(
+    parameters: &SigningParameters,
+    pub_key: &Fq,
+    msg_hash: &Fq,
+    signature: &SchnorrSignature,
+    hasher: HashFunction,
+) -> bool {
+    let point = match lift_x(pub_key) {
+        Some(point) => point,
+        None => return false,
+    };
+
+    let e = challenge(hasher, &signature.r, pub_key, msg_hash);
+    let nonce_point = (parameters.generator.mul(signature.s.into_repr())
+        - point.mul(e.into_repr()))
+    .into_affine();
+
+    !nonce_point.is_zero() && !nonce_point.y.into_repr().is_odd() && nonce_point.x == signature.r
+}
+
+/// Checks sum(a_i * s_i) * G = sum(a_i * R_i) + sum(a_i * e_i * P_i) with random weights a_i,
+/// which is faster than verifying each signature but doesn't tell which one is invalid
+pub fn batch_verify<R: Rng>(
+    parameters: &SigningParameters,
+    pub_keys: &[Fq],
+    msg_hashes: &[Fq],
+    signatures: &[SchnorrSignature],
+    hasher: HashFunction,
+    rng: &mut R,
+) -> Result<bool, Error> {
+    if pub_keys.len() != msg_hashes.len() || pub_keys.len() != signatures.len() {
+        return Err(Error::IncorrectLenError);
+    }
+
+    let mut s_sum = Fr::zero();
+    let mut sum = Projective::zero();
+    for (i, ((pub_key, msg_hash), signature)) in pub_keys
+        .iter()
+        .zip(msg_hashes.iter())
+        .zip(signatures.iter())
+        .enumerate()
+    {
+        let (point, nonce_point) = match (lift_x(pub_key), lift_x(&signature.r)) {
+            (Some(point), Some(nonce_point)) => (point, nonce_point),
+            _ => return Ok(false),
+        };
+
+        // the first weight can be one without loss of security
+        let a = if i == 0 {
+            Fr::from(1u64)
+        } else {
+            Fr::rand(rng)
+        };
+        let e = challenge(hasher, &signature.r, pub_key, msg_hash);
+
+        s_sum += a * signature.s;
+        sum += nonce_point.mul(a.into_repr()) + point.mul((a * e).into_repr());
+    }
+
+    Ok(parameters.generator.mul(s_sum.into_repr()) == sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_verify, challenge, lift_x, sign, verify, SchnorrSignature};
+    use crate::error::Error;
+    use crate::hash::HashFunction;
+    use crate::signature::{parameters, private_key_to_public_key};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    #[test]
+    fn test_sign_and_verify() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        for hasher in [HashFunction::Pedersen, HashFunction::Poseidon] {
+            for _ in 0..4 {
+                let private_key = Fr::rand(rng);
+                let stark_key = private_key_to_public_key(&parameters, private_key)
+                    .into_affine()
+                    .x;
+                let msg_hash = Fq::rand(rng);
+
+                let sig = sign(&parameters, private_key, msg_hash, hasher).unwrap();
+                assert!(verify(&parameters, &stark_key, &msg_hash, &sig, hasher));
+
+                // nonces are deterministic
+                assert_eq!(
+                    sig,
+                    sign(&parameters, private_key, msg_hash, hasher).unwrap()
+                );
+
+                let other_hasher = match hasher {
+                    HashFunction::Pedersen => HashFunction::Poseidon,
+                    HashFunction::Poseidon => HashFunction::Pedersen,
+                };
+                assert!(!verify(
+                    &parameters,
+                    &stark_key,
+                    &msg_hash,
+                    &sig,
+                    other_hasher
+                ));
+
+                let wrong_hash = msg_hash + Fq::from(1u64);
+                assert!(!verify(&parameters, &stark_key, &wrong_hash, &sig, hasher));
+
+                let wrong_sig = SchnorrSignature {
+                    r: sig.r,
+                    s: sig.s + Fr::from(1u64),
+                };
+                assert!(!verify(
+                    &parameters,
+                    &stark_key,
+                    &msg_hash,
+                    &wrong_sig,
+                    hasher
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_even_nonce_point() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let stark_key = private_key_to_public_key(&parameters, private_key)
+            .into_affine()
+            .x;
+        let msg_hash = Fq::rand(rng);
+        let sig = sign(&parameters, private_key, msg_hash, HashFunction::Poseidon).unwrap();
+
+        // R = s*G - e*P is the lift of r
+        let e = challenge(HashFunction::Poseidon, &sig.r, &stark_key, &msg_hash);
+        let nonce_point = (parameters.generator.mul(sig.s.into_repr())
+            - lift_x(&stark_key).unwrap().mul(e.into_repr()))
+        .into_affine();
+        assert_eq!(lift_x(&sig.r).unwrap(), nonce_point);
+        assert!(!nonce_point.y.into_repr().is_odd());
+
+        assert_eq!(
+            Some(Error::InvalidPrivateKey),
+            sign(
+                &parameters,
+                Fr::from(0u64),
+                msg_hash,
+                HashFunction::Poseidon
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+        let hasher = HashFunction::Pedersen;
+
+        let mut pub_keys = vec![];
+        let mut msg_hashes = vec![];
+        let mut signatures = vec![];
+        for _ in 0..5 {
+            let private_key = Fr::rand(rng);
+            let msg_hash = Fq::rand(rng);
+
+            pub_keys.push(
+                private_key_to_public_key(&parameters, private_key)
+                    .into_affine()
+                    .x,
+            );
+            msg_hashes.push(msg_hash);
+            signatures.push(sign(&parameters, private_key, msg_hash, hasher).unwrap());
+        }
+
+        assert!(batch_verify(
+            &parameters,
+            &pub_keys,
+            &msg_hashes,
+            &signatures,
+            hasher,
+            rng
+        )
+        .unwrap());
+
+        msg_hashes.swap(1, 2);
+        assert!(!batch_verify(
+            &parameters,
+            &pub_keys,
+            &msg_hashes,
+            &signatures,
+            hasher,
+            rng
+        )
+        .unwrap());
+
+        assert_eq!(
+            Some(Error::IncorrectLenError),
+            batch_verify(
+                &parameters,
+                &pub_keys[1..],
+                &msg_hashes,
+                &signatures,
+                hasher,
+                rng
+            )
+            .err()
+        );
+    }
+}
@@ -0,0 +1,486 @@
+use crate::{
+    error::Error,
+    keccak::get_selector_from_name,
+    poseidon::{poseidon_hash, poseidon_hash_many},
+    signature::{private_key_to_public_key, sign, verify, Signature, SigningParameters},
+    typed_data::{
+        merkle_proof, merkle_root, string_hash, struct_hash, type_hash, Revision, StarknetDomain,
+    },
+    util::{field_to_hex, short_string_to_field},
+};
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use num_bigint_dig::BigUint;
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr};
+
+/*
+Session keys in the format of Argent's session accounts
+(https://github.com/argentlabs/argent-contracts-starknet/blob/main/docs/session_keys.md).
+
+The owner signs a SNIP-12 revision 1 message that authorizes an ephemeral key until an expiry, for
+a merkle tree of allowed (contract, selector) pairs and with a metadata string, where the spending
+caps are the txFees of the metadata json. The session key then signs
+    poseidon_hash_many([transaction hash, session message hash, cache authorization])
+for every transaction it sends on the owner's behalf.
+*/
+
+const SESSION_TYPE: &str = "\"Session\"(\"Expires At\":\"timestamp\",\"Allowed Methods\":\"merkletree\",\"Metadata\":\"string\",\"Session Key\":\"felt\")";
+const ALLOWED_METHOD_TYPE: &str =
+    "\"Allowed Method\"(\"Contract Address\":\"ContractAddress\",\"selector\":\"selector\")";
+
+const DOMAIN_NAME: &str = "SessionAccount.session";
+// the short string '1' of the account contract, i.e. 0x31 and not the 0x1 that starknet.js
+// encodes for "1", see StarknetDomain::new
+const DOMAIN_VERSION: &str = "1";
+const STARKNET_SIGNER: &str = "Starknet Signer";
+
+const REVISION: Revision = Revision::V1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AllowedMethod {
+    pub contract_address: Fq,
+    pub selector: Fq,
+}
+
+impl AllowedMethod {
+    pub fn new(contract_address: Fq, entry_point: &str) -> Result<Self, Error> {
+        Ok(Self {
+            contract_address,
+            selector: get_selector_from_name(entry_point)?,
+        })
+    }
+
+    /// Leaf of the allowed methods merkle tree
+    pub fn hash(&self) -> Fq {
+        struct_hash(
+            REVISION,
+            &type_hash(ALLOWED_METHOD_TYPE),
+            &[self.contract_address, self.selector],
+        )
+    }
+}
+
+/// Maximum amount of a token that the session can spend on fees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpendingLimit {
+    pub token_address: Fq,
+    pub max_amount: Fq,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    /// Unix timestamp in seconds
+    pub expires_at: u64,
+    pub allowed_methods: Vec<AllowedMethod>,
+    pub project_id: String,
+    pub spending_limits: Vec<SpendingLimit>,
+    /// x coordinate of the session public key
+    pub session_key: Fq,
+}
+
+/// The guid of a stark key in Argent accounts, the two to one poseidon hash of the signer type
+/// and the key
+pub fn session_key_guid(public_key: &Fq) -> Fq {
+    // it's safe to unwrap, the tag is a short ascii string
+    poseidon_hash(&short_string_to_field(STARKNET_SIGNER).unwrap(), public_key)
+}
+
+/// Ephemeral key pair, the private key should never leave the session
+pub fn new_session_key<R: Rng>(parameters: &SigningParameters, rng: &mut R) -> (Fr, Affine) {
+    let private_key = Fr::rand(rng);
+    let public_key = private_key_to_public_key(parameters, private_key).into_affine();
+
+    (private_key, public_key)
+}
+
+fn escape_json(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn to_decimal(value: &Fq) -> String {
+    BigUint::from_bytes_le(&value.into_repr().to_bytes_le()).to_string()
+}
+
+impl Session {
+    /// {"projectID":"...","txFees":[{"tokenAddress":"0x...","maxAmount":"..."}]}
+    pub fn metadata(&self) -> String {
+        let tx_fees: Vec<String> = self
+            .spending_limits
+            .iter()
+            .map(|limit| {
+                format!(
+                    "{{\"tokenAddress\":\"{}\",\"maxAmount\":\"{}\"}}",
+                    field_to_hex(&limit.token_address),
+                    to_decimal(&limit.max_amount)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"projectID\":\"{}\",\"txFees\":[{}]}}",
+            escape_json(&self.project_id),
+            tx_fees.join(",")
+        )
+    }
+
+    fn leaves(&self) -> Vec<Fq> {
+        self.allowed_methods
+            .iter()
+            .map(|method| method.hash())
+            .collect()
+    }
+
+    pub fn allowed_methods_root(&self) -> Result<Fq, Error> {
+        merkle_root(&self.leaves(), REVISION)
+    }
+
+    /// Proof of the method for the session token, None if the method is not allowed
+    pub fn allowed_method_proof(&self, method: &AllowedMethod) -> Result<Option<Vec<Fq>>, Error> {
+        merkle_proof(&self.leaves(), &method.hash(), REVISION)
+    }
+
+    /// The session as the account's Session struct:
+    /// [expires_at, allowed_methods_root, metadata_hash, session_key_guid]
+    pub fn to_felts(&self) -> Result<[Fq; 4], Error> {
+        Ok([
+            Fq::from(self.expires_at),
+            self.allowed_methods_root()?,
+            string_hash(&self.metadata()),
+            session_key_guid(&self.session_key),
+        ])
+    }
+
+    pub fn hash(&self) -> Result<Fq, Error> {
+        Ok(struct_hash(
+            REVISION,
+            &type_hash(SESSION_TYPE),
+            &self.to_felts()?,
+        ))
+    }
+
+    /// The SNIP-12 message that the owner signs
+    pub fn message_hash(&self, chain_id: &Fq, account: &Fq) -> Result<Fq, Error> {
+        let domain = StarknetDomain {
+            // it's safe to unwrap, the name and the version are short ascii strings
+            name: short_string_to_field(DOMAIN_NAME).unwrap(),
+            version: short_string_to_field(DOMAIN_VERSION).unwrap(),
+            chain_id: *chain_id,
+            revision: REVISION,
+        };
+
+        Ok(domain.message_hash(account, &self.hash()?))
+    }
+
+    pub fn allows(&self, contract_address: &Fq, selector: &Fq, timestamp: u64) -> bool {
+        timestamp < self.expires_at
+            && self.allowed_methods.iter().any(|method| {
+                method.contract_address == *contract_address && method.selector == *selector
+            })
+    }
+}
+
+/// Message that the session key signs for a transaction
+pub fn transaction_message_hash(
+    session_message_hash: &Fq,
+    transaction_hash: &Fq,
+    cache_authorization: bool,
+) -> Fq {
+    poseidon_hash_many(&[
+        *transaction_hash,
+        *session_message_hash,
+        Fq::from(cache_authorization as u64),
+    ])
+}
+
+/// The owner's signature of the session
+pub fn authorize(
+    parameters: &SigningParameters,
+    owner_key: Fr,
+    session: &Session,
+    chain_id: &Fq,
+    account: &Fq,
+) -> Result<Signature, Error> {
+    sign(
+        parameters,
+        owner_key,
+        session.message_hash(chain_id, account)?,
+        None,
+    )
+}
+
+pub fn verify_authorization(
+    parameters: &SigningParameters,
+    owner_key: &Affine,
+    session: &Session,
+    chain_id: &Fq,
+    account: &Fq,
+    signature: &Signature,
+) -> Result<bool, Error> {
+    let msg_hash = session.message_hash(chain_id, account)?;
+    Ok(verify(parameters, owner_key, &msg_hash, signature))
+}
+
+/// The session key's signature of a transaction, the session key must be the one of the session
+pub fn sign_transaction(
+    parameters: &SigningParameters,
+    session_private_key: Fr,
+    session: &Session,
+    chain_id: &Fq,
+    account: &Fq,
+    transaction_hash: &Fq,
+    cache_authorization: bool,
+) -> Result<Signature, Error> {
+    let public_key = private_key_to_public_key(parameters, session_private_key).into_affine();
+    if public_key.x != session.session_key {
+        return Err(Error::InvalidPrivateKey);
+    }
+
+    let msg_hash = transaction_message_hash(
+        &session.message_hash(chain_id, account)?,
+        transaction_hash,
+        cache_authorization,
+    );
+    sign(parameters, session_private_key, msg_hash, None)
+}
+
+pub fn verify_transaction(
+    parameters: &SigningParameters,
+    session: &Session,
+    chain_id: &Fq,
+    account: &Fq,
+    transaction_hash: &Fq,
+    cache_authorization: bool,
+    signature: &Signature,
+) -> Result<bool, Error> {
+    let session_key = match Affine::get_point_from_x(session.session_key, false) {
+        Some(point) => point,
+        None => return Err(Error::InvalidPublicKey),
+    };
+    let msg_hash = transaction_message_hash(
+        &session.message_hash(chain_id, account)?,
+        transaction_hash,
+        cache_authorization,
+    );
+
+    // stark keys are only x coordinates, either point is the session key
+    Ok(verify(parameters, &session_key, &msg_hash, signature)
+        || verify(parameters, &-session_key, &msg_hash, signature))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        authorize, new_session_key, session_key_guid, sign_transaction, verify_authorization,
+        verify_transaction, AllowedMethod, Session, SpendingLimit, SESSION_TYPE,
+    };
+    use crate::error::Error;
+    use crate::poseidon::{poseidon_hash, poseidon_hash_many};
+    use crate::signature::{parameters, private_key_to_public_key};
+    use crate::typed_data::{string_hash, type_hash, verify_merkle_proof, Revision};
+    use crate::util::{hex_to_field, short_string_to_field};
+    use ark_ec::ProjectiveCurve;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    fn session(session_key: Fq) -> Session {
+        let game: Fq = hex_to_field("0x1234").unwrap();
+        Session {
+            expires_at: 1_700_000_000,
+            allowed_methods: vec![
+                AllowedMethod::new(game, "move").unwrap(),
+                AllowedMethod::new(game, "attack").unwrap(),
+                AllowedMethod::new(hex_to_field("0x5678").unwrap(), "claim").unwrap(),
+            ],
+            project_id: "game".to_string(),
+            spending_limits: vec![SpendingLimit {
+                token_address: hex_to_field(
+                    "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                )
+                .unwrap(),
+                max_amount: Fq::from(1_000_000_000_000_000u64),
+            }],
+            session_key,
+        }
+    }
+
+    #[test]
+    fn test_session_data() {
+        let session = session(Fq::from(1u64));
+        assert_eq!(
+            "{\"projectID\":\"game\",\"txFees\":[{\"tokenAddress\":\"0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d\",\"maxAmount\":\"1000000000000000\"}]}",
+            session.metadata()
+        );
+
+        let root = session.allowed_methods_root().unwrap();
+        for method in session.allowed_methods.iter() {
+            let proof = session.allowed_method_proof(method).unwrap().unwrap();
+            assert!(verify_merkle_proof(
+                &root,
+                &method.hash(),
+                &proof,
+                Revision::V1
+            ));
+        }
+
+        let method = session.allowed_methods[0];
+        assert!(session.allows(&method.contract_address, &method.selector, 1_600_000_000));
+        assert!(!session.allows(&method.contract_address, &method.selector, 1_700_000_000));
+        let other = AllowedMethod::new(method.contract_address, "claim").unwrap();
+        assert!(!session.allows(&other.contract_address, &other.selector, 1_600_000_000));
+        assert_eq!(None, session.allowed_method_proof(&other).unwrap());
+
+        let empty = Session {
+            allowed_methods: vec![],
+            ..session
+        };
+        assert_eq!(Err(Error::EmptyDataError), empty.hash());
+    }
+
+    #[test]
+    fn test_session_hash() {
+        let word = |value: &str| short_string_to_field::<Fq>(value).unwrap();
+        let session = session(Fq::from(1u64));
+
+        // the guid is the two to one hash, not poseidon_hash_many of the pair
+        let guid = session_key_guid(&session.session_key);
+        assert_eq!(
+            poseidon_hash(&word("Starknet Signer"), &Fq::from(1u64)),
+            guid
+        );
+        assert_ne!(
+            poseidon_hash_many(&[word("Starknet Signer"), Fq::from(1u64)]),
+            guid
+        );
+
+        let session_hash = poseidon_hash_many(&[
+            type_hash(SESSION_TYPE),
+            Fq::from(1_700_000_000u64),
+            session.allowed_methods_root().unwrap(),
+            string_hash(&session.metadata()),
+            guid,
+        ]);
+        assert_eq!(session_hash, session.hash().unwrap());
+
+        let chain_id = word("SN_SEPOLIA");
+        // the revision 1 domain type hash of SNIP-12
+        let domain_hash = poseidon_hash_many(&[
+            hex_to_field("0x1ff2f602e42168014d405a94f75e8a93d640751d71d16311266e140d8b0a210")
+                .unwrap(),
+            word("SessionAccount.session"),
+            Fq::from(0x31u64),
+            chain_id,
+            Fq::from(1u64),
+        ]);
+        assert_eq!(
+            poseidon_hash_many(&[
+                word("StarkNet Message"),
+                domain_hash,
+                Fq::from(0xaccu64),
+                session_hash
+            ]),
+            session
+                .message_hash(&chain_id, &Fq::from(0xaccu64))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_authorize_and_sign() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let owner_key = Fr::rand(rng);
+        let owner_public_key = private_key_to_public_key(&parameters, owner_key).into_affine();
+        let (session_private_key, session_public_key) = new_session_key(&parameters, rng);
+
+        let session = session(session_public_key.x);
+        let chain_id = short_string_to_field("SN_SEPOLIA").unwrap();
+        let account = Fq::from(0xaccu64);
+
+        let authorization =
+            authorize(&parameters, owner_key, &session, &chain_id, &account).unwrap();
+        assert!(verify_authorization(
+            &parameters,
+            &owner_public_key,
+            &session,
+            &chain_id,
+            &account,
+            &authorization
+        )
+        .unwrap());
+        assert!(!verify_authorization(
+            &parameters,
+            &owner_public_key,
+            &session,
+            &chain_id,
+            &Fq::from(1u64),
+            &authorization
+        )
+        .unwrap());
+
+        let tx_hash = Fq::from(0x7777u64);
+        let signature = sign_transaction(
+            &parameters,
+            session_private_key,
+            &session,
+            &chain_id,
+            &account,
+            &tx_hash,
+            false,
+        )
+        .unwrap();
+        assert!(verify_transaction(
+            &parameters,
+            &session,
+            &chain_id,
+            &account,
+            &tx_hash,
+            false,
+            &signature
+        )
+        .unwrap());
+        assert!(!verify_transaction(
+            &parameters,
+            &session,
+            &chain_id,
+            &account,
+            &tx_hash,
+            true,
+            &signature
+        )
+        .unwrap());
+
+        // the signature references the session, a longer session is another authorization
+        let extended = Session {
+            expires_at: session.expires_at + 1,
+            ..session.clone()
+        };
+        assert!(!verify_transaction(
+            &parameters,
+            &extended,
+            &chain_id,
+            &account,
+            &tx_hash,
+            false,
+            &signature
+        )
+        .unwrap());
+
+        assert_eq!(
+            Some(Error::InvalidPrivateKey),
+            sign_transaction(
+                &parameters,
+                owner_key,
+                &session,
+                &chain_id,
+                &account,
+                &tx_hash,
+                false
+            )
+            .err()
+        );
+    }
+}
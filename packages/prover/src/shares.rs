@@ -0,0 +1,274 @@
+use crate::{
+    error::Error,
+    signature::{private_key_to_public_key, SigningParameters},
+};
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use rand::Rng;
+use starknet_curve::{Affine, Fr, Projective};
+
+/*
+Shamir secret sharing of private keys with Feldman commitments.
+
+The secret is the constant term of a random polynomial f of degree threshold - 1, share i is f(i).
+The commitment to f is [a_0 * G, a_1 * G, ...], its first point is the public key of the secret,
+so a share holder can check that its share belongs to a given account without learning anything
+else: f(i) * G = sum(a_k * G * i^k)
+*/
+
+/// f(x) = coefficients[0] + coefficients[1] * x + ...
+pub fn evaluate_polynomial(coefficients: &[Fr], x: &Fr) -> Fr {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |acc, coefficient| acc * x + coefficient)
+}
+
+/// The commitment to f(x), sum(C_k * x^k) where C_k are the commitments to the coefficients
+pub fn evaluate_commitment(commitment: &[Affine], x: &Fr) -> Projective {
+    commitment.iter().rev().fold(Projective::zero(), |acc, c| {
+        acc.mul(x.into_repr()).add_mixed(c)
+    })
+}
+
+/// Coefficient of f(x_i) when interpolating f(0) from the points with the given x coordinates
+pub fn lagrange_coefficient(x_i: &Fr, xs: &[Fr]) -> Result<Fr, Error> {
+    let mut numerator = Fr::one();
+    let mut denominator = Fr::one();
+    for x_j in xs.iter().filter(|x_j| *x_j != x_i) {
+        numerator *= x_j;
+        denominator *= *x_j - x_i;
+    }
+
+    Ok(numerator * denominator.inverse().ok_or(Error::InvalidShare)?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Share {
+    pub index: u64,
+    pub value: Fr,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct FeldmanCommitment(pub Vec<Affine>);
+
+impl FeldmanCommitment {
+    /// Same as private_key_to_public_key of the shared secret
+    pub fn public_key(&self) -> Option<Affine> {
+        self.0.first().copied()
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn verify_share(&self, parameters: &SigningParameters, share: &Share) -> bool {
+        share.index != 0
+            && !self.0.is_empty()
+            && parameters.generator.mul(share.value.into_repr())
+                == evaluate_commitment(&self.0, &Fr::from(share.index))
+    }
+}
+
+/// Splits the secret into n shares with indices 1..=n, any threshold of them recover it
+pub fn split<R: Rng>(
+    parameters: &SigningParameters,
+    secret: Fr,
+    threshold: u64,
+    n: u64,
+    rng: &mut R,
+) -> Result<(Vec<Share>, FeldmanCommitment), Error> {
+    if threshold == 0 || threshold > n {
+        return Err(Error::InvalidThreshold);
+    }
+
+    let mut coefficients = vec![secret];
+    coefficients.extend((1..threshold).map(|_| Fr::rand(rng)));
+
+    let shares = (1..=n)
+        .map(|index| Share {
+            index,
+            value: evaluate_polynomial(&coefficients, &Fr::from(index)),
+        })
+        .collect();
+
+    let commitment = coefficients
+        .iter()
+        .map(|coefficient| private_key_to_public_key(parameters, *coefficient).into_affine())
+        .collect();
+
+    Ok((shares, FeldmanCommitment(commitment)))
+}
+
+/// Interpolates the secret, the result is only correct with at least threshold distinct shares
+pub fn reconstruct(shares: &[Share]) -> Result<Fr, Error> {
+    if shares.is_empty() {
+        return Err(Error::EmptyDataError);
+    }
+
+    let mut indices: Vec<u64> = shares.iter().map(|share| share.index).collect();
+    indices.sort_unstable();
+    if indices[0] == 0 || indices.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(Error::InvalidShare);
+    }
+
+    let xs: Vec<Fr> = shares.iter().map(|share| Fr::from(share.index)).collect();
+    shares
+        .iter()
+        .zip(xs.iter())
+        .try_fold(Fr::zero(), |acc, (share, x)| {
+            Ok(acc + lagrange_coefficient(x, &xs)? * share.value)
+        })
+}
+
+/// Checks every share against the commitment before reconstructing the secret, and the secret
+/// against the public key of the commitment
+pub fn reconstruct_verified(
+    parameters: &SigningParameters,
+    shares: &[Share],
+    commitment: &FeldmanCommitment,
+) -> Result<Fr, Error> {
+    if shares.len() < commitment.threshold() {
+        return Err(Error::InvalidThreshold);
+    }
+    if !shares
+        .iter()
+        .all(|share| commitment.verify_share(parameters, share))
+    {
+        return Err(Error::InvalidShare);
+    }
+
+    let secret = reconstruct(shares)?;
+    if Some(private_key_to_public_key(parameters, secret).into_affine()) != commitment.public_key()
+    {
+        return Err(Error::InvalidPrivateKey);
+    }
+
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        evaluate_polynomial, lagrange_coefficient, reconstruct, reconstruct_verified, split,
+        FeldmanCommitment, Share,
+    };
+    use crate::error::Error;
+    use crate::signature::{parameters, private_key_to_public_key};
+    use ark_ec::ProjectiveCurve;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::Fr;
+
+    #[test]
+    fn test_lagrange_interpolation() {
+        let rng = &mut thread_rng();
+        let coefficients: Vec<Fr> = (0..3).map(|_| Fr::rand(rng)).collect();
+
+        let xs = [Fr::from(2u64), Fr::from(5u64), Fr::from(7u64)];
+        let secret = xs.iter().fold(Fr::from(0u64), |acc, x| {
+            acc + lagrange_coefficient(x, &xs).unwrap() * evaluate_polynomial(&coefficients, x)
+        });
+        assert_eq!(coefficients[0], secret);
+    }
+
+    #[test]
+    fn test_split_and_reconstruct() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let public_key = private_key_to_public_key(&parameters, private_key).into_affine();
+
+        let (shares, commitment) = split(&parameters, private_key, 3, 5, rng).unwrap();
+        assert_eq!(Some(public_key), commitment.public_key());
+        assert_eq!(3, commitment.threshold());
+
+        for share in shares.iter() {
+            assert!(commitment.verify_share(&parameters, share));
+        }
+        let wrong_share = Share {
+            index: shares[0].index,
+            value: shares[0].value + Fr::from(1u64),
+        };
+        assert!(!commitment.verify_share(&parameters, &wrong_share));
+
+        assert_eq!(private_key, reconstruct(&shares[..3]).unwrap());
+        assert_eq!(private_key, reconstruct(&shares[2..]).unwrap());
+        assert_eq!(
+            private_key,
+            reconstruct(&[shares[4], shares[0], shares[2]]).unwrap()
+        );
+        assert_ne!(private_key, reconstruct(&shares[..2]).unwrap());
+
+        assert_eq!(
+            Some(Error::InvalidShare),
+            reconstruct(&[shares[0], shares[0], shares[1]]).err()
+        );
+        assert_eq!(
+            Some(Error::InvalidThreshold),
+            split(&parameters, private_key, 6, 5, rng).err()
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_verified() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let (shares, commitment) = split(&parameters, private_key, 3, 5, rng).unwrap();
+        assert_eq!(
+            private_key,
+            reconstruct_verified(&parameters, &shares[1..4], &commitment).unwrap()
+        );
+
+        assert_eq!(
+            Some(Error::InvalidThreshold),
+            reconstruct_verified(&parameters, &shares[..2], &commitment).err()
+        );
+
+        let mut wrong_shares = shares.clone();
+        wrong_shares[1].value += Fr::from(1u64);
+        assert_eq!(
+            Some(Error::InvalidShare),
+            reconstruct_verified(&parameters, &wrong_shares, &commitment).err()
+        );
+
+        // shares of another key don't match the commitment
+        let (other_shares, _) = split(&parameters, Fr::rand(rng), 3, 5, rng).unwrap();
+        assert_eq!(
+            Some(Error::InvalidShare),
+            reconstruct_verified(&parameters, &other_shares, &commitment).err()
+        );
+
+        // a repeated share doesn't count towards the threshold
+        assert_eq!(
+            Some(Error::InvalidShare),
+            reconstruct_verified(&parameters, &[shares[0], shares[1], shares[1]], &commitment)
+                .err()
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let (shares, commitment) = split(&parameters, Fr::rand(rng), 2, 3, rng).unwrap();
+
+        let mut bytes = vec![];
+        shares[1].serialize(&mut bytes).unwrap();
+        assert_eq!(shares[1], Share::deserialize(bytes.as_slice()).unwrap());
+
+        let mut bytes = vec![];
+        commitment.serialize(&mut bytes).unwrap();
+        assert_eq!(
+            commitment,
+            FeldmanCommitment::deserialize(bytes.as_slice()).unwrap()
+        );
+    }
+}
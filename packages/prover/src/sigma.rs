@@ -0,0 +1,313 @@
+use crate::{signature::SigningParameters, transcript::Transcript};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, UniformRand, Zero};
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr};
+
+/*
+Non interactive sigma protocols with the Fiat-Shamir transform.
+
+The challenge comes from a transcript with the protocol's label, where the context, the statement
+and the commitment are appended in this order, so that proofs can be checked in cairo. The context
+binds a proof to its use, e.g. the account a key is registered for, so that it can't be replayed
+elsewhere.
+
+Proofs are in the compact form (c, z): the verifier recomputes the commitment from the statement,
+the challenge and the response and checks that it hashes to the same challenge.
+*/
+
+pub trait SigmaProtocol {
+    /// Domain separator of the challenges, a short string
+    const LABEL: &'static str;
+
+    type Statement;
+    type Witness;
+    type Commitment;
+    type Response;
+    /// The prover's randomness between the commitment and the response
+    type State;
+
+    fn statement_felts(statement: &Self::Statement) -> Vec<Fq>;
+
+    fn commitment_felts(commitment: &Self::Commitment) -> Vec<Fq>;
+
+    fn commit<R: Rng>(
+        parameters: &SigningParameters,
+        statement: &Self::Statement,
+        rng: &mut R,
+    ) -> (Self::Commitment, Self::State);
+
+    fn respond(witness: &Self::Witness, state: Self::State, challenge: &Fr) -> Self::Response;
+
+    /// The only commitment accepted with this challenge and response, None if the statement is invalid
+    fn recompute_commitment(
+        parameters: &SigningParameters,
+        statement: &Self::Statement,
+        challenge: &Fr,
+        response: &Self::Response,
+    ) -> Option<Self::Commitment>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Proof<T> {
+    pub challenge: Fr,
+    pub response: T,
+}
+
+fn challenge<S: SigmaProtocol>(
+    context: &[Fq],
+    statement: &S::Statement,
+    commitment: &S::Commitment,
+) -> Fr {
+    let mut transcript = Transcript::new(S::LABEL);
+    transcript.append_felts("context", context);
+    transcript.append_felts("statement", &S::statement_felts(statement));
+    transcript.append_felts("commitment", &S::commitment_felts(commitment));
+
+    transcript.challenge_fr("challenge")
+}
+
+pub fn prove<S: SigmaProtocol, R: Rng>(
+    parameters: &SigningParameters,
+    statement: &S::Statement,
+    witness: &S::Witness,
+    context: &[Fq],
+    rng: &mut R,
+) -> Proof<S::Response> {
+    let (commitment, state) = S::commit(parameters, statement, rng);
+    let challenge = challenge::<S>(context, statement, &commitment);
+
+    Proof {
+        challenge,
+        response: S::respond(witness, state, &challenge),
+    }
+}
+
+pub fn verify<S: SigmaProtocol>(
+    parameters: &SigningParameters,
+    statement: &S::Statement,
+    context: &[Fq],
+    proof: &Proof<S::Response>,
+) -> bool {
+    match S::recompute_commitment(parameters, statement, &proof.challenge, &proof.response) {
+        Some(commitment) => challenge::<S>(context, statement, &commitment) == proof.challenge,
+        None => false,
+    }
+}
+
+fn is_valid_point(point: &Affine) -> bool {
+    !point.is_zero() && point.is_on_curve()
+}
+
+/// Proof of knowledge of x such that X = x*G, e.g. the private key of a public key
+pub struct SchnorrProof;
+
+impl SigmaProtocol for SchnorrProof {
+    const LABEL: &'static str = "STARK_SIGMA_SCHNORR";
+
+    type Statement = Affine;
+    type Witness = Fr;
+    type Commitment = Affine;
+    type Response = Fr;
+    type State = Fr;
+
+    fn statement_felts(statement: &Affine) -> Vec<Fq> {
+        vec![statement.x, statement.y]
+    }
+
+    fn commitment_felts(commitment: &Affine) -> Vec<Fq> {
+        vec![commitment.x, commitment.y]
+    }
+
+    fn commit<R: Rng>(
+        parameters: &SigningParameters,
+        _statement: &Affine,
+        rng: &mut R,
+    ) -> (Affine, Fr) {
+        let k = Fr::rand(rng);
+        (parameters.generator.mul(k.into_repr()).into_affine(), k)
+    }
+
+    fn respond(witness: &Fr, state: Fr, challenge: &Fr) -> Fr {
+        state + *challenge * witness
+    }
+
+    /// A = z*G - c*X
+    fn recompute_commitment(
+        parameters: &SigningParameters,
+        statement: &Affine,
+        challenge: &Fr,
+        response: &Fr,
+    ) -> Option<Affine> {
+        if !is_valid_point(statement) {
+            return None;
+        }
+
+        let commitment =
+            parameters.generator.mul(response.into_repr()) - statement.mul(challenge.into_repr());
+        Some(commitment.into_affine())
+    }
+}
+
+/// X = x*G and Y = x*H with H the second base
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DleqStatement {
+    pub base: Affine,
+    pub x: Affine,
+    pub y: Affine,
+}
+
+/// Proof that two points have the same discrete log, with respect to G and to another base
+pub struct DleqProof;
+
+impl SigmaProtocol for DleqProof {
+    const LABEL: &'static str = "STARK_SIGMA_DLEQ";
+
+    type Statement = DleqStatement;
+    type Witness = Fr;
+    type Commitment = (Affine, Affine);
+    type Response = Fr;
+    type State = Fr;
+
+    fn statement_felts(statement: &DleqStatement) -> Vec<Fq> {
+        vec![
+            statement.base.x,
+            statement.base.y,
+            statement.x.x,
+            statement.x.y,
+            statement.y.x,
+            statement.y.y,
+        ]
+    }
+
+    fn commitment_felts(commitment: &(Affine, Affine)) -> Vec<Fq> {
+        vec![
+            commitment.0.x,
+            commitment.0.y,
+            commitment.1.x,
+            commitment.1.y,
+        ]
+    }
+
+    fn commit<R: Rng>(
+        parameters: &SigningParameters,
+        statement: &DleqStatement,
+        rng: &mut R,
+    ) -> ((Affine, Affine), Fr) {
+        let k = Fr::rand(rng);
+        let commitment = (
+            parameters.generator.mul(k.into_repr()).into_affine(),
+            statement.base.mul(k.into_repr()).into_affine(),
+        );
+
+        (commitment, k)
+    }
+
+    fn respond(witness: &Fr, state: Fr, challenge: &Fr) -> Fr {
+        state + *challenge * witness
+    }
+
+    /// A = z*G - c*X and B = z*H - c*Y
+    fn recompute_commitment(
+        parameters: &SigningParameters,
+        statement: &DleqStatement,
+        challenge: &Fr,
+        response: &Fr,
+    ) -> Option<(Affine, Affine)> {
+        let DleqStatement { base, x, y } = statement;
+        if !is_valid_point(base) || !is_valid_point(x) || !is_valid_point(y) {
+            return None;
+        }
+
+        let a = parameters.generator.mul(response.into_repr()) - x.mul(challenge.into_repr());
+        let b = base.mul(response.into_repr()) - y.mul(challenge.into_repr());
+
+        Some((a.into_affine(), b.into_affine()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prove, verify, DleqProof, DleqStatement, SchnorrProof};
+    use crate::signature::{parameters, private_key_to_public_key};
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    #[test]
+    fn test_schnorr_proof() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let private_key = Fr::rand(rng);
+        let public_key = private_key_to_public_key(&parameters, private_key).into_affine();
+        let context = [Fq::from(0x1234u64)];
+
+        let proof = prove::<SchnorrProof, _>(&parameters, &public_key, &private_key, &context, rng);
+        assert!(verify::<SchnorrProof>(
+            &parameters,
+            &public_key,
+            &context,
+            &proof
+        ));
+
+        // bound to the context and the public key
+        assert!(!verify::<SchnorrProof>(
+            &parameters,
+            &public_key,
+            &[],
+            &proof
+        ));
+        let other_key = private_key_to_public_key(&parameters, Fr::rand(rng)).into_affine();
+        assert!(!verify::<SchnorrProof>(
+            &parameters,
+            &other_key,
+            &context,
+            &proof
+        ));
+
+        // a wrong witness doesn't give a valid proof
+        let proof =
+            prove::<SchnorrProof, _>(&parameters, &public_key, &Fr::rand(rng), &context, rng);
+        assert!(!verify::<SchnorrProof>(
+            &parameters,
+            &public_key,
+            &context,
+            &proof
+        ));
+    }
+
+    #[test]
+    fn test_dleq_proof() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let secret = Fr::rand(rng);
+        let base = private_key_to_public_key(&parameters, Fr::rand(rng)).into_affine();
+        let statement = DleqStatement {
+            base,
+            x: private_key_to_public_key(&parameters, secret).into_affine(),
+            y: base.mul(secret.into_repr()).into_affine(),
+        };
+
+        let proof = prove::<DleqProof, _>(&parameters, &statement, &secret, &[], rng);
+        assert!(verify::<DleqProof>(&parameters, &statement, &[], &proof));
+
+        let wrong_statement = DleqStatement {
+            y: base
+                .mul((secret + Fr::from(1u64)).into_repr())
+                .into_affine(),
+            ..statement
+        };
+        let proof = prove::<DleqProof, _>(&parameters, &wrong_statement, &secret, &[], rng);
+        assert!(!verify::<DleqProof>(
+            &parameters,
+            &wrong_statement,
+            &[],
+            &proof
+        ));
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{constants::TWO_MODULUS_BITS, error::Error, rfc6979::generate_k_rfc6979};
 
-use ark_ec::ProjectiveCurve;
-use ark_ff::{Field, FpParameters, One, PrimeField, Zero};
-use starknet_curve::{Fq, Fr, Projective};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, FpParameters, One, PrimeField, Zero};
+use starknet_curve::{Affine, Fq, Fr, Projective};
 
 /*
 starknet curve
@@ -106,12 +106,80 @@ pub fn private_key_to_public_key(parameters: &SigningParameters, priv_key: Fr) -
     parameters.generator.mul(priv_key.into_repr())
 }
 
+/// Recomputes R = k*G from the signature as w * (msg_hash * G + r * pub_key), where w = s^-1.
+/// Returns None if any of the values is out of the range accepted by cairo-lang.
+/// https://github.com/starkware-libs/cairo-lang/blob/167b28bcd940fd25ea3816204fa882a0b0a49603/src/starkware/crypto/starkware/crypto/signature/signature.py#L187
+fn nonce_point(
+    parameters: &SigningParameters,
+    pub_key: &Affine,
+    msg_hash: &Fq,
+    signature: &Signature,
+) -> Option<Affine> {
+    let r = signature.r;
+    let s = signature.s;
+
+    if !(r >= Fr::one() && r.into_repr() < TWO_MODULUS_BITS) {
+        return None;
+    }
+
+    // s is in Fr so it's always smaller than EC_ORDER, it's only checked to be non zero
+    let w = s.inverse()?;
+    if !(w >= Fr::one() && w.into_repr() < TWO_MODULUS_BITS) {
+        return None;
+    }
+
+    if msg_hash.into_repr() >= TWO_MODULUS_BITS {
+        return None;
+    }
+
+    if pub_key.is_zero() || !pub_key.is_on_curve() {
+        return None;
+    }
+
+    // since we checked that msg hash is in bound, it's safe to unwrap
+    let msg_hash = Fr::from_repr(msg_hash.into_repr()).unwrap();
+
+    let point =
+        parameters.generator.mul((msg_hash * w).into_repr()) + pub_key.mul((r * w).into_repr());
+
+    Some(point.into_affine())
+}
+
+/// Verifies the signature of msg_hash against the full public key.
+pub fn verify(
+    parameters: &SigningParameters,
+    pub_key: &Affine,
+    msg_hash: &Fq,
+    signature: &Signature,
+) -> bool {
+    match nonce_point(parameters, pub_key, msg_hash, signature) {
+        // r < 2**251 < Fq so comparing the representations is enough
+        Some(point) => point.x.into_repr() == signature.r.into_repr(),
+        None => false,
+    }
+}
+
+/// Returns the recovery bit of a valid signature, that is the parity of the y coordinate of R = k*G.
+/// Since r = R.x is always smaller than EC_ORDER, the recovery id is either 0 or 1.
+pub fn recovery_id(
+    parameters: &SigningParameters,
+    pub_key: &Affine,
+    msg_hash: &Fq,
+    signature: &Signature,
+) -> Result<u8, Error> {
+    let point = nonce_point(parameters, pub_key, msg_hash, signature)
+        .filter(|point| point.x.into_repr() == signature.r.into_repr())
+        .ok_or(Error::InvalidSignature)?;
+
+    Ok(point.y.into_repr().is_odd() as u8)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{parameters, private_key_to_public_key, sign, Signature, SigningParameters};
+    use super::{parameters, private_key_to_public_key, recovery_id, sign, verify, Signature};
     use crate::pedersen::compute_hash_on_elements;
-    use ark_ec::{AffineCurve, ProjectiveCurve};
-    use ark_ff::{Field, PrimeField};
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::field_new;
     use ark_std::UniformRand;
     use rand::thread_rng;
     use starknet::{
@@ -123,29 +191,55 @@ mod tests {
     };
     use starknet_curve::{Affine, Fq, Fr};
 
-    // here we skip range and on_curve checks since this is used just for local testing
-    pub fn verify_signature(
-        parameters: &SigningParameters,
-        pub_key: &Affine,
-        msg_hash: &Fq,
-        signature: &Signature,
-    ) -> bool {
-        let gen = parameters.generator;
+    #[test]
+    fn test_verify_with_crypto_cpp() {
+        // https://github.com/starkware-libs/crypto-cpp/blob/95864fbe11d5287e345432dbe1e80dea3c35fc58/src/starkware/crypto/ffi/crypto_lib_test.go
+        let parameters = parameters();
+
+        let stark_key = field_new!(
+            Fq,
+            "874739451078007766457464989774322083649278607533249481151382481072868806602"
+        );
+        let msg_hash = Fq::from(2u64);
+        let sig = Signature {
+            r: field_new!(
+                Fr,
+                "1839793652349538280924927302501143912227271479439798783640887258675143576352"
+            ),
+            s: field_new!(
+                Fr,
+                "1819432147005223164874083361865404672584671743718628757598322238853218813979"
+            ),
+        };
+
+        // only the x coordinate is known, so one of the two points has to verify
+        let public_key = Affine::get_point_from_x(stark_key, false).unwrap();
+        assert!(
+            verify(&parameters, &public_key, &msg_hash, &sig)
+                || verify(&parameters, &-public_key, &msg_hash, &sig)
+        );
+
+        let wrong_hash = Fq::from(3u64);
+        assert!(!verify(&parameters, &public_key, &wrong_hash, &sig));
+        assert!(!verify(&parameters, &-public_key, &wrong_hash, &sig));
+    }
 
-        let r = signature.r;
-        let s = signature.s;
-        // # Compute w = s^-1 (mod EC_ORDER).
-        let w = s.inverse().unwrap();
+    #[test]
+    fn test_recovery_id() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
 
-        // since we check that msg hash is in bound, it's safe to unwrap
-        let msg_hash = Fr::from_repr(msg_hash.into_repr()).unwrap();
+        let private_key = Fr::rand(rng);
+        let public_key = private_key_to_public_key(&parameters, private_key).into_affine();
 
-        let point = gen.mul((msg_hash * w).into_repr()).into_affine()
-            + pub_key.mul((r * w).into_repr()).into_affine();
+        let msg_hash = compute_hash_on_elements(&[Fq::rand(rng)]);
+        let sig = sign(&parameters, private_key, msg_hash, None).unwrap();
 
-        let x = Fr::from_repr(point.x.into_repr()).unwrap();
+        let recovery = recovery_id(&parameters, &public_key, &msg_hash, &sig).unwrap();
+        assert!(recovery == 0 || recovery == 1);
 
-        x == r
+        let wrong_hash = msg_hash + Fq::from(1u64);
+        assert!(recovery_id(&parameters, &public_key, &wrong_hash, &sig).is_err());
     }
 
     #[test]
@@ -161,13 +255,10 @@ mod tests {
 
         let msg = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
 
-        let msg_hash = compute_hash_on_elements(&msg).unwrap();
+        let msg_hash = compute_hash_on_elements(&msg);
         let sig = sign(&parameters, private_key, msg_hash, None).unwrap();
 
-        assert_eq!(
-            true,
-            verify_signature(&parameters, &public_key, &msg_hash, &sig)
-        );
+        assert_eq!(true, verify(&parameters, &public_key, &msg_hash, &sig));
 
         println!("sig r: {}", sig.r);
         println!("sig s: {}", sig.s);
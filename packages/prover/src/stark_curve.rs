@@ -0,0 +1,249 @@
+/*
+   Mirrors the `ec.starkCurve` API of starknet.js so that this module can be swapped in without
+   touching call sites. Unlike the rest of the crate, values follow starknet.js conventions:
+   felts are hex strings, bigints or numbers, and byte arrays are in BE endianness.
+*/
+
+use crate::error::Error;
+use crate::pedersen::{compute_hash_on_elements, pedersen_hash};
+use crate::poseidon::poseidon_hash_many;
+use crate::signature::{
+    parameters, private_key_to_public_key, recovery_id, sign as starknet_sign,
+    verify as starknet_verify, Signature,
+};
+use crate::util::{be_bytes_to_field, field_to_hex, hex_to_bytes, hex_to_field};
+
+use ark_ec::ProjectiveCurve;
+use ark_ff::{BigInteger, BigInteger256, PrimeField, Zero};
+use js_sys::{BigInt, Reflect, Uint8Array};
+use starknet_curve::{Affine, Fq, Fr};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+// largest integer that a JS number can represent exactly, 2**53 - 1, larger numbers may have lost
+// precision and must be BigInt or strings
+pub(crate) const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+#[wasm_bindgen]
+pub struct RecoveredSignature {
+    // store bytes in LE endianness as a convention
+    r: Vec<u8>,
+    s: Vec<u8>,
+    recovery: u8,
+}
+
+#[wasm_bindgen]
+impl RecoveredSignature {
+    #[wasm_bindgen(getter)]
+    pub fn r(&self) -> BigInt {
+        to_bigint(&Fr::from_le_bytes_mod_order(self.r.as_slice()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn s(&self) -> BigInt {
+        to_bigint(&Fr::from_le_bytes_mod_order(self.s.as_slice()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn recovery(&self) -> u8 {
+        self.recovery
+    }
+}
+
+#[wasm_bindgen(js_name = getStarkKey)]
+pub fn get_stark_key(private_key: JsValue) -> Result<String, JsValue> {
+    let public_key = public_key_from_js_private_key(&private_key).map_err(|e| e.to_jsval())?;
+    Ok(field_to_hex(&public_key.x))
+}
+
+/// Returns 0x04 || x || y, or 0x02/0x03 || x when compressed
+#[wasm_bindgen(js_name = getPublicKey)]
+pub fn get_public_key(
+    private_key: JsValue,
+    is_compressed: Option<bool>,
+) -> Result<Uint8Array, JsValue> {
+    let public_key = public_key_from_js_private_key(&private_key).map_err(|e| e.to_jsval())?;
+
+    let mut bytes = Vec::with_capacity(65);
+    if is_compressed.unwrap_or(false) {
+        bytes.push(if public_key.y.into_repr().is_odd() {
+            3
+        } else {
+            2
+        });
+        bytes.extend(public_key.x.into_repr().to_bytes_be());
+    } else {
+        bytes.push(4);
+        bytes.extend(public_key.x.into_repr().to_bytes_be());
+        bytes.extend(public_key.y.into_repr().to_bytes_be());
+    }
+
+    Ok(Uint8Array::from(&bytes[..]))
+}
+
+#[wasm_bindgen(js_name = sign)]
+pub fn sign(msg_hash: JsValue, private_key: JsValue) -> Result<RecoveredSignature, JsValue> {
+    let parameters = parameters();
+
+    let private_key = private_key_from_js(&private_key).map_err(|e| e.to_jsval())?;
+    let msg_hash: Fq = felt_from_js(&msg_hash).map_err(|e| e.to_jsval())?;
+
+    let sig = starknet_sign(&parameters, private_key, msg_hash, None).map_err(|e| e.to_jsval())?;
+
+    let public_key = private_key_to_public_key(&parameters, private_key).into_affine();
+    let recovery =
+        recovery_id(&parameters, &public_key, &msg_hash, &sig).map_err(|e| e.to_jsval())?;
+
+    Ok(RecoveredSignature {
+        r: sig.r.into_repr().to_bytes_le(),
+        s: sig.s.into_repr().to_bytes_le(),
+        recovery,
+    })
+}
+
+/// signature is either an object with `r` and `s` fields or the 64 bytes compact form r || s
+#[wasm_bindgen(js_name = verify)]
+pub fn verify(signature: JsValue, msg_hash: JsValue, public_key: JsValue) -> Result<bool, JsValue> {
+    let signature = signature_from_js(&signature).map_err(|e| e.to_jsval())?;
+    let msg_hash: Fq = felt_from_js(&msg_hash).map_err(|e| e.to_jsval())?;
+    let public_key = public_key_from_js(&public_key).map_err(|e| e.to_jsval())?;
+
+    Ok(starknet_verify(
+        &parameters(),
+        &public_key,
+        &msg_hash,
+        &signature,
+    ))
+}
+
+#[wasm_bindgen(js_name = pedersen)]
+pub fn pedersen(x: JsValue, y: JsValue) -> Result<String, JsValue> {
+    let x: Fq = felt_from_js(&x).map_err(|e| e.to_jsval())?;
+    let y: Fq = felt_from_js(&y).map_err(|e| e.to_jsval())?;
+
+    Ok(field_to_hex(&pedersen_hash(&x, &y)))
+}
+
+#[wasm_bindgen(js_name = poseidonHashMany)]
+pub fn poseidon_hash_many_js(values: js_sys::Array) -> Result<BigInt, JsValue> {
+    let values = felts_from_js(&values).map_err(|e| e.to_jsval())?;
+    Ok(to_bigint(&poseidon_hash_many(&values)))
+}
+
+#[wasm_bindgen(js_name = computeHashOnElements)]
+pub fn compute_hash_on_elements_js(data: js_sys::Array) -> Result<String, JsValue> {
+    let data = felts_from_js(&data).map_err(|e| e.to_jsval())?;
+    Ok(field_to_hex(&compute_hash_on_elements(&data)))
+}
+
+fn to_bigint<F: PrimeField<BigInt = BigInteger256>>(element: &F) -> BigInt {
+    // 0x prefixed hex strings are always valid BigInt literals
+    BigInt::new(&JsValue::from_str(&field_to_hex(element))).unwrap_throw()
+}
+
+/// accepts hex strings, bigints, non negative integer numbers and BE byte arrays
+fn felt_from_js<F: PrimeField<BigInt = BigInteger256>>(value: &JsValue) -> Result<F, Error> {
+    if let Some(hex) = value.as_string() {
+        return hex_to_field(&hex);
+    }
+
+    if let Some(bigint) = value.dyn_ref::<BigInt>() {
+        let hex: String = bigint.to_string(16).map_err(|_| Error::TypeError)?.into();
+        // negative bigints are rejected by the hex parser because of the sign
+        return hex_to_field(&hex);
+    }
+
+    if let Some(number) = value.as_f64() {
+        if !(0.0..=MAX_SAFE_INTEGER).contains(&number) || number.fract() != 0.0 {
+            return Err(Error::TypeError);
+        }
+        return Ok(F::from(number as u64));
+    }
+
+    if let Some(bytes) = value.dyn_ref::<Uint8Array>() {
+        return be_bytes_to_field(&bytes.to_vec());
+    }
+
+    Err(Error::TypeError)
+}
+
+fn felts_from_js(values: &js_sys::Array) -> Result<Vec<Fq>, Error> {
+    values.iter().map(|value| felt_from_js(&value)).collect()
+}
+
+fn private_key_from_js(value: &JsValue) -> Result<Fr, Error> {
+    let private_key: Fr = felt_from_js(value)?;
+    if private_key.is_zero() {
+        return Err(Error::InvalidPrivateKey);
+    }
+
+    Ok(private_key)
+}
+
+fn public_key_from_js_private_key(value: &JsValue) -> Result<Affine, Error> {
+    let private_key = private_key_from_js(value)?;
+    Ok(private_key_to_public_key(&parameters(), private_key).into_affine())
+}
+
+fn public_key_from_js(value: &JsValue) -> Result<Affine, Error> {
+    let bytes = match value.as_string() {
+        Some(hex) => hex_to_bytes(&hex)?,
+        None => value
+            .dyn_ref::<Uint8Array>()
+            .ok_or(Error::TypeError)?
+            .to_vec(),
+    };
+
+    let public_key = match (bytes.len(), bytes.first()) {
+        (65, Some(4)) => Affine::new(
+            be_bytes_to_field(&bytes[1..33])?,
+            be_bytes_to_field(&bytes[33..])?,
+            false,
+        ),
+        (33, Some(&prefix)) if prefix == 2 || prefix == 3 => {
+            let x: Fq = be_bytes_to_field(&bytes[1..])?;
+            let point = Affine::get_point_from_x(x, false).ok_or(Error::InvalidPublicKey)?;
+            if point.y.into_repr().is_odd() == (prefix == 3) {
+                point
+            } else {
+                -point
+            }
+        }
+        _ => return Err(Error::InvalidPublicKey),
+    };
+
+    if !public_key.is_on_curve() {
+        return Err(Error::InvalidPublicKey);
+    }
+
+    Ok(public_key)
+}
+
+fn signature_from_js(value: &JsValue) -> Result<Signature, Error> {
+    if value.is_object() && !value.is_instance_of::<Uint8Array>() {
+        let r = Reflect::get(value, &JsValue::from_str("r"))?;
+        let s = Reflect::get(value, &JsValue::from_str("s"))?;
+
+        return Ok(Signature {
+            r: felt_from_js(&r)?,
+            s: felt_from_js(&s)?,
+        });
+    }
+
+    let bytes = match value.as_string() {
+        Some(hex) => hex_to_bytes(&hex)?,
+        None => value
+            .dyn_ref::<Uint8Array>()
+            .ok_or(Error::TypeError)?
+            .to_vec(),
+    };
+
+    if bytes.len() != 64 {
+        return Err(Error::IncorrectLenError);
+    }
+
+    Ok(Signature {
+        r: be_bytes_to_field(&bytes[..32])?,
+        s: be_bytes_to_field(&bytes[32..])?,
+    })
+}
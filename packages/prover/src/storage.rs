@@ -0,0 +1,98 @@
+use crate::constants::ADDR_BOUND;
+use crate::error::Error;
+use crate::keccak::starknet_keccak;
+use crate::pedersen::pedersen_hash;
+
+use ark_ff::PrimeField;
+use starknet_curve::Fq;
+
+/// Computes the address of a storage variable, keys are the keys of a mapping (if any):
+///     h(...h(h(sn_keccak(var_name), keys[0]), keys[1]), ...) % ADDR_BOUND
+/// https://github.com/starkware-libs/cairo-lang/blob/v0.10.3/src/starkware/starknet/public/abi.py
+pub fn get_storage_var_address(var_name: &str, keys: &[Fq]) -> Result<Fq, Error> {
+    // cairo-lang encodes storage variable names in utf-8, unlike selectors which are ascii
+    let address = keys
+        .iter()
+        .fold(starknet_keccak(var_name.as_bytes()), |acc, key| {
+            pedersen_hash(&acc, key)
+        });
+
+    Ok(reduce_to_addr_bound(address))
+}
+
+/// Since the field modulus is smaller than 2 * ADDR_BOUND, reducing is at most one subtraction
+fn reduce_to_addr_bound(address: Fq) -> Fq {
+    if address.into_repr() >= ADDR_BOUND {
+        // ADDR_BOUND < modulus so it's safe to unwrap
+        address - Fq::from_repr(ADDR_BOUND).unwrap()
+    } else {
+        address
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_storage_var_address, reduce_to_addr_bound};
+    use crate::keccak::starknet_keccak;
+    use ark_ff::field_new;
+    use starknet_curve::Fq;
+
+    #[test]
+    fn test_storage_var_address_with_cairo() {
+        // CAIRO: get_storage_var_address("balance") -> 0x206f38f7e4f15e87567361213c28f235cccdaa1d7fd34c9db1dfe9489c6a091
+
+        let expected = field_new!(
+            Fq,
+            "916907772491729262376534102982219947830828984996257231353398618781993312401"
+        );
+
+        assert_eq!(Ok(expected), get_storage_var_address("balance", &[]));
+    }
+
+    #[test]
+    fn test_mapping_address_with_cairo() {
+        // CAIRO: get_storage_var_address("balanceOf", 1234) -> 0x7de334d65aa93d9185729b424025918b18892418c85b802775d1f0d2be30a1d
+
+        let expected = field_new!(
+            Fq,
+            "3558784064149958713964021127559928894422215496421638410312215880517504141853"
+        );
+
+        assert_eq!(
+            Ok(expected),
+            get_storage_var_address("balanceOf", &[Fq::from(1234u64)])
+        );
+
+        // names are utf-8, the keccak of "balanceÖf" is the one of its 10 bytes
+        let utf8 = get_storage_var_address("balanceÖf", &[]).unwrap();
+        assert_eq!(
+            reduce_to_addr_bound(starknet_keccak(b"balance\xc3\x96f")),
+            utf8
+        );
+    }
+
+    #[test]
+    fn test_addr_bound_reduction() {
+        let addr_bound = field_new!(
+            Fq,
+            "3618502788666131106986593281521497120414687020801267626233049500247285300992"
+        );
+
+        assert_eq!(Fq::from(0u64), reduce_to_addr_bound(addr_bound));
+        assert_eq!(
+            Fq::from(5u64),
+            reduce_to_addr_bound(addr_bound + Fq::from(5u64))
+        );
+        assert_eq!(
+            addr_bound - Fq::from(1u64),
+            reduce_to_addr_bound(addr_bound - Fq::from(1u64))
+        );
+
+        // largest felt is reduced to P - 1 - ADDR_BOUND
+        let expected = field_new!(
+            Fq,
+            "106710729501573572985208420194530329073740042555888586719488"
+        );
+        assert_eq!(expected, reduce_to_addr_bound(-Fq::from(1u64)));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{poseidon::poseidon_permute, util::short_string_to_field};
+
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use starknet_curve::{Affine, Fq, Fr};
+
+/*
+Fiat-Shamir transcript over felts with a Poseidon sponge (width 3, rate 2), with the API of Merlin
+(https://merlin.cool). packages/verifier/src/transcript.cairo is the matching cairo implementation.
+
+Every operation absorbs a list of felts padded as in poseidon_hash_many, with 1 and then 0 if
+needed to fill the last pair:
+    new(label)                  absorbs [DOMAIN, label]
+    append_felts(label, values) absorbs [label, len(values), values...]
+    challenge_fq(label)         absorbs [label] and outputs the first element of the state
+
+Points are appended as [x, y] and scalars as felts, challenge_fr reduces challenge_fq modulo
+EC_ORDER. Labels are short strings of at most 31 ascii characters.
+*/
+
+const DOMAIN: &str = "STARK_TRANSCRIPT";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transcript {
+    state: [Fq; 3],
+}
+
+fn label_to_field(label: &'static str) -> Fq {
+    short_string_to_field(label).expect("labels are short ascii strings")
+}
+
+impl Transcript {
+    pub fn new(label: &'static str) -> Self {
+        let mut transcript = Self {
+            state: [Fq::zero(); 3],
+        };
+        transcript.absorb(&[label_to_field(DOMAIN), label_to_field(label)]);
+
+        transcript
+    }
+
+    fn absorb(&mut self, data: &[Fq]) {
+        let mut chunks = data.chunks_exact(2);
+        for chunk in chunks.by_ref() {
+            self.state[0] += chunk[0];
+            self.state[1] += chunk[1];
+            poseidon_permute(&mut self.state);
+        }
+
+        let remainder = chunks.remainder();
+        if let Some(last) = remainder.first() {
+            self.state[0] += last;
+        }
+        self.state[remainder.len()] += Fq::one();
+        poseidon_permute(&mut self.state);
+    }
+
+    pub fn append_felts(&mut self, label: &'static str, values: &[Fq]) {
+        let mut data = vec![label_to_field(label), Fq::from(values.len() as u64)];
+        data.extend_from_slice(values);
+
+        self.absorb(&data);
+    }
+
+    pub fn append_felt(&mut self, label: &'static str, value: &Fq) {
+        self.append_felts(label, &[*value]);
+    }
+
+    pub fn append_point(&mut self, label: &'static str, point: &Affine) {
+        self.append_felts(label, &[point.x, point.y]);
+    }
+
+    pub fn append_scalar(&mut self, label: &'static str, scalar: &Fr) {
+        // EC_ORDER is smaller than FIELD_PRIME so it's safe to unwrap
+        self.append_felt(label, &Fq::from_repr(scalar.into_repr()).unwrap());
+    }
+
+    pub fn challenge_fq(&mut self, label: &'static str) -> Fq {
+        self.absorb(&[label_to_field(label)]);
+        self.state[0]
+    }
+
+    pub fn challenge_fr(&mut self, label: &'static str) -> Fr {
+        let challenge = self.challenge_fq(label);
+        Fr::from_le_bytes_mod_order(&challenge.into_repr().to_bytes_le())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{label_to_field, Transcript, DOMAIN};
+    use crate::poseidon::poseidon_hash_many;
+    use crate::signature::parameters;
+    use crate::util::hex_to_field;
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::PrimeField;
+    use starknet_curve::{Fq, Fr};
+
+    #[test]
+    fn test_transcript() {
+        // the first absorption from the zero state is poseidon_hash_many
+        let transcript = Transcript::new("test");
+        assert_eq!(
+            poseidon_hash_many(&[label_to_field(DOMAIN), label_to_field("test")]),
+            transcript.state[0]
+        );
+
+        let generator = parameters().generator.into_affine();
+        let challenges = |values: &[Fq]| {
+            let mut transcript = Transcript::new("test");
+            transcript.append_felts("values", values);
+            transcript.append_point("point", &generator);
+            transcript.append_scalar("scalar", &Fr::from(7u64));
+
+            (
+                transcript.challenge_fq("first"),
+                transcript.challenge_fr("second"),
+            )
+        };
+
+        let (first, second) = challenges(&[Fq::from(1u64), Fq::from(2u64)]);
+        // the same vector is checked against the cairo transcript in
+        // packages/verifier/tests/transcript.py
+        assert_eq!(
+            hex_to_field::<Fq>("0x645513400b830cc45135d0f641d871c1be2f60b7fcc4a6681dacdb3bd5357ac")
+                .unwrap(),
+            first
+        );
+        assert_eq!(
+            hex_to_field::<Fr>("0x1077a9e12697c7826ef16c51f8c768ade215feb0cbace6d216259a75a3c73e9")
+                .unwrap(),
+            second
+        );
+        assert_eq!(
+            (first, second),
+            challenges(&[Fq::from(1u64), Fq::from(2u64)])
+        );
+        assert_ne!(first, Fq::from_repr(second.into_repr()).unwrap());
+
+        // the length is absorbed, so values can't be shifted from one message to the next
+        assert_ne!(first, challenges(&[Fq::from(1u64)]).0);
+        assert_ne!(
+            first,
+            challenges(&[Fq::from(1u64), Fq::from(2u64), Fq::from(1u64)]).0
+        );
+
+        let mut other = Transcript::new("other");
+        other.append_felts("values", &[Fq::from(1u64), Fq::from(2u64)]);
+        assert_ne!(first, other.challenge_fq("first"));
+    }
+}
@@ -0,0 +1,547 @@
+use crate::constants::{CONTRACT_CLASS_LEAF_V0, STARKNET_STATE_V0};
+use crate::error::Error;
+use crate::hash::HashFunction;
+use crate::pedersen::pedersen_hash;
+use crate::poseidon::poseidon_hash_many;
+
+use ark_ff::{BigInteger, BigInteger256, Field, One, PrimeField, Zero};
+use starknet_curve::Fq;
+use std::collections::BTreeMap;
+
+/// Height of the contracts, storage and classes tries in Starknet
+pub const STARKNET_TRIE_HEIGHT: usize = 251;
+
+/// Nodes as returned by starknet_getProof, ordered from the root to the leaf
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProofNode {
+    Binary { left: Fq, right: Fq },
+    Edge { child: Fq, path: Fq, length: usize },
+}
+
+impl ProofNode {
+    /// Binary nodes are hashed as h(left, right) and edge nodes as h(child, path) + length
+    pub fn hash(&self, hasher: HashFunction) -> Fq {
+        match self {
+            Self::Binary { left, right } => hasher.hash(left, right),
+            Self::Edge {
+                child,
+                path,
+                length,
+            } => hasher.hash(child, path) + Fq::from(*length as u64),
+        }
+    }
+}
+
+/// Binary Merkle-Patricia trie as used for Starknet state commitments.
+/// Leaves with a zero value are considered empty, so inserting zero removes the key.
+pub struct MerklePatriciaTrie {
+    height: usize,
+    hasher: HashFunction,
+    leaves: BTreeMap<BigInteger256, Fq>,
+}
+
+impl MerklePatriciaTrie {
+    /// Keys are height bits long, at most STARKNET_TRIE_HEIGHT so that they fit in a felt
+    pub fn new(height: usize, hasher: HashFunction) -> Result<Self, Error> {
+        if height == 0 || height > STARKNET_TRIE_HEIGHT {
+            return Err(Error::IncorrectLenError);
+        }
+
+        Ok(Self {
+            height,
+            hasher,
+            leaves: BTreeMap::new(),
+        })
+    }
+
+    /// Trie of contract states and of contract storage
+    pub fn pedersen() -> Self {
+        Self::starknet(HashFunction::Pedersen)
+    }
+
+    /// Trie of declared classes
+    pub fn poseidon() -> Self {
+        Self::starknet(HashFunction::Poseidon)
+    }
+
+    fn starknet(hasher: HashFunction) -> Self {
+        // it's safe to unwrap, the height is valid
+        Self::new(STARKNET_TRIE_HEIGHT, hasher).unwrap()
+    }
+
+    pub fn insert(&mut self, key: &Fq, value: &Fq) -> Result<(), Error> {
+        let key = self.checked_key(key)?;
+
+        if value.is_zero() {
+            self.leaves.remove(&key);
+        } else {
+            self.leaves.insert(key, *value);
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, key: &Fq) -> Fq {
+        self.leaves
+            .get(&key.into_repr())
+            .copied()
+            .unwrap_or_else(Fq::zero)
+    }
+
+    /// Root of the empty trie is 0
+    pub fn root(&self) -> Fq {
+        let entries: Vec<_> = self.leaves.iter().collect();
+        if entries.is_empty() {
+            return Fq::zero();
+        }
+
+        self.node_hash(&entries, 0)
+    }
+
+    /// Nodes on the path from the root to the key. If the key is not in the trie the proof ends
+    /// with the edge node diverging from the key (or is empty for the empty trie).
+    pub fn prove(&self, key: &Fq) -> Result<Vec<ProofNode>, Error> {
+        let key = self.checked_key(key)?;
+
+        let mut proof = vec![];
+        let mut entries: Vec<_> = self.leaves.iter().collect();
+        let mut depth = 0;
+
+        while !entries.is_empty() && depth < self.height {
+            let common = self.common_prefix_end(&entries, depth);
+
+            if common > depth {
+                let path = self.path(entries[0].0, depth, common);
+                proof.push(ProofNode::Edge {
+                    child: self.node_hash(&entries, common),
+                    path,
+                    length: common - depth,
+                });
+
+                if self.path(&key, depth, common) != path {
+                    break;
+                }
+                depth = common;
+            } else {
+                let split = self.split(&entries, depth);
+                let (left, right) = entries.split_at(split);
+                proof.push(ProofNode::Binary {
+                    left: self.node_hash(left, depth + 1),
+                    right: self.node_hash(right, depth + 1),
+                });
+
+                entries = if self.bit(&key, depth) {
+                    right.to_vec()
+                } else {
+                    left.to_vec()
+                };
+                depth += 1;
+            }
+        }
+
+        Ok(proof)
+    }
+
+    fn checked_key(&self, key: &Fq) -> Result<BigInteger256, Error> {
+        let repr = key.into_repr();
+        if repr.num_bits() as usize > self.height {
+            return Err(Error::OverflowError);
+        }
+
+        Ok(repr)
+    }
+
+    fn bit(&self, key: &BigInteger256, depth: usize) -> bool {
+        key_bit(key, self.height, depth)
+    }
+
+    fn path(&self, key: &BigInteger256, from: usize, to: usize) -> Fq {
+        key_path(key, self.height, from, to)
+    }
+
+    /// Entries are sorted, so the prefix shared by all of them is the one of the first and last
+    fn common_prefix_end(&self, entries: &[(&BigInteger256, &Fq)], depth: usize) -> usize {
+        let first = entries[0].0;
+        let last = entries[entries.len() - 1].0;
+
+        (depth..self.height)
+            .find(|&d| self.bit(first, d) != self.bit(last, d))
+            .unwrap_or(self.height)
+    }
+
+    /// Index of the first entry that goes to the right child
+    fn split(&self, entries: &[(&BigInteger256, &Fq)], depth: usize) -> usize {
+        entries
+            .iter()
+            .position(|(key, _)| self.bit(key, depth))
+            .unwrap_or(entries.len())
+    }
+
+    fn node_hash(&self, entries: &[(&BigInteger256, &Fq)], depth: usize) -> Fq {
+        if depth == self.height {
+            // a leaf, keys are unique so there's exactly one entry
+            return *entries[0].1;
+        }
+
+        let common = self.common_prefix_end(entries, depth);
+        if common > depth {
+            let node = ProofNode::Edge {
+                child: self.node_hash(entries, common),
+                path: self.path(entries[0].0, depth, common),
+                length: common - depth,
+            };
+            return node.hash(self.hasher);
+        }
+
+        let (left, right) = entries.split_at(self.split(entries, depth));
+        self.hasher.hash(
+            &self.node_hash(left, depth + 1),
+            &self.node_hash(right, depth + 1),
+        )
+    }
+}
+
+/// Bits are indexed from the root, so depth 0 is the most significant bit of the key
+fn key_bit(key: &BigInteger256, height: usize, depth: usize) -> bool {
+    key.get_bit(height - 1 - depth)
+}
+
+/// Value of the key bits in [from, to)
+fn key_path(key: &BigInteger256, height: usize, from: usize, to: usize) -> Fq {
+    (from..to).fold(Fq::zero(), |acc, depth| {
+        if key_bit(key, height, depth) {
+            acc.double() + Fq::one()
+        } else {
+            acc.double()
+        }
+    })
+}
+
+/// Returns the value proven for the key (zero if the proof shows that the key is not set),
+/// or None if the proof doesn't match the root.
+pub fn proven_value(
+    root: &Fq,
+    key: &Fq,
+    proof: &[ProofNode],
+    height: usize,
+    hasher: HashFunction,
+) -> Option<Fq> {
+    let key = key.into_repr();
+    if key.num_bits() as usize > height {
+        return None;
+    }
+
+    let mut expected = *root;
+    let mut depth = 0;
+
+    for (i, node) in proof.iter().enumerate() {
+        if depth >= height || node.hash(hasher) != expected {
+            return None;
+        }
+
+        match node {
+            ProofNode::Binary { left, right } => {
+                expected = if key_bit(&key, height, depth) {
+                    *right
+                } else {
+                    *left
+                };
+                depth += 1;
+            }
+            ProofNode::Edge {
+                child,
+                path,
+                length,
+            } => {
+                if depth + length > height {
+                    return None;
+                }
+
+                if key_path(&key, height, depth, depth + length) != *path {
+                    // the key diverges from the only path in this subtree, so it's not set
+                    return if i == proof.len() - 1 {
+                        Some(Fq::zero())
+                    } else {
+                        None
+                    };
+                }
+
+                expected = *child;
+                depth += length;
+            }
+        }
+    }
+
+    if depth == height {
+        Some(expected)
+    } else if proof.is_empty() && root.is_zero() {
+        // empty trie
+        Some(Fq::zero())
+    } else {
+        None
+    }
+}
+
+/// Checks that the proof shows value at key, use a zero value to check that the key is not set
+pub fn verify_proof(
+    root: &Fq,
+    key: &Fq,
+    value: &Fq,
+    proof: &[ProofNode],
+    height: usize,
+    hasher: HashFunction,
+) -> bool {
+    proven_value(root, key, proof, height, hasher) == Some(*value)
+}
+
+/// Leaf of the contracts trie: h(h(h(class_hash, storage_root), nonce), 0)
+pub fn contract_state_hash(class_hash: &Fq, storage_root: &Fq, nonce: &Fq) -> Fq {
+    let hash = pedersen_hash(class_hash, storage_root);
+    let hash = pedersen_hash(&hash, nonce);
+
+    // contract state hash version
+    pedersen_hash(&hash, &Fq::zero())
+}
+
+/// Leaf of the classes trie: poseidon("CONTRACT_CLASS_LEAF_V0", compiled_class_hash)
+pub fn class_leaf_hash(compiled_class_hash: &Fq) -> Fq {
+    HashFunction::Poseidon.hash(&CONTRACT_CLASS_LEAF_V0, compiled_class_hash)
+}
+
+/// Global state root of a block. Before classes were committed (classes root is 0)
+/// the state root is the contracts trie root.
+pub fn state_commitment(contracts_root: &Fq, classes_root: &Fq) -> Fq {
+    if classes_root.is_zero() {
+        return *contracts_root;
+    }
+
+    poseidon_hash_many(&[STARKNET_STATE_V0, *contracts_root, *classes_root])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        class_leaf_hash, contract_state_hash, state_commitment, verify_proof, MerklePatriciaTrie,
+        ProofNode, STARKNET_TRIE_HEIGHT,
+    };
+    use crate::error::Error;
+    use crate::hash::HashFunction;
+    use crate::pedersen::pedersen_hash;
+    use crate::poseidon::poseidon_hash;
+    use crate::util::{hex_to_field, short_string_to_field};
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::Fq;
+
+    const FIXTURES: &str = include_str!("../fixtures/patricia_trie.txt");
+
+    #[test]
+    fn test_roots_with_fixtures() {
+        let cases = FIXTURES
+            .split("\n\n")
+            .map(|case| {
+                case.lines()
+                    .filter(|line| !line.starts_with('#') && !line.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|case| !case.is_empty());
+
+        let mut n_cases = 0;
+        for case in cases {
+            let mut trie: Option<MerklePatriciaTrie> = None;
+            let mut height = 0;
+
+            for line in case {
+                let words: Vec<&str> = line.split_whitespace().collect();
+                match words[0] {
+                    "height" => height = words[1].parse().unwrap(),
+                    "hash" => {
+                        let hasher = match words[1] {
+                            "pedersen" => HashFunction::Pedersen,
+                            _ => HashFunction::Poseidon,
+                        };
+                        trie = Some(MerklePatriciaTrie::new(height, hasher).unwrap());
+                    }
+                    "leaf" => {
+                        let key = hex_to_field(words[1]).unwrap();
+                        let value = hex_to_field(words[2]).unwrap();
+                        trie.as_mut().unwrap().insert(&key, &value).unwrap();
+                    }
+                    "root" => {
+                        let expected: Fq = hex_to_field(words[1]).unwrap();
+                        assert_eq!(expected, trie.as_ref().unwrap().root());
+                        n_cases += 1;
+                    }
+                    _ => panic!("unknown fixture line: {}", line),
+                }
+            }
+        }
+
+        assert_eq!(3, n_cases);
+    }
+
+    #[test]
+    fn test_state_commitment() {
+        let felt = |hex: &str| -> Fq { hex_to_field(hex).unwrap() };
+
+        // test vector of pathfinder (crates/merkle-tree/src/contract_state.rs)
+        let class_hash = felt("0x2ff4903e17f87b298ded00c44bfeb22874c5f73be2ced8f1d9d9556fb509779");
+        let storage_root =
+            felt("0x4fb440e8ca9b74fc12a22ebffe0bc0658206337897226117b985434c239c028");
+        assert_eq!(
+            felt("0x7161b591c893836263a64f2a7e0d829c92f6956148a60ce5e99a3f55c7973f3"),
+            contract_state_hash(&class_hash, &storage_root, &Fq::from(0u64))
+        );
+
+        let compiled_class_hash = Fq::from(0x5678u64);
+        assert_eq!(
+            poseidon_hash(
+                &short_string_to_field("CONTRACT_CLASS_LEAF_V0").unwrap(),
+                &compiled_class_hash
+            ),
+            class_leaf_hash(&compiled_class_hash)
+        );
+
+        // before classes were committed the state root is the contracts root
+        let contracts_root = Fq::from(0x1234u64);
+        assert_eq!(
+            contracts_root,
+            state_commitment(&contracts_root, &Fq::from(0u64))
+        );
+        assert_ne!(
+            contracts_root,
+            state_commitment(&contracts_root, &Fq::from(1u64))
+        );
+    }
+
+    #[test]
+    fn test_invalid_height() {
+        for height in [0, STARKNET_TRIE_HEIGHT + 1] {
+            assert_eq!(
+                Some(Error::IncorrectLenError),
+                MerklePatriciaTrie::new(height, HashFunction::Pedersen).err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_leaf_is_an_edge() {
+        let mut trie = MerklePatriciaTrie::pedersen();
+        assert_eq!(Fq::from(0u64), trie.root());
+
+        let key = Fq::from(5u64);
+        let value = Fq::from(17u64);
+        trie.insert(&key, &value).unwrap();
+
+        let expected = pedersen_hash(&value, &key) + Fq::from(STARKNET_TRIE_HEIGHT as u64);
+        assert_eq!(expected, trie.root());
+
+        // inserting zero deletes the leaf
+        trie.insert(&key, &Fq::from(0u64)).unwrap();
+        assert_eq!(Fq::from(0u64), trie.root());
+    }
+
+    #[test]
+    fn test_two_leaves_under_a_binary_node() {
+        let mut trie = MerklePatriciaTrie::new(2, HashFunction::Pedersen).unwrap();
+        trie.insert(&Fq::from(0u64), &Fq::from(3u64)).unwrap();
+        trie.insert(&Fq::from(3u64), &Fq::from(4u64)).unwrap();
+
+        let left = ProofNode::Edge {
+            child: Fq::from(3u64),
+            path: Fq::from(0u64),
+            length: 1,
+        };
+        let right = ProofNode::Edge {
+            child: Fq::from(4u64),
+            path: Fq::from(1u64),
+            length: 1,
+        };
+        let expected = pedersen_hash(
+            &left.hash(HashFunction::Pedersen),
+            &right.hash(HashFunction::Pedersen),
+        );
+
+        assert_eq!(expected, trie.root());
+    }
+
+    #[test]
+    fn test_proofs() {
+        let rng = &mut thread_rng();
+
+        for hasher in [HashFunction::Pedersen, HashFunction::Poseidon] {
+            let mut trie = MerklePatriciaTrie::new(STARKNET_TRIE_HEIGHT, hasher).unwrap();
+            let mut keys = vec![];
+            for i in 0..8u64 {
+                // keys have to fit in 251 bits
+                let key = Fq::from(u64::rand(rng)) * Fq::from(1u64 << 60) + Fq::from(i);
+                trie.insert(&key, &Fq::from(i + 1)).unwrap();
+                keys.push(key);
+            }
+
+            let root = trie.root();
+            for key in keys.iter() {
+                let proof = trie.prove(key).unwrap();
+                let value = trie.get(key);
+
+                assert!(verify_proof(
+                    &root,
+                    key,
+                    &value,
+                    &proof,
+                    STARKNET_TRIE_HEIGHT,
+                    hasher
+                ));
+                assert!(!verify_proof(
+                    &root,
+                    key,
+                    &(value + Fq::from(1u64)),
+                    &proof,
+                    STARKNET_TRIE_HEIGHT,
+                    hasher
+                ));
+            }
+
+            // proof of non membership
+            let missing = Fq::from(42u64);
+            let proof = trie.prove(&missing).unwrap();
+            assert!(verify_proof(
+                &root,
+                &missing,
+                &Fq::from(0u64),
+                &proof,
+                STARKNET_TRIE_HEIGHT,
+                hasher
+            ));
+
+            // proof for another root
+            let proof = trie.prove(&keys[0]).unwrap();
+            trie.insert(&keys[0], &Fq::from(100u64)).unwrap();
+            assert!(!verify_proof(
+                &trie.root(),
+                &keys[0],
+                &Fq::from(1u64),
+                &proof,
+                STARKNET_TRIE_HEIGHT,
+                hasher
+            ));
+        }
+    }
+
+    #[test]
+    fn test_empty_trie_proof() {
+        let trie = MerklePatriciaTrie::poseidon();
+        let key = Fq::from(1u64);
+        let proof = trie.prove(&key).unwrap();
+
+        assert!(proof.is_empty());
+        assert!(verify_proof(
+            &Fq::from(0u64),
+            &key,
+            &Fq::from(0u64),
+            &proof,
+            STARKNET_TRIE_HEIGHT,
+            HashFunction::Poseidon
+        ));
+    }
+}
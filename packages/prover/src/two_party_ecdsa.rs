@@ -0,0 +1,932 @@
+use crate::{
+    constants::TWO_MODULUS_BITS,
+    error::Error,
+    paillier::{
+        biguints_to_bytes, hash_to_biguint, Ciphertext, CorrectKeyProof, DecryptionKey,
+        EncryptionKey,
+    },
+    poseidon::poseidon_hash_many,
+    signature::{verify, Signature, SigningParameters},
+    util::short_string_to_field,
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, FpParameters, One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use num_bigint_dig::{BigUint, RandBigInt};
+use num_integer::Integer;
+use rand::Rng;
+use starknet_curve::{Affine, Fq, Fr, FrParameters};
+
+/*
+Two party ECDSA of Lindell (https://eprint.iacr.org/2017/552) producing ordinary STARK ECDSA
+signatures, so they are accepted by verify_ecdsa_signature and Verifier.cairo. Only the 2-of-2
+case is covered, threshold Schnorr signatures are in the frost module.
+
+The private key is x = x1 * x2, party 1 holds x1 and a Paillier key, party 2 holds x2 and the
+encryption of x1 under the Paillier key of party 1. To sign, both parties agree on R = k1 * k2 * G,
+party 2 computes Enc(k2^-1 * (m + r * x2 * x1)) homomorphically and party 1 decrypts it and
+multiplies by k1^-1. Party 1 commits to its first message in both protocols, so that the shares of
+party 2 can't depend on it.
+
+Besides the Schnorr proofs of knowledge of the key and nonce shares, party 1 proves in key
+generation that its Paillier modulus is well formed (see the paillier module) and that the
+encrypted share is the discrete log of its public share (PDL). The PDL proof is a cut and choose
+sigma protocol with one bit challenges, so the extracted plaintext is an integer x with x * G = Q1
+and |x| < 2**(PDL_MASK_BITS + 1): it may differ from x1 by a multiple of EC_ORDER, but the mask
+added by party 2 to the partial signature keeps it from wrapping around the modulus.
+
+A signing attempt can fail with UnsignableMessage when r or w are out of the range accepted by
+cairo, in this case it has to be restarted with new nonces.
+*/
+
+// the Paillier plaintext is smaller than EC_ORDER**3, so a bigger modulus never wraps around
+pub const PAILLIER_BITS: usize = 2048;
+const MIN_PAILLIER_BITS: usize = 1024;
+
+const PROOF_TAG: &str = "STARK_2P_ECDSA_DLOG";
+const COMMITMENT_TAG: &str = "STARK_2P_ECDSA_COMMIT";
+const PDL_TAG: &[u8] = b"STARK_2P_ECDSA_PDL";
+
+// one bit challenges, for a soundness error of 2**-128
+const PDL_ROUNDS: usize = 128;
+// the 252 bits of EC_ORDER and 128 bits of statistical hiding
+const PDL_MASK_BITS: usize = 380;
+
+fn to_biguint(scalar: &Fr) -> BigUint {
+    BigUint::from_bytes_le(&scalar.into_repr().to_bytes_le())
+}
+
+fn to_scalar(value: &BigUint) -> Fr {
+    Fr::from_le_bytes_mod_order(&value.to_bytes_le())
+}
+
+fn scalar_to_field(scalar: &Fr) -> Fq {
+    // EC_ORDER is smaller than FIELD_PRIME so it's safe to unwrap
+    Fq::from_repr(scalar.into_repr()).unwrap()
+}
+
+fn field_to_scalar(element: &Fq) -> Fr {
+    Fr::from_le_bytes_mod_order(&element.into_repr().to_bytes_le())
+}
+
+/// Schnorr proof of knowledge of the discrete log of a public share
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DLogProof {
+    pub a: Affine,
+    pub z: Fr,
+}
+
+impl DLogProof {
+    fn challenge(public: &Affine, a: &Affine) -> Fr {
+        // it's safe to unwrap, the tag is a short ascii string
+        let tag = short_string_to_field(PROOF_TAG).unwrap();
+        field_to_scalar(&poseidon_hash_many(&[tag, public.x, public.y, a.x, a.y]))
+    }
+
+    fn prove<R: Rng>(parameters: &SigningParameters, secret: &Fr, rng: &mut R) -> Self {
+        let public = parameters.generator.mul(secret.into_repr()).into_affine();
+        let k = Fr::rand(rng);
+        let a = parameters.generator.mul(k.into_repr()).into_affine();
+
+        Self {
+            a,
+            z: k + Self::challenge(&public, &a) * secret,
+        }
+    }
+
+    fn verify(&self, parameters: &SigningParameters, public: &Affine) -> bool {
+        let c = Self::challenge(public, &self.a);
+        !public.is_zero()
+            && parameters.generator.mul(self.z.into_repr())
+                == self.a.into_projective() + public.mul(c.into_repr())
+    }
+}
+
+fn field_to_biguint(element: &Fq) -> BigUint {
+    BigUint::from_bytes_le(&element.into_repr().to_bytes_le())
+}
+
+/// Proof that the Paillier ciphertext c encrypts the discrete log of Q1. Every round commits to
+/// A = Enc(alpha, beta), Y = alpha * G and answers the challenge bit e with
+/// z1 = alpha + e * x1, z2 = beta * r^e, where r is the randomness of c.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdlProof {
+    pub commitments: Vec<(Ciphertext, Affine)>,
+    pub responses: Vec<(BigUint, BigUint)>,
+}
+
+impl PdlProof {
+    fn challenge(
+        encryption_key: &EncryptionKey,
+        ciphertext: &Ciphertext,
+        public: &Affine,
+        commitments: &[(Ciphertext, Affine)],
+    ) -> Vec<bool> {
+        let mut values = vec![
+            encryption_key.n.clone(),
+            ciphertext.0.clone(),
+            field_to_biguint(&public.x),
+            field_to_biguint(&public.y),
+        ];
+        for (a, y) in commitments.iter() {
+            values.extend([a.0.clone(), field_to_biguint(&y.x), field_to_biguint(&y.y)]);
+        }
+
+        let values: Vec<&BigUint> = values.iter().collect();
+        let bits = hash_to_biguint(PDL_TAG, &values, &(BigUint::one() << PDL_ROUNDS));
+        (0..PDL_ROUNDS)
+            .map(|i| ((&bits >> i) & BigUint::one()).is_one())
+            .collect()
+    }
+
+    fn prove<R: Rng>(
+        parameters: &SigningParameters,
+        encryption_key: &EncryptionKey,
+        ciphertext: &Ciphertext,
+        secret: &Fr,
+        randomness: &BigUint,
+        rng: &mut R,
+    ) -> Self {
+        let masks: Vec<(BigUint, BigUint)> = (0..PDL_ROUNDS)
+            .map(|_| {
+                (
+                    rng.gen_biguint(PDL_MASK_BITS),
+                    encryption_key.randomness(rng),
+                )
+            })
+            .collect();
+        let commitments: Vec<(Ciphertext, Affine)> = masks
+            .iter()
+            .map(|(alpha, beta)| {
+                (
+                    encryption_key.encrypt_with(alpha, beta),
+                    parameters
+                        .generator
+                        .mul(to_scalar(alpha).into_repr())
+                        .into_affine(),
+                )
+            })
+            .collect();
+
+        let public = parameters.generator.mul(secret.into_repr()).into_affine();
+        let challenge = Self::challenge(encryption_key, ciphertext, &public, &commitments);
+        let responses = masks
+            .into_iter()
+            .zip(challenge)
+            .map(|((alpha, beta), bit)| {
+                if bit {
+                    (
+                        alpha + to_biguint(secret),
+                        (beta * randomness) % &encryption_key.n,
+                    )
+                } else {
+                    (alpha, beta)
+                }
+            })
+            .collect();
+
+        Self {
+            commitments,
+            responses,
+        }
+    }
+
+    fn verify(
+        &self,
+        parameters: &SigningParameters,
+        encryption_key: &EncryptionKey,
+        ciphertext: &Ciphertext,
+        public: &Affine,
+    ) -> bool {
+        if self.commitments.len() != PDL_ROUNDS || self.responses.len() != PDL_ROUNDS {
+            return false;
+        }
+
+        let n = &encryption_key.n;
+        let bound = BigUint::one() << (PDL_MASK_BITS + 1);
+        let challenge = Self::challenge(encryption_key, ciphertext, public, &self.commitments);
+
+        self.commitments
+            .iter()
+            .zip(self.responses.iter())
+            .zip(challenge)
+            .all(|(((a, y), (z1, z2)), bit)| {
+                if *z1 >= bound || z2 >= n || !z2.gcd(n).is_one() || !encryption_key.is_valid(a) {
+                    return false;
+                }
+
+                let (expected_ciphertext, expected_point) = if bit {
+                    (
+                        encryption_key.add(a, ciphertext),
+                        y.into_projective() + public.into_projective(),
+                    )
+                } else {
+                    (a.clone(), y.into_projective())
+                };
+
+                encryption_key.encrypt_with(z1, z2) == expected_ciphertext
+                    && parameters.generator.mul(to_scalar(z1).into_repr()) == expected_point
+            })
+    }
+}
+
+impl CanonicalSerialize for PdlProof {
+    fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+        let (z1, z2): (Vec<BigUint>, Vec<BigUint>) = self.responses.iter().cloned().unzip();
+        self.commitments.serialize(&mut writer)?;
+        biguints_to_bytes(&z1).serialize(&mut writer)?;
+        biguints_to_bytes(&z2).serialize(writer)
+    }
+
+    fn serialized_size(&self) -> usize {
+        let (z1, z2): (Vec<BigUint>, Vec<BigUint>) = self.responses.iter().cloned().unzip();
+        self.commitments.serialized_size()
+            + biguints_to_bytes(&z1).serialized_size()
+            + biguints_to_bytes(&z2).serialized_size()
+    }
+}
+
+impl CanonicalDeserialize for PdlProof {
+    fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+        let commitments = Vec::<(Ciphertext, Affine)>::deserialize(&mut reader)?;
+        let z1 = Vec::<Vec<u8>>::deserialize(&mut reader)?;
+        let z2 = Vec::<Vec<u8>>::deserialize(reader)?;
+        if z1.len() != z2.len() {
+            return Err(SerializationError::InvalidData);
+        }
+
+        Ok(Self {
+            commitments,
+            responses: z1
+                .iter()
+                .zip(z2.iter())
+                .map(|(z1, z2)| (BigUint::from_bytes_le(z1), BigUint::from_bytes_le(z2)))
+                .collect(),
+        })
+    }
+}
+
+fn commitment(blinding: &Fq, public: &Affine, proof: &DLogProof) -> Fq {
+    // it's safe to unwrap, the tag is a short ascii string
+    let tag = short_string_to_field(COMMITMENT_TAG).unwrap();
+    poseidon_hash_many(&[
+        tag,
+        *blinding,
+        public.x,
+        public.y,
+        proof.a.x,
+        proof.a.y,
+        scalar_to_field(&proof.z),
+    ])
+}
+
+/// A random share with its public share and proof, committed to with a random blinding
+struct CommittedShare {
+    secret: Fr,
+    public: Affine,
+    proof: DLogProof,
+    blinding: Fq,
+}
+
+impl CommittedShare {
+    fn new<R: Rng>(parameters: &SigningParameters, rng: &mut R) -> Self {
+        let secret = Fr::rand(rng);
+        Self {
+            secret,
+            public: parameters.generator.mul(secret.into_repr()).into_affine(),
+            proof: DLogProof::prove(parameters, &secret, rng),
+            blinding: Fq::rand(rng),
+        }
+    }
+
+    fn commitment(&self) -> Fq {
+        commitment(&self.blinding, &self.public, &self.proof)
+    }
+}
+
+/// Opens the commitment of party 1 and checks the proof
+fn check_opening(
+    parameters: &SigningParameters,
+    commitment_value: &Fq,
+    blinding: &Fq,
+    public: &Affine,
+    proof: &DLogProof,
+) -> Result<(), Error> {
+    if commitment(blinding, public, proof) != *commitment_value || !proof.verify(parameters, public)
+    {
+        return Err(Error::InvalidShare);
+    }
+
+    Ok(())
+}
+
+/// Commitment to the public share of party 1
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Party1FirstMsg {
+    pub commitment: Fq,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Party2FirstMsg {
+    pub public_share: Affine,
+    pub proof: DLogProof,
+}
+
+/// Opening of the commitment, with the encrypted key share and its proofs in key generation
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Party1KeyGenSecondMsg {
+    pub public_share: Affine,
+    pub proof: DLogProof,
+    pub blinding: Fq,
+    pub encryption_key: EncryptionKey,
+    pub encrypted_share: Ciphertext,
+    pub correct_key_proof: CorrectKeyProof,
+    pub pdl_proof: PdlProof,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Party1SignSecondMsg {
+    pub public_share: Affine,
+    pub proof: DLogProof,
+    pub blinding: Fq,
+}
+
+/// Encryption of the signature before the multiplication by k1^-1
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Party2SignSecondMsg {
+    pub partial_signature: Ciphertext,
+}
+
+/// State of party 1 between its two key generation or signing messages
+pub struct Party1State {
+    share: CommittedShare,
+}
+
+impl Party1State {
+    /// Serializes the secret share, its proof and the blinding of the commitment
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![];
+        (self.share.secret, self.share.proof, self.share.blinding)
+            .serialize(&mut bytes)
+            .map_err(|_| Error::IOError)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(parameters: &SigningParameters, bytes: &[u8]) -> Result<Self, Error> {
+        let (secret, proof, blinding) =
+            <(Fr, DLogProof, Fq)>::deserialize(bytes).map_err(|_| Error::IOError)?;
+        // the share is inverted when signing
+        if secret.is_zero() {
+            return Err(Error::IOError);
+        }
+
+        Ok(Self {
+            share: CommittedShare {
+                secret,
+                public: parameters.generator.mul(secret.into_repr()).into_affine(),
+                proof,
+                blinding,
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Party1Key {
+    x1: Fr,
+    decryption_key: DecryptionKey,
+    pub public_key: Affine,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Party2Key {
+    x2: Fr,
+    encryption_key: EncryptionKey,
+    encrypted_share: Ciphertext,
+    pub public_key: Affine,
+}
+
+impl Party1Key {
+    /// The stark key of the shared private key
+    pub fn stark_key(&self) -> Fq {
+        self.public_key.x
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![];
+        (self.x1, self.decryption_key.clone(), self.public_key)
+            .serialize(&mut bytes)
+            .map_err(|_| Error::IOError)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (x1, decryption_key, public_key) =
+            <(Fr, DecryptionKey, Affine)>::deserialize(bytes).map_err(|_| Error::IOError)?;
+
+        Ok(Self {
+            x1,
+            decryption_key,
+            public_key,
+        })
+    }
+}
+
+impl Party2Key {
+    pub fn stark_key(&self) -> Fq {
+        self.public_key.x
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = vec![];
+        (
+            self.x2,
+            self.encryption_key.clone(),
+            self.encrypted_share.clone(),
+            self.public_key,
+        )
+            .serialize(&mut bytes)
+            .map_err(|_| Error::IOError)?;
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (x2, encryption_key, encrypted_share, public_key) =
+            <(Fr, EncryptionKey, Ciphertext, Affine)>::deserialize(bytes)
+                .map_err(|_| Error::IOError)?;
+        if encryption_key.n.bits() < MIN_PAILLIER_BITS || !encryption_key.is_valid(&encrypted_share)
+        {
+            return Err(Error::InvalidPublicKey);
+        }
+
+        Ok(Self {
+            x2,
+            encryption_key,
+            encrypted_share,
+            public_key,
+        })
+    }
+}
+
+pub mod keygen {
+    use super::*;
+
+    pub fn party1_first<R: Rng>(
+        parameters: &SigningParameters,
+        rng: &mut R,
+    ) -> (Party1State, Party1FirstMsg) {
+        let share = CommittedShare::new(parameters, rng);
+        let msg = Party1FirstMsg {
+            commitment: share.commitment(),
+        };
+
+        (Party1State { share }, msg)
+    }
+
+    /// Returns the secret share of party 2 with its public part
+    pub fn party2_first<R: Rng>(
+        parameters: &SigningParameters,
+        rng: &mut R,
+    ) -> (Fr, Party2FirstMsg) {
+        let x2 = Fr::rand(rng);
+        let msg = Party2FirstMsg {
+            public_share: parameters.generator.mul(x2.into_repr()).into_affine(),
+            proof: DLogProof::prove(parameters, &x2, rng),
+        };
+
+        (x2, msg)
+    }
+
+    /// paillier_bits should be PAILLIER_BITS outside of tests
+    pub fn party1_second<R: Rng>(
+        parameters: &SigningParameters,
+        state: Party1State,
+        party2_msg: &Party2FirstMsg,
+        paillier_bits: usize,
+        rng: &mut R,
+    ) -> Result<(Party1Key, Party1KeyGenSecondMsg), Error> {
+        if !party2_msg
+            .proof
+            .verify(parameters, &party2_msg.public_share)
+        {
+            return Err(Error::InvalidShare);
+        }
+        if paillier_bits < MIN_PAILLIER_BITS {
+            return Err(Error::IncorrectLenError);
+        }
+
+        let share = state.share;
+        let decryption_key = DecryptionKey::generate(paillier_bits, rng);
+        let encryption_key = decryption_key.encryption_key.clone();
+        let randomness = encryption_key.randomness(rng);
+        let encrypted_share = encryption_key.encrypt_with(&to_biguint(&share.secret), &randomness);
+        let pdl_proof = PdlProof::prove(
+            parameters,
+            &encryption_key,
+            &encrypted_share,
+            &share.secret,
+            &randomness,
+            rng,
+        );
+        let correct_key_proof = decryption_key.correct_key_proof();
+
+        let key = Party1Key {
+            x1: share.secret,
+            decryption_key,
+            public_key: party2_msg
+                .public_share
+                .mul(share.secret.into_repr())
+                .into_affine(),
+        };
+        let msg = Party1KeyGenSecondMsg {
+            public_share: share.public,
+            proof: share.proof,
+            blinding: share.blinding,
+            encryption_key,
+            encrypted_share,
+            correct_key_proof,
+            pdl_proof,
+        };
+
+        Ok((key, msg))
+    }
+
+    pub fn party2_second(
+        parameters: &SigningParameters,
+        x2: Fr,
+        party1_first_msg: &Party1FirstMsg,
+        party1_second_msg: &Party1KeyGenSecondMsg,
+    ) -> Result<Party2Key, Error> {
+        check_opening(
+            parameters,
+            &party1_first_msg.commitment,
+            &party1_second_msg.blinding,
+            &party1_second_msg.public_share,
+            &party1_second_msg.proof,
+        )?;
+
+        let encryption_key = party1_second_msg.encryption_key.clone();
+        if encryption_key.n.bits() < MIN_PAILLIER_BITS
+            || !encryption_key.is_valid(&party1_second_msg.encrypted_share)
+            || !encryption_key.verify_correct_key(&party1_second_msg.correct_key_proof)
+        {
+            return Err(Error::InvalidPublicKey);
+        }
+        if !party1_second_msg.pdl_proof.verify(
+            parameters,
+            &encryption_key,
+            &party1_second_msg.encrypted_share,
+            &party1_second_msg.public_share,
+        ) {
+            return Err(Error::InvalidShare);
+        }
+
+        Ok(Party2Key {
+            x2,
+            encryption_key,
+            encrypted_share: party1_second_msg.encrypted_share.clone(),
+            public_key: party1_second_msg
+                .public_share
+                .mul(x2.into_repr())
+                .into_affine(),
+        })
+    }
+}
+
+pub mod sign {
+    use super::*;
+
+    /// State of party 2 between its nonce and its partial signature
+    pub struct Party2State {
+        k2: Fr,
+    }
+
+    impl Party2State {
+        pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+            let mut bytes = vec![];
+            self.k2.serialize(&mut bytes).map_err(|_| Error::IOError)?;
+
+            Ok(bytes)
+        }
+
+        pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+            let k2 = Fr::deserialize(bytes).map_err(|_| Error::IOError)?;
+            // the nonce is inverted when signing
+            if k2.is_zero() {
+                return Err(Error::IOError);
+            }
+
+            Ok(Self { k2 })
+        }
+    }
+
+    fn r_from_nonce_point(nonce_point: &Affine) -> Result<Fr, Error> {
+        let r = nonce_point.x.into_repr();
+        if !(r >= Fr::one().into_repr() && r < TWO_MODULUS_BITS) {
+            return Err(Error::UnsignableMessage);
+        }
+
+        // r < 2**251 so it's safe to unwrap
+        Ok(Fr::from_repr(r).unwrap())
+    }
+
+    pub fn party1_first<R: Rng>(
+        parameters: &SigningParameters,
+        rng: &mut R,
+    ) -> (Party1State, Party1FirstMsg) {
+        super::keygen::party1_first(parameters, rng)
+    }
+
+    pub fn party2_first<R: Rng>(
+        parameters: &SigningParameters,
+        rng: &mut R,
+    ) -> (Party2State, Party2FirstMsg) {
+        let (k2, msg) = super::keygen::party2_first(parameters, rng);
+        (Party2State { k2 }, msg)
+    }
+
+    /// Opens the nonce commitment, party 1 keeps its state to finish the signature
+    pub fn party1_second(
+        parameters: &SigningParameters,
+        state: &Party1State,
+        party2_msg: &Party2FirstMsg,
+    ) -> Result<Party1SignSecondMsg, Error> {
+        if !party2_msg
+            .proof
+            .verify(parameters, &party2_msg.public_share)
+        {
+            return Err(Error::InvalidShare);
+        }
+
+        Ok(Party1SignSecondMsg {
+            public_share: state.share.public,
+            proof: state.share.proof,
+            blinding: state.share.blinding,
+        })
+    }
+
+    pub fn party2_second<R: Rng>(
+        parameters: &SigningParameters,
+        key: &Party2Key,
+        state: Party2State,
+        msg_hash: &Fq,
+        party1_first_msg: &Party1FirstMsg,
+        party1_second_msg: &Party1SignSecondMsg,
+        rng: &mut R,
+    ) -> Result<Party2SignSecondMsg, Error> {
+        if msg_hash.into_repr() >= TWO_MODULUS_BITS {
+            return Err(Error::UnsignableMessage);
+        }
+        check_opening(
+            parameters,
+            &party1_first_msg.commitment,
+            &party1_second_msg.blinding,
+            &party1_second_msg.public_share,
+            &party1_second_msg.proof,
+        )?;
+
+        let nonce_point = party1_second_msg
+            .public_share
+            .mul(state.k2.into_repr())
+            .into_affine();
+        let r = r_from_nonce_point(&nonce_point)?;
+
+        // k2 is never zero so it's safe to unwrap
+        let k2_inverse = state.k2.inverse().unwrap();
+        let m = field_to_scalar(msg_hash);
+
+        // Enc(rho * EC_ORDER + k2^-1 * m), the multiple of EC_ORDER hides the value modulo n
+        let order = BigUint::from_bytes_le(&FrParameters::MODULUS.to_bytes_le());
+        let rho = rng.gen_biguint_below(&(&order * &order));
+        let c1 = key
+            .encryption_key
+            .encrypt(&(rho * &order + to_biguint(&(k2_inverse * m))), rng);
+
+        // Enc(x1)^(k2^-1 * r * x2)
+        let v = k2_inverse * r * key.x2;
+        let c2 = key
+            .encryption_key
+            .mul(&key.encrypted_share, &to_biguint(&v));
+
+        Ok(Party2SignSecondMsg {
+            partial_signature: key.encryption_key.add(&c1, &c2),
+        })
+    }
+
+    pub fn party1_third(
+        parameters: &SigningParameters,
+        key: &Party1Key,
+        state: Party1State,
+        msg_hash: &Fq,
+        party2_first_msg: &Party2FirstMsg,
+        party2_second_msg: &Party2SignSecondMsg,
+    ) -> Result<Signature, Error> {
+        let k1 = state.share.secret;
+        let nonce_point = party2_first_msg
+            .public_share
+            .mul(k1.into_repr())
+            .into_affine();
+        let r = r_from_nonce_point(&nonce_point)?;
+
+        let s_prime = to_scalar(
+            &key.decryption_key
+                .decrypt(&party2_second_msg.partial_signature),
+        );
+        // k1 is never zero so it's safe to unwrap
+        let s = k1.inverse().unwrap() * s_prime;
+
+        // w = s^-1 must be in the range accepted by cairo too
+        let w = s.inverse().ok_or(Error::UnsignableMessage)?;
+        if w.into_repr() >= TWO_MODULUS_BITS {
+            return Err(Error::UnsignableMessage);
+        }
+
+        let signature = Signature { r, s };
+        if !verify(parameters, &key.public_key, msg_hash, &signature) {
+            return Err(Error::InvalidSignature);
+        }
+
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keygen, sign, to_biguint, Party1Key, Party1State, Party2Key, Party2SignSecondMsg};
+    use crate::error::Error;
+    use crate::signature::{parameters, verify, Signature};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::UniformRand;
+    use num_bigint_dig::BigUint;
+    use rand::thread_rng;
+    use rand::Rng;
+    use starknet_curve::{Affine, Fq, Fr};
+
+    // smallest modulus accepted, key generation is slow in debug builds
+    const TEST_PAILLIER_BITS: usize = 1024;
+
+    fn generate_keys() -> (Party1Key, Party2Key) {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let (state, party1_first_msg) = keygen::party1_first(&parameters, rng);
+        let (x2, party2_first_msg) = keygen::party2_first(&parameters, rng);
+        let (party1_key, party1_second_msg) = keygen::party1_second(
+            &parameters,
+            state,
+            &party2_first_msg,
+            TEST_PAILLIER_BITS,
+            rng,
+        )
+        .unwrap();
+        let party2_key =
+            keygen::party2_second(&parameters, x2, &party1_first_msg, &party1_second_msg).unwrap();
+
+        (party1_key, party2_key)
+    }
+
+    fn sign_with(party1_key: &Party1Key, party2_key: &Party2Key, msg_hash: &Fq) -> Signature {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        loop {
+            let (party1_state, party1_first_msg) = sign::party1_first(&parameters, rng);
+            let (party2_state, party2_first_msg) = sign::party2_first(&parameters, rng);
+            let party1_second_msg =
+                sign::party1_second(&parameters, &party1_state, &party2_first_msg).unwrap();
+
+            // states are kept in their serialized form between the messages
+            let party1_state =
+                Party1State::from_bytes(&parameters, &party1_state.to_bytes().unwrap()).unwrap();
+            let party2_state =
+                sign::Party2State::from_bytes(&party2_state.to_bytes().unwrap()).unwrap();
+
+            let party2_second_msg = match sign::party2_second(
+                &parameters,
+                party2_key,
+                party2_state,
+                msg_hash,
+                &party1_first_msg,
+                &party1_second_msg,
+                rng,
+            ) {
+                Err(Error::UnsignableMessage) => continue,
+                msg => msg.unwrap(),
+            };
+
+            // messages go through the serialized form
+            let mut bytes = vec![];
+            party2_second_msg.serialize(&mut bytes).unwrap();
+            let party2_second_msg = Party2SignSecondMsg::deserialize(bytes.as_slice()).unwrap();
+
+            match sign::party1_third(
+                &parameters,
+                party1_key,
+                party1_state,
+                msg_hash,
+                &party2_first_msg,
+                &party2_second_msg,
+            ) {
+                Err(Error::UnsignableMessage) => continue,
+                signature => break signature.unwrap(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_two_party_signature() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let (party1_key, party2_key) = generate_keys();
+        assert_eq!(party1_key.public_key, party2_key.public_key);
+
+        let party1_bytes = party1_key.to_bytes().unwrap();
+        let party2_bytes = party2_key.to_bytes().unwrap();
+        assert_eq!(party1_key, Party1Key::from_bytes(&party1_bytes).unwrap());
+        assert_eq!(party2_key, Party2Key::from_bytes(&party2_bytes).unwrap());
+        assert_eq!(
+            Some(Error::IOError),
+            Party1Key::from_bytes(&party1_bytes[..party1_bytes.len() - 1]).err()
+        );
+        assert_eq!(
+            Some(Error::IOError),
+            sign::Party2State::from_bytes(&[0u8; 32]).err()
+        );
+
+        for _ in 0..3 {
+            let msg_hash = Fq::from(u64::rand(rng));
+            let signature = sign_with(&party1_key, &party2_key, &msg_hash);
+
+            // as in Verifier.cairo, only the stark key is known
+            let public_key = Affine::get_point_from_x(party1_key.stark_key(), false).unwrap();
+            assert!(
+                verify(&parameters, &public_key, &msg_hash, &signature)
+                    || verify(&parameters, &-public_key, &msg_hash, &signature)
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let (state, party1_first_msg) = keygen::party1_first(&parameters, rng);
+        let (x2, mut party2_first_msg) = keygen::party2_first(&parameters, rng);
+
+        // the proof of party 2 doesn't match another public share
+        let (_, other_msg) = keygen::party2_first(&parameters, rng);
+        party2_first_msg.public_share = other_msg.public_share;
+        assert_eq!(
+            Some(Error::InvalidShare),
+            keygen::party1_second(
+                &parameters,
+                state,
+                &party2_first_msg,
+                TEST_PAILLIER_BITS,
+                rng
+            )
+            .err()
+        );
+
+        // party 1 can't change its share after the commitment
+        let (state, other_first_msg) = keygen::party1_first(&parameters, rng);
+        let (_, party1_second_msg) =
+            keygen::party1_second(&parameters, state, &other_msg, TEST_PAILLIER_BITS, rng).unwrap();
+        assert_eq!(
+            Some(Error::InvalidShare),
+            keygen::party2_second(&parameters, x2, &party1_first_msg, &party1_second_msg).err()
+        );
+
+        // nor encrypt anything else than its share
+        let encryption_key = &party1_second_msg.encryption_key;
+        let other_share = Fr::rand(rng);
+        let mut tampered = party1_second_msg.clone();
+        tampered.encrypted_share = encryption_key.encrypt(&to_biguint(&other_share), rng);
+        assert_eq!(
+            Some(Error::InvalidShare),
+            keygen::party2_second(&parameters, x2, &other_first_msg, &tampered).err()
+        );
+
+        // and its Paillier modulus is proven to be well formed
+        let mut tampered = party1_second_msg.clone();
+        let root = &mut tampered.correct_key_proof.roots[0];
+        *root = (&*root + BigUint::from(1u32)) % &encryption_key.n;
+        assert_eq!(
+            Some(Error::InvalidPublicKey),
+            keygen::party2_second(&parameters, x2, &other_first_msg, &tampered).err()
+        );
+
+        let (state, _) = keygen::party1_first(&parameters, rng);
+        assert_eq!(
+            Some(Error::IncorrectLenError),
+            keygen::party1_second(&parameters, state, &other_msg, rng.gen_range(0..1024), rng)
+                .err()
+        );
+    }
+}
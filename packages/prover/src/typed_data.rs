@@ -0,0 +1,357 @@
+use crate::{
+    cairo_serde::to_felts,
+    error::Error,
+    hash::HashFunction,
+    keccak::starknet_keccak,
+    util::{be_bytes_to_field, hex_to_field, short_string_to_field},
+};
+
+use ark_ff::{PrimeField, Zero};
+use num_bigint_dig::BigUint;
+use starknet_curve::Fq;
+
+/*
+Building blocks of SNIP-12 typed data hashing (https://github.com/starknet-io/SNIPs/blob/main/SNIPS/snip-12.md)
+for structs whose type is known in advance, as starknet.js computes them.
+
+Revision 0 hashes with compute_hash_on_elements and revision 1 with poseidon_hash_many:
+    struct_hash = hash([starknet_keccak(encoded type), encoded fields...])
+    message_hash = hash(["StarkNet Message", domain struct_hash, account address, struct_hash])
+*/
+
+pub const MESSAGE_PREFIX: &str = "StarkNet Message";
+
+const DOMAIN_TYPE_V0: &str = "StarkNetDomain(name:felt,version:felt,chainId:felt)";
+const DOMAIN_TYPE_V1: &str = "\"StarknetDomain\"(\"name\":\"shortstring\",\"version\":\"shortstring\",\"chainId\":\"shortstring\",\"revision\":\"shortstring\")";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Revision {
+    V0,
+    V1,
+}
+
+impl Revision {
+    pub fn hasher(&self) -> HashFunction {
+        match self {
+            Self::V0 => HashFunction::Pedersen,
+            Self::V1 => HashFunction::Poseidon,
+        }
+    }
+}
+
+/// Hash of the encoded type, e.g. "Policy(contractAddress:felt,selector:selector)"
+pub fn type_hash(encoded_type: &str) -> Fq {
+    starknet_keccak(encoded_type.as_bytes())
+}
+
+pub fn struct_hash(revision: Revision, type_hash: &Fq, fields: &[Fq]) -> Fq {
+    let mut data = vec![*type_hash];
+    data.extend_from_slice(fields);
+
+    revision.hasher().hash_many(&data)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StarknetDomain {
+    pub name: Fq,
+    pub version: Fq,
+    pub chain_id: Fq,
+    pub revision: Revision,
+}
+
+/// Encodes a domain field as starknet.js does, whole numbers in decimal or 0x prefixed hex are
+/// numbers and anything else is a short string, so "1" is 0x1 and not 0x31
+fn encode_shortstring(value: &str) -> Result<Fq, Error> {
+    if let Some(hex) = value.strip_prefix("0x") {
+        if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return hex_to_field(value);
+        }
+    }
+
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        // it's safe to unwrap, the value only has decimal digits
+        let number = BigUint::parse_bytes(value.as_bytes(), 10).unwrap();
+        return be_bytes_to_field(&number.to_bytes_be());
+    }
+
+    short_string_to_field(value)
+}
+
+impl StarknetDomain {
+    /// name, version and chain id as in the domain of starknet.js typed data, e.g. "SN_MAIN"
+    pub fn new(
+        name: &str,
+        version: &str,
+        chain_id: &str,
+        revision: Revision,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            name: encode_shortstring(name)?,
+            version: encode_shortstring(version)?,
+            chain_id: encode_shortstring(chain_id)?,
+            revision,
+        })
+    }
+
+    pub fn hash(&self) -> Fq {
+        match self.revision {
+            Revision::V0 => struct_hash(
+                self.revision,
+                &type_hash(DOMAIN_TYPE_V0),
+                &[self.name, self.version, self.chain_id],
+            ),
+            Revision::V1 => struct_hash(
+                self.revision,
+                &type_hash(DOMAIN_TYPE_V1),
+                &[self.name, self.version, self.chain_id, Fq::from(1u64)],
+            ),
+        }
+    }
+
+    /// Hash that the account signs for the struct
+    pub fn message_hash(&self, account: &Fq, struct_hash: &Fq) -> Fq {
+        // it's safe to unwrap, the prefix is a short ascii string
+        let prefix = short_string_to_field(MESSAGE_PREFIX).unwrap();
+
+        self.revision
+            .hasher()
+            .hash_many(&[prefix, self.hash(), *account, *struct_hash])
+    }
+}
+
+/// Revision 1 string, the poseidon_hash_many of the serialized ByteArray
+/// [number of full words, full words..., pending word, pending word length]
+pub fn string_hash(value: &str) -> Fq {
+    HashFunction::Poseidon.hash_many(&to_felts(&value.to_string()))
+}
+
+/// Nodes hash as hash(min, max) with the two to one hash of the revision, and the last node of a
+/// level with an odd number of nodes is hashed with 0, as the merkletree type of starknet.js
+fn merkle_levels(leaves: &[Fq], revision: Revision) -> Result<Vec<Vec<Fq>>, Error> {
+    if leaves.is_empty() {
+        return Err(Error::EmptyDataError);
+    }
+
+    let mut levels = vec![leaves.to_vec()];
+    while levels.last().unwrap().len() > 1 {
+        let level = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| {
+                let sibling = pair.get(1).copied().unwrap_or_else(Fq::zero);
+                sorted_hash(revision, &pair[0], &sibling)
+            })
+            .collect();
+        levels.push(level);
+    }
+
+    Ok(levels)
+}
+
+fn sorted_hash(revision: Revision, a: &Fq, b: &Fq) -> Fq {
+    if a.into_repr() < b.into_repr() {
+        revision.hasher().hash(a, b)
+    } else {
+        revision.hasher().hash(b, a)
+    }
+}
+
+pub fn merkle_root(leaves: &[Fq], revision: Revision) -> Result<Fq, Error> {
+    Ok(merkle_levels(leaves, revision)?.last().unwrap()[0])
+}
+
+/// Siblings of the leaf from the bottom up, None if the leaf is not in the tree
+pub fn merkle_proof(
+    leaves: &[Fq],
+    leaf: &Fq,
+    revision: Revision,
+) -> Result<Option<Vec<Fq>>, Error> {
+    let levels = merkle_levels(leaves, revision)?;
+    let mut index = match leaves.iter().position(|node| node == leaf) {
+        Some(index) => index,
+        None => return Ok(None),
+    };
+
+    let mut proof = vec![];
+    for level in levels[..levels.len() - 1].iter() {
+        proof.push(level.get(index ^ 1).copied().unwrap_or_else(Fq::zero));
+        index /= 2;
+    }
+
+    Ok(Some(proof))
+}
+
+pub fn verify_merkle_proof(root: &Fq, leaf: &Fq, proof: &[Fq], revision: Revision) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*leaf, |node, sibling| sorted_hash(revision, &node, sibling));
+
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        merkle_proof, merkle_root, string_hash, struct_hash, type_hash, verify_merkle_proof,
+        Revision, StarknetDomain, DOMAIN_TYPE_V0, DOMAIN_TYPE_V1,
+    };
+    use crate::error::Error;
+    use crate::poseidon::poseidon_hash_many;
+    use crate::util::{hex_to_field, short_string_to_field};
+    use starknet_curve::Fq;
+
+    #[test]
+    fn test_string_hash() {
+        let word = |value: &str| short_string_to_field::<Fq>(value).unwrap();
+
+        assert_eq!(
+            poseidon_hash_many(&[Fq::from(0u64), word("gm"), Fq::from(2u64)]),
+            string_hash("gm")
+        );
+        assert_eq!(
+            poseidon_hash_many(&[Fq::from(0u64), Fq::from(0u64), Fq::from(0u64)]),
+            string_hash("")
+        );
+
+        let long = "a".repeat(31) + "bc";
+        assert_eq!(
+            poseidon_hash_many(&[
+                Fq::from(1u64),
+                word(&"a".repeat(31)),
+                word("bc"),
+                Fq::from(2u64)
+            ]),
+            string_hash(&long)
+        );
+    }
+
+    #[test]
+    fn test_merkle_tree() {
+        for revision in [Revision::V0, Revision::V1] {
+            for n_leaves in 1..=7u64 {
+                let leaves: Vec<Fq> = (0..n_leaves).map(|i| Fq::from(100 + i)).collect();
+                let root = merkle_root(&leaves, revision).unwrap();
+
+                for leaf in leaves.iter() {
+                    let proof = merkle_proof(&leaves, leaf, revision).unwrap().unwrap();
+                    assert!(verify_merkle_proof(&root, leaf, &proof, revision));
+                    assert!(!verify_merkle_proof(
+                        &root,
+                        &Fq::from(1u64),
+                        &proof,
+                        revision
+                    ));
+                }
+            }
+
+            assert_eq!(
+                None,
+                merkle_proof(&[Fq::from(1u64)], &Fq::from(2u64), revision).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_domain() {
+        let v0 = StarknetDomain::new("app", "1", "SN_MAIN", Revision::V0).unwrap();
+        let v1 = StarknetDomain {
+            revision: Revision::V1,
+            ..v0
+        };
+
+        // whole numbers are numbers as in starknet.js, anything else is a short string
+        assert_eq!(Fq::from(1u64), v0.version);
+        assert_eq!(
+            v0,
+            StarknetDomain::new("app", "0x1", "SN_MAIN", Revision::V0).unwrap()
+        );
+        assert_eq!(
+            short_string_to_field::<Fq>("v1").unwrap(),
+            StarknetDomain::new("app", "v1", "SN_MAIN", Revision::V0)
+                .unwrap()
+                .version
+        );
+        assert_eq!(
+            Some(Error::OverflowError),
+            StarknetDomain::new("app", &"9".repeat(77), "SN_MAIN", Revision::V0).err()
+        );
+
+        // type hashes of SNIP-12
+        assert_eq!(
+            hex_to_field::<Fq>("0x1bfc207425a47a5dfa1a50a4f5241203f50624ca5fdf5e18755765416b8e288")
+                .unwrap(),
+            type_hash(DOMAIN_TYPE_V0)
+        );
+        assert_eq!(
+            hex_to_field::<Fq>("0x1ff2f602e42168014d405a94f75e8a93d640751d71d16311266e140d8b0a210")
+                .unwrap(),
+            type_hash(DOMAIN_TYPE_V1)
+        );
+
+        assert_ne!(v0.hash(), v1.hash());
+        assert_ne!(
+            v1.message_hash(&Fq::from(1u64), &Fq::from(2u64)),
+            v1.message_hash(&Fq::from(3u64), &Fq::from(2u64))
+        );
+        assert!(
+            StarknetDomain::new("a".repeat(32).as_str(), "1", "SN_MAIN", Revision::V1).is_err()
+        );
+    }
+
+    #[test]
+    fn test_starknet_js_examples() {
+        // typedDataExample and typedDataStructArrayExample of starknet.js __tests__/utils/typedData.test.ts
+        let word = |value: &str| short_string_to_field::<Fq>(value).unwrap();
+        let felt = |value: &str| hex_to_field::<Fq>(value).unwrap();
+        let domain = StarknetDomain::new("StarkNet Mail", "1", "1", Revision::V0).unwrap();
+        let account = felt("0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826");
+
+        let person_type = "Person(name:felt,wallet:felt)";
+        let person = |name: &str, wallet: &str| {
+            struct_hash(
+                Revision::V0,
+                &type_hash(person_type),
+                &[word(name), felt(wallet)],
+            )
+        };
+        let from = person("Cow", "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826");
+        let to = person("Bob", "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB");
+
+        let mail = struct_hash(
+            Revision::V0,
+            &type_hash(&format!(
+                "Mail(from:Person,to:Person,contents:felt){person_type}"
+            )),
+            &[from, to, word("Hello, Bob!")],
+        );
+        assert_eq!(
+            felt("0x6fcff244f63e38b9d88b9e3378d44757710d1b244282b435cb472053c8d78d0"),
+            domain.message_hash(&account, &mail)
+        );
+
+        let post_type = "Post(title:felt,content:felt)";
+        let post = |title: &str, content: &str| {
+            struct_hash(
+                Revision::V0,
+                &type_hash(post_type),
+                &[word(title), word(content)],
+            )
+        };
+        let posts = Revision::V0.hasher().hash_many(&[
+            post("Greeting", "Hello, Bob!"),
+            post("Farewell", "Goodbye, Bob!"),
+        ]);
+        let mail = struct_hash(
+            Revision::V0,
+            &type_hash(&format!(
+                "Mail(from:Person,to:Person,posts_len:felt,posts:Post*){person_type}{post_type}"
+            )),
+            &[from, to, Fq::from(2u64), posts],
+        );
+        assert_eq!(
+            felt("0x5914ed2764eca2e6a41eb037feefd3d2e33d9af6225a9e7fe31ac943ff712c"),
+            domain.message_hash(&account, &mail)
+        );
+    }
+}
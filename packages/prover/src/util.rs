@@ -1,5 +1,5 @@
 use crate::Error;
-use ark_ff::{BigInteger256, FpParameters, FromBytes, PrimeField};
+use ark_ff::{BigInteger, BigInteger256, FpParameters, FromBytes, PrimeField};
 
 pub fn bytes_safe<F: PrimeField<BigInt = BigInteger256>>(
     unchecked_bytes: &Vec<u8>,
@@ -11,7 +11,7 @@ pub fn bytes_safe<F: PrimeField<BigInt = BigInteger256>>(
 
     let repr = BigInteger256::read(unchecked_bytes.as_slice()).map_err(|_| Error::IOError)?;
 
-    if repr > F::Params::MODULUS {
+    if repr >= F::Params::MODULUS {
         return Err(Error::OverflowError);
     }
 
@@ -26,3 +26,134 @@ pub fn try_bytes_to_field<F: PrimeField<BigInt = BigInteger256>>(
     // it's safe to unwrap
     Ok(F::from_repr(repr).unwrap())
 }
+
+/// bytes are expected to be in BE representation, shorter inputs are left padded with zeros
+pub fn be_bytes_to_field<F: PrimeField<BigInt = BigInteger256>>(
+    unchecked_bytes: &[u8],
+) -> Result<F, Error> {
+    if unchecked_bytes.len() > 32 {
+        return Err(Error::IncorrectLenError);
+    }
+
+    let mut bytes = vec![0u8; 32 - unchecked_bytes.len()];
+    bytes.extend_from_slice(unchecked_bytes);
+    bytes.reverse();
+
+    try_bytes_to_field(&bytes)
+}
+
+/// Parses a BE hex string, the 0x prefix is optional and odd lengths are left padded with a zero
+pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, Error> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::TypeError);
+    }
+
+    let hex = if hex.len().is_multiple_of(2) {
+        hex.to_string()
+    } else {
+        format!("0{}", hex)
+    };
+
+    // it's safe to unwrap since all chars are ascii hex digits
+    Ok((0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+pub fn hex_to_field<F: PrimeField<BigInt = BigInteger256>>(hex: &str) -> Result<F, Error> {
+    let bytes = hex_to_bytes(hex)?;
+    if bytes.is_empty() {
+        return Err(Error::IncorrectLenError);
+    }
+
+    be_bytes_to_field(&bytes)
+}
+
+/// Formats a field element as a 0x prefixed BE hex string without leading zeros
+pub fn field_to_hex<F: PrimeField<BigInt = BigInteger256>>(element: &F) -> String {
+    let hex: String = element
+        .into_repr()
+        .to_bytes_be()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    match hex.trim_start_matches('0') {
+        "" => "0x0".to_string(),
+        stripped => format!("0x{}", stripped),
+    }
+}
+
+/// Encodes a cairo short string, at most 31 ascii characters, as a felt
+pub fn short_string_to_field<F: PrimeField<BigInt = BigInteger256>>(
+    value: &str,
+) -> Result<F, Error> {
+    if !value.is_ascii() {
+        return Err(Error::TypeError);
+    }
+
+    if value.len() > 31 {
+        return Err(Error::IncorrectLenError);
+    }
+
+    be_bytes_to_field(value.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_to_hex, hex_to_field, short_string_to_field};
+    use crate::Error;
+    use starknet_curve::Fq;
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let element: Fq =
+            hex_to_field("0x1ef15c18599971b7beced415a40f0c7deacfd9b0d1819e03d723d8bc943cfca")
+                .unwrap();
+        assert_eq!(
+            "0x1ef15c18599971b7beced415a40f0c7deacfd9b0d1819e03d723d8bc943cfca",
+            field_to_hex(&element)
+        );
+
+        assert_eq!(Ok(Fq::from(17u64)), hex_to_field::<Fq>("11"));
+        assert_eq!(Ok(Fq::from(10u64)), hex_to_field::<Fq>("0x00a"));
+        assert_eq!("0x0", field_to_hex(&Fq::from(0u64)));
+    }
+
+    #[test]
+    fn test_invalid_hex() {
+        assert_eq!(Err(Error::TypeError), hex_to_field::<Fq>("0xzz"));
+        assert_eq!(Err(Error::IncorrectLenError), hex_to_field::<Fq>("0x"));
+        assert_eq!(
+            Err(Error::OverflowError),
+            hex_to_field::<Fq>(
+                "0x0900000000000000000000000000000000000000000000000000000000000000"
+            )
+        );
+
+        // P and P + 1
+        assert_eq!(
+            Err(Error::OverflowError),
+            hex_to_field::<Fq>("0x800000000000011000000000000000000000000000000000000000000000001")
+        );
+        assert_eq!(
+            Err(Error::OverflowError),
+            hex_to_field::<Fq>("0x800000000000011000000000000000000000000000000000000000000000002")
+        );
+    }
+
+    #[test]
+    fn test_short_string() {
+        assert_eq!(
+            Ok(crate::constants::STARKNET_STATE_V0),
+            short_string_to_field::<Fq>("STARKNET_STATE_V0")
+        );
+        assert_eq!(Ok(Fq::from(0u64)), short_string_to_field::<Fq>(""));
+        assert_eq!(
+            Err(Error::IncorrectLenError),
+            short_string_to_field::<Fq>("this string is longer than 31 chars")
+        );
+    }
+}
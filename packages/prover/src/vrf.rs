@@ -0,0 +1,261 @@
+use crate::{
+    error::Error,
+    poseidon::poseidon_hash_many,
+    rfc6979::generate_k_rfc6979,
+    schnorr::lift_x,
+    signature::{private_key_to_public_key, SigningParameters},
+    util::short_string_to_field,
+};
+
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, BigInteger256, FpParameters, PrimeField, Zero};
+use starknet_curve::{Affine, Fq, Fr};
+
+/*
+ECVRF with the structure of RFC 9381, where every hash is Poseidon with a short string tag so that
+proofs can be verified in cairo. The public key is the stark key lifted to the point with an even
+y coordinate, as in the schnorr module.
+
+H = encode_to_curve(Y, alpha), by try and increment on poseidon([tag, Y.x, alpha..., counter])
+Gamma = x*H
+c = poseidon([tag, Y, H, Gamma, k*G, k*H]) truncated to 128 bits, s = k + c*x
+beta = poseidon([tag, Gamma.x, Gamma.y])
+
+A proof is the four felts [Gamma.x, Gamma.y, c, s] and beta is the random output.
+*/
+
+const ENCODE_TO_CURVE_TAG: &str = "STARK_ECVRF_H2C";
+const NONCE_TAG: &str = "STARK_ECVRF_NONCE";
+const CHALLENGE_TAG: &str = "STARK_ECVRF_CHALLENGE";
+const PROOF_TO_HASH_TAG: &str = "STARK_ECVRF_OUTPUT";
+
+// challenges are truncated to cLen = 16 bytes as in RFC 9381
+const CHALLENGE_BITS: usize = 128;
+
+fn tagged_hash(tag: &str, data: &[Fq]) -> Fq {
+    // it's safe to unwrap, tags are short ascii strings
+    let mut input = vec![short_string_to_field(tag).unwrap()];
+    input.extend_from_slice(data);
+
+    poseidon_hash_many(&input)
+}
+
+fn to_scalar(element: &Fq) -> Fr {
+    Fr::from_le_bytes_mod_order(&element.into_repr().to_bytes_le())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VrfProof {
+    pub gamma: Affine,
+    pub c: Fr,
+    pub s: Fr,
+}
+
+impl VrfProof {
+    pub fn to_felts(&self) -> [Fq; 4] {
+        // EC_ORDER is smaller than FIELD_PRIME so it's safe to unwrap
+        [
+            self.gamma.x,
+            self.gamma.y,
+            Fq::from_repr(self.c.into_repr()).unwrap(),
+            Fq::from_repr(self.s.into_repr()).unwrap(),
+        ]
+    }
+
+    pub fn from_felts(felts: &[Fq]) -> Result<Self, Error> {
+        if felts.len() != 4 {
+            return Err(Error::IncorrectLenError);
+        }
+
+        let gamma = Affine::new(felts[0], felts[1], false);
+        if !gamma.is_on_curve() {
+            return Err(Error::InvalidSignature);
+        }
+
+        let c = Fr::from_repr(felts[2].into_repr()).ok_or(Error::OverflowError)?;
+        let s = Fr::from_repr(felts[3].into_repr()).ok_or(Error::OverflowError)?;
+
+        Ok(Self { gamma, c, s })
+    }
+}
+
+/// Try and increment, the point with an even y coordinate is taken
+pub fn encode_to_curve(pub_key: &Fq, alpha: &[Fq]) -> Affine {
+    let mut input = vec![*pub_key];
+    input.extend_from_slice(alpha);
+    input.push(Fq::zero());
+
+    let mut counter = 0u64;
+    loop {
+        *input.last_mut().unwrap() = Fq::from(counter);
+        if let Some(point) = lift_x(&tagged_hash(ENCODE_TO_CURVE_TAG, &input)) {
+            break point;
+        }
+
+        counter += 1;
+    }
+}
+
+fn challenge(points: &[&Affine]) -> Fr {
+    let coordinates: Vec<Fq> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+    let mut c = tagged_hash(CHALLENGE_TAG, &coordinates).into_repr();
+
+    // keep the lowest CHALLENGE_BITS bits
+    let BigInteger256(limbs) = &mut c;
+    for limb in limbs.iter_mut().skip(CHALLENGE_BITS / 64) {
+        *limb = 0;
+    }
+
+    // it's smaller than 2**128 so it's safe to unwrap
+    Fr::from_repr(c).unwrap()
+}
+
+pub fn prove(
+    parameters: &SigningParameters,
+    priv_key: Fr,
+    alpha: &[Fq],
+) -> Result<VrfProof, Error> {
+    if priv_key.is_zero() {
+        return Err(Error::InvalidPrivateKey);
+    }
+
+    let pub_key = private_key_to_public_key(parameters, priv_key).into_affine();
+    let priv_key = if pub_key.y.into_repr().is_odd() {
+        -priv_key
+    } else {
+        priv_key
+    };
+    // the public key with an even y coordinate
+    let pub_key = private_key_to_public_key(parameters, priv_key).into_affine();
+
+    let h = encode_to_curve(&pub_key.x, alpha);
+    let gamma = h.mul(priv_key.into_repr()).into_affine();
+
+    let nonce_msg = to_scalar(&tagged_hash(NONCE_TAG, &[h.x, h.y]));
+    let k = generate_k_rfc6979(
+        &starknet_curve::FrParameters::MODULUS,
+        &priv_key,
+        &nonce_msg,
+        None,
+    );
+    let u = parameters.generator.mul(k.into_repr()).into_affine();
+    let v = h.mul(k.into_repr()).into_affine();
+
+    let c = challenge(&[&pub_key, &h, &gamma, &u, &v]);
+
+    Ok(VrfProof {
+        gamma,
+        c,
+        s: k + c * priv_key,
+    })
+}
+
+/// Returns the output beta if the proof is valid for the stark key and alpha
+pub fn verify(
+    parameters: &SigningParameters,
+    pub_key: &Fq,
+    alpha: &[Fq],
+    proof: &VrfProof,
+) -> Option<Fq> {
+    let point = lift_x(pub_key)?;
+    if proof.gamma.is_zero() || !proof.gamma.is_on_curve() {
+        return None;
+    }
+
+    let h = encode_to_curve(pub_key, alpha);
+    let u = (parameters.generator.mul(proof.s.into_repr()) - point.mul(proof.c.into_repr()))
+        .into_affine();
+    let v = (h.mul(proof.s.into_repr()) - proof.gamma.mul(proof.c.into_repr())).into_affine();
+
+    if challenge(&[&point, &h, &proof.gamma, &u, &v]) != proof.c {
+        return None;
+    }
+
+    Some(proof_to_hash(proof))
+}
+
+/// The VRF output, only meaningful once the proof is verified
+pub fn proof_to_hash(proof: &VrfProof) -> Fq {
+    tagged_hash(PROOF_TO_HASH_TAG, &[proof.gamma.x, proof.gamma.y])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_to_curve, proof_to_hash, prove, verify, VrfProof};
+    use crate::error::Error;
+    use crate::signature::{parameters, private_key_to_public_key};
+    use ark_ec::ProjectiveCurve;
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_std::UniformRand;
+    use rand::thread_rng;
+    use starknet_curve::{Fq, Fr};
+
+    #[test]
+    fn test_prove_and_verify() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        for _ in 0..4 {
+            let private_key = Fr::rand(rng);
+            let stark_key = private_key_to_public_key(&parameters, private_key)
+                .into_affine()
+                .x;
+            let alpha = vec![Fq::from(42u64), Fq::rand(rng)];
+
+            let proof = prove(&parameters, private_key, &alpha).unwrap();
+            let beta = verify(&parameters, &stark_key, &alpha, &proof).unwrap();
+            assert_eq!(beta, proof_to_hash(&proof));
+            assert!(proof.c.into_repr().num_bits() <= 128);
+
+            // the output is unique, the proof is deterministic
+            assert_eq!(proof, prove(&parameters, private_key, &alpha).unwrap());
+
+            let other_alpha = vec![Fq::from(43u64), alpha[1]];
+            assert_eq!(None, verify(&parameters, &stark_key, &other_alpha, &proof));
+            assert_ne!(
+                beta,
+                proof_to_hash(&prove(&parameters, private_key, &other_alpha).unwrap())
+            );
+
+            let wrong_proof = VrfProof {
+                s: proof.s + Fr::from(1u64),
+                ..proof
+            };
+            assert_eq!(None, verify(&parameters, &stark_key, &alpha, &wrong_proof));
+
+            // another key can't produce the same output
+            let other_key = private_key_to_public_key(&parameters, Fr::rand(rng))
+                .into_affine()
+                .x;
+            assert_eq!(None, verify(&parameters, &other_key, &alpha, &proof));
+        }
+    }
+
+    #[test]
+    fn test_felts() {
+        let rng = &mut thread_rng();
+        let parameters = parameters();
+
+        let proof = prove(&parameters, Fr::rand(rng), &[Fq::from(1u64)]).unwrap();
+        assert_eq!(proof, VrfProof::from_felts(&proof.to_felts()).unwrap());
+
+        let mut felts = proof.to_felts();
+        felts[1] += Fq::from(1u64);
+        assert_eq!(
+            Some(Error::InvalidSignature),
+            VrfProof::from_felts(&felts).err()
+        );
+        assert_eq!(
+            Some(Error::IncorrectLenError),
+            VrfProof::from_felts(&felts[1..]).err()
+        );
+    }
+
+    #[test]
+    fn test_encode_to_curve() {
+        let point = encode_to_curve(&Fq::from(1u64), &[]);
+        assert!(point.is_on_curve());
+        assert!(!point.y.into_repr().is_odd());
+        assert_ne!(point, encode_to_curve(&Fq::from(1u64), &[Fq::from(0u64)]));
+    }
+}
@@ -1,5 +1,17 @@
 #!/bin/bash
-mkdir artifacts;
-mkdir  artifacts/abis;
-./.tox/py37/bin/starknet-compile packages/verifier/src/Verifier.cairo --cairo_path=packages/verifier/src --output=artifacts/Verifier.json --abi=artifacts/abis/Verifier.json;
-./.tox/py37/bin/starknet deploy --contract=artifacts/Verifier.json --network=alpha-goerli;
\ No newline at end of file
+# cairo-lang 0.11 (tox.ini), Cairo 0 contracts are compiled with starknet-compile-deprecated and
+# declared with --deprecated
+set -e
+BIN=./.tox/py39/bin
+mkdir -p artifacts/abis;
+
+deploy() {
+    $BIN/starknet-compile-deprecated $1 --cairo_path=packages/verifier/src --output=artifacts/$2.json --abi=artifacts/abis/$2.json;
+    class_hash=$($BIN/starknet declare --contract=artifacts/$2.json --deprecated --network=alpha-goerli | grep "class hash" | awk '{print $NF}');
+    $BIN/starknet deploy --class_hash=$class_hash --network=alpha-goerli;
+}
+
+deploy packages/verifier/src/Verifier.cairo Verifier
+deploy packages/verifier/src/SchnorrVerifier.cairo SchnorrVerifier
+# transcript.cairo is a library, the test contract checks that it compiles with the pinned toolchain
+$BIN/starknet-compile-deprecated packages/verifier/tests/TranscriptTest.cairo --cairo_path=packages/verifier/src --output=artifacts/TranscriptTest.json --abi=artifacts/abis/TranscriptTest.json;
@@ -0,0 +1,58 @@
+%lang starknet
+
+// Reference verifier of the Schnorr signatures of packages/prover/src/schnorr.rs
+// with the Pedersen challenge e = compute_hash_on_elements([r, signer_pubkey, msg_hash]).
+// The public key and the nonce point are the points with an even y coordinate.
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.cairo_builtins import HashBuiltin
+from starkware.cairo.common.ec import StarkCurve, ec_add, ec_mul, ec_neg, recover_y
+from starkware.cairo.common.ec_point import EcPoint
+from starkware.cairo.common.hash_state import hash_felts
+from starkware.cairo.common.math import assert_not_zero, split_felt, unsigned_div_rem
+
+func is_even{range_check_ptr}(value: felt) -> (res: felt) {
+    // the parity of a felt is the parity of its low 128 bits
+    let (_, low) = split_felt(value);
+    let (_, bit) = unsigned_div_rem(low, 2);
+    return (res=1 - bit);
+}
+
+func lift_x{range_check_ptr}(x: felt) -> (point: EcPoint) {
+    alloc_locals;
+    let (local point: EcPoint) = recover_y(x);
+    let (even) = is_even(point.y);
+    if (even == 1) {
+        return (point=point);
+    }
+    let (neg_point) = ec_neg(point);
+    return (point=neg_point);
+}
+
+@view
+func verify_schnorr_sig{syscall_ptr: felt*, pedersen_ptr: HashBuiltin*, range_check_ptr}(
+    msg_len: felt, msg: felt*, signer_pubkey: felt, sig: (felt, felt)
+) {
+    alloc_locals;
+    let (msg_hash) = hash_felts{hash_ptr=pedersen_ptr}(msg, msg_len);
+
+    let (challenge_data: felt*) = alloc();
+    assert challenge_data[0] = sig[0];
+    assert challenge_data[1] = signer_pubkey;
+    assert challenge_data[2] = msg_hash;
+    let (e) = hash_felts{hash_ptr=pedersen_ptr}(challenge_data, 3);
+
+    // R = s*G - e*P
+    let (public_key) = lift_x(signer_pubkey);
+    let (s_g) = ec_mul(sig[1], EcPoint(x=StarkCurve.GEN_X, y=StarkCurve.GEN_Y));
+    let (e_p) = ec_mul(e, public_key);
+    let (neg_e_p) = ec_neg(e_p);
+    let (local nonce_point: EcPoint) = ec_add(s_g, neg_e_p);
+
+    // the point at infinity is (0, 0) and no point of the curve has y = 0
+    assert_not_zero(nonce_point.y);
+    assert nonce_point.x = sig[0];
+    let (even) = is_even(nonce_point.y);
+    assert even = 1;
+    return ();
+}
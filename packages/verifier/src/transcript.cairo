@@ -0,0 +1,79 @@
+// Fiat-Shamir transcript of packages/prover/src/transcript.rs, a Poseidon sponge of width 3 and
+// rate 2 over the Poseidon builtin. Every operation absorbs a list of felts padded with 1 and then
+// 0 if needed to fill the last pair, as poseidon_hash_many does:
+//     transcript_new(label)                absorbs [DOMAIN, label]
+//     append_felts(label, values)          absorbs [label, values_len, values...]
+//     challenge(label)                     absorbs [label] and outputs s0
+// The Poseidon builtin requires cairo-lang 0.11 or later, the version pinned in tox.ini.
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.cairo_builtins import PoseidonBuiltin, PoseidonBuiltinState
+from starkware.cairo.common.ec_point import EcPoint
+from starkware.cairo.common.memcpy import memcpy
+
+const DOMAIN = 'STARK_TRANSCRIPT';
+
+func permute{poseidon_ptr: PoseidonBuiltin*}(state: PoseidonBuiltinState) -> PoseidonBuiltinState {
+    assert poseidon_ptr.input = state;
+    let output = poseidon_ptr.output;
+    let poseidon_ptr = poseidon_ptr + PoseidonBuiltin.SIZE;
+    return output;
+}
+
+func absorb{poseidon_ptr: PoseidonBuiltin*}(
+    state: PoseidonBuiltinState, data_len: felt, data: felt*
+) -> PoseidonBuiltinState {
+    if (data_len == 0) {
+        return permute(PoseidonBuiltinState(s0=state.s0 + 1, s1=state.s1, s2=state.s2));
+    }
+    if (data_len == 1) {
+        return permute(PoseidonBuiltinState(s0=state.s0 + data[0], s1=state.s1 + 1, s2=state.s2));
+    }
+
+    let next_state = permute(
+        PoseidonBuiltinState(s0=state.s0 + data[0], s1=state.s1 + data[1], s2=state.s2)
+    );
+    return absorb(next_state, data_len - 2, data + 2);
+}
+
+func transcript_new{poseidon_ptr: PoseidonBuiltin*}(label: felt) -> PoseidonBuiltinState {
+    alloc_locals;
+    let (data: felt*) = alloc();
+    assert data[0] = DOMAIN;
+    assert data[1] = label;
+    return absorb(PoseidonBuiltinState(s0=0, s1=0, s2=0), 2, data);
+}
+
+func append_felts{poseidon_ptr: PoseidonBuiltin*}(
+    state: PoseidonBuiltinState, label: felt, values_len: felt, values: felt*
+) -> PoseidonBuiltinState {
+    alloc_locals;
+    let (data: felt*) = alloc();
+    assert data[0] = label;
+    assert data[1] = values_len;
+    memcpy(dst=data + 2, src=values, len=values_len);
+    return absorb(state, values_len + 2, data);
+}
+
+func append_point{poseidon_ptr: PoseidonBuiltin*}(
+    state: PoseidonBuiltinState, label: felt, point: EcPoint
+) -> PoseidonBuiltinState {
+    alloc_locals;
+    let (data: felt*) = alloc();
+    assert data[0] = label;
+    assert data[1] = 2;
+    assert data[2] = point.x;
+    assert data[3] = point.y;
+    return absorb(state, 4, data);
+}
+
+// challenge_fr of the rust transcript is this challenge reduced modulo EC_ORDER
+func challenge{poseidon_ptr: PoseidonBuiltin*}(state: PoseidonBuiltinState, label: felt) -> (
+    state: PoseidonBuiltinState, challenge: felt
+) {
+    alloc_locals;
+    let (data: felt*) = alloc();
+    assert data[0] = label;
+    let next_state = absorb(state, 1, data);
+    return (state=next_state, challenge=next_state.s0);
+}
@@ -0,0 +1,30 @@
+%lang starknet
+
+// Test contract of transcript.cairo, replays the transcript of test_transcript in
+// packages/prover/src/transcript.rs.
+
+from starkware.cairo.common.alloc import alloc
+from starkware.cairo.common.cairo_builtins import HashBuiltin, PoseidonBuiltin
+from starkware.cairo.common.ec_point import EcPoint
+
+from transcript import append_felts, append_point, challenge, transcript_new
+
+@view
+func challenges{
+    syscall_ptr: felt*,
+    pedersen_ptr: HashBuiltin*,
+    range_check_ptr,
+    poseidon_ptr: PoseidonBuiltin*,
+}(values_len: felt, values: felt*, point: EcPoint, scalar: felt) -> (first: felt, second: felt) {
+    alloc_locals;
+    let state = transcript_new('test');
+    let state = append_felts(state, 'values', values_len, values);
+    let state = append_point(state, 'point', point);
+    let (scalars: felt*) = alloc();
+    assert scalars[0] = scalar;
+    let state = append_felts(state, 'scalar', 1, scalars);
+
+    let (state, first) = challenge(state, 'first');
+    let (_, second) = challenge(state, 'second');
+    return (first=first, second=second);
+}
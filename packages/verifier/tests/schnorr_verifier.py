@@ -0,0 +1,57 @@
+import pytest
+import asyncio
+from typing import NamedTuple
+from starkware.cairo.common.hash_state import compute_hash_on_elements
+from starkware.crypto.signature.math_utils import ec_mult
+from starkware.crypto.signature.signature import ALPHA, EC_GEN, EC_ORDER, FIELD_PRIME
+
+from starkware.starknet.testing.contract import StarknetContract
+from starkware.starknet.testing.starknet import Starknet
+from starkware.starkware_utils.error_handling import StarkException
+
+
+class TestsDeps(NamedTuple):
+    starknet: Starknet
+    verifier: StarknetContract
+
+@pytest.fixture(scope='module')
+def event_loop():
+    return asyncio.new_event_loop()
+
+async def setup():
+    starknet = await Starknet.empty()
+    verifier = await starknet.deploy("packages/verifier/src/SchnorrVerifier.cairo", cairo_path=["packages/verifier/src"])
+    return TestsDeps(starknet=starknet, verifier=verifier)
+
+@pytest.fixture(scope='module')
+async def factory():
+    return await setup()
+
+
+def schnorr_sign(priv_key, msg_hash, k):
+    # same as schnorr::sign with HashFunction::Pedersen, but with an explicit nonce
+    public_key = ec_mult(priv_key, EC_GEN, ALPHA, FIELD_PRIME)
+    if public_key[1] % 2 == 1:
+        priv_key = EC_ORDER - priv_key
+
+    nonce_point = ec_mult(k, EC_GEN, ALPHA, FIELD_PRIME)
+    if nonce_point[1] % 2 == 1:
+        k = EC_ORDER - k
+
+    e = compute_hash_on_elements([nonce_point[0], public_key[0], msg_hash]) % EC_ORDER
+    return public_key[0], (nonce_point[0], (k + e * priv_key) % EC_ORDER)
+
+
+@pytest.mark.asyncio
+async def test_schnorr_verifier():
+    starknet, verifier = await setup()
+
+    msg = [1, 2, 3, 4, 5]
+    msg_hash = compute_hash_on_elements(msg)
+
+    for priv_key, k in [(0xbeef, 0xcafe), (0xdead, 0xf00d)]:
+        public_key, (sig_r, sig_s) = schnorr_sign(priv_key, msg_hash, k)
+        await verifier.verify_schnorr_sig(msg, public_key, (sig_r, sig_s)).call()
+
+        with pytest.raises(StarkException):
+            await verifier.verify_schnorr_sig(msg, public_key, (sig_r, sig_s + 1)).call()
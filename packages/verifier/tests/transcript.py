@@ -0,0 +1,40 @@
+import pytest
+import asyncio
+from typing import NamedTuple
+from starkware.crypto.signature.signature import EC_GEN, EC_ORDER
+
+from starkware.starknet.testing.contract import StarknetContract
+from starkware.starknet.testing.starknet import Starknet
+
+
+class TestsDeps(NamedTuple):
+    starknet: Starknet
+    transcript: StarknetContract
+
+@pytest.fixture(scope='module')
+def event_loop():
+    return asyncio.new_event_loop()
+
+async def setup():
+    starknet = await Starknet.empty()
+    transcript = await starknet.deploy("packages/verifier/tests/TranscriptTest.cairo", cairo_path=["packages/verifier/src"])
+    return TestsDeps(starknet=starknet, transcript=transcript)
+
+@pytest.fixture(scope='module')
+async def factory():
+    return await setup()
+
+
+@pytest.mark.asyncio
+async def test_transcript():
+    starknet, transcript = await setup()
+
+    # same vector as test_transcript in packages/prover/src/transcript.rs
+    execution_info = await transcript.challenges([1, 2], EC_GEN, 7).call()
+    first, second = execution_info.result
+    assert first == 0x645513400b830cc45135d0f641d871c1be2f60b7fcc4a6681dacdb3bd5357ac
+    assert second % EC_ORDER == 0x1077a9e12697c7826ef16c51f8c768ade215feb0cbace6d216259a75a3c73e9
+
+    # the length is absorbed, so values can't be shifted from one message to the next
+    execution_info = await transcript.challenges([1], EC_GEN, 7).call()
+    assert execution_info.result.first != first
@@ -2,10 +2,10 @@ import pytest
 import asyncio
 from typing import NamedTuple
 from starkware.cairo.common.hash_state import compute_hash_on_elements
+from starkware.crypto.signature.signature import private_to_stark_key, sign
 
 from starkware.starknet.testing.contract import StarknetContract
 from starkware.starknet.testing.starknet import Starknet
-from nile.signer import Signer
 
 
 class TestsDeps(NamedTuple):
@@ -30,13 +30,14 @@ async def factory():
 async def test_verifier():
     starknet, verifier = await setup()
 
-    signer = Signer(0xbeef)
+    private_key = 0xbeef
+    public_key = private_to_stark_key(private_key)
 
     msg = [1, 2, 3, 4, 5]
     msg_hash = compute_hash_on_elements(msg)
 
-    (sig_r, sig_s) = signer.sign(msg_hash)
+    (sig_r, sig_s) = sign(msg_hash, private_key)
 
-    await verifier.verify_sig(msg, signer.public_key, (sig_r, sig_s)).call()
-    await verifier.verify_signature(msg, signer.public_key, (sig_r, sig_s)).invoke()
+    await verifier.verify_sig(msg, public_key, (sig_r, sig_s)).call()
+    await verifier.verify_signature(msg, public_key, (sig_r, sig_s)).invoke()
 
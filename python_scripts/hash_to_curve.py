@@ -0,0 +1,145 @@
+# reference implementation of hash_to_curve (RFC 9380) on the STARK curve, as in
+# packages/prover/src/hash_to_curve.rs: expand_message_xmd with SHA-256, L = 48 and the simplified
+# SWU map with the Z given by find_z_sswu of appendix H.2
+#
+# python3 python_scripts/hash_to_curve.py prints Z and the test vector of hash_to_curve.rs
+
+import hashlib
+
+P = 2**251 + 17 * 2**192 + 1
+A = 1
+B = 0x6f21413efbe40de150e596d72f7a8c5609ad26c15c915c1f4cdfcb99cee9e89
+L = 48
+
+
+def is_square(x):
+    x %= P
+    return x == 0 or pow(x, (P - 1) // 2, P) == 1
+
+
+def sqrt(x):
+    # Tonelli-Shanks, P - 1 = 2^s * q
+    x %= P
+    if x == 0:
+        return 0
+    q, s = P - 1, 0
+    while q % 2 == 0:
+        q, s = q // 2, s + 1
+    z = 2
+    while is_square(z):
+        z += 1
+    m, c, t, r = s, pow(z, q, P), pow(x, q, P), pow(x, (q + 1) // 2, P)
+    while t != 1:
+        i, t2 = 0, t
+        while t2 != 1:
+            t2, i = t2 * t2 % P, i + 1
+        b = pow(c, 1 << (m - i - 1), P)
+        m, c, t, r = i, b * b % P, t * b * b % P, r * b % P
+    return r
+
+
+def g(x):
+    return (x**3 + A * x + B) % P
+
+
+def has_root(c0):
+    # x^3 + A x + c0 has a root iff gcd(x^P - x, x^3 + A x + c0) != 1
+    def mulmod(a, b):
+        r = [0] * 5
+        for i in range(3):
+            for j in range(3):
+                r[i + j] = (r[i + j] + a[i] * b[j]) % P
+        for d in (4, 3):
+            c, r[d] = r[d], 0
+            r[d - 2] = (r[d - 2] - c * A) % P
+            r[d - 3] = (r[d - 3] - c * c0) % P
+        return r[:3]
+
+    res, base, e = [1, 0, 0], [0, 1, 0], P
+    while e:
+        if e & 1:
+            res = mulmod(res, base)
+        base, e = mulmod(base, base), e >> 1
+
+    def trim(p):
+        while p and p[-1] == 0:
+            p = p[:-1]
+        return p
+
+    f, h = [c0 % P, A, 0, 1], trim([res[0], (res[1] - 1) % P, res[2]])
+    while h:
+        f = trim(f)
+        while len(f) >= len(h):
+            c, shift = f[-1] * pow(h[-1], -1, P) % P, len(f) - len(h)
+            for i in range(len(h)):
+                f[shift + i] = (f[shift + i] - c * h[i]) % P
+            f = trim(f)
+        f, h = h, f
+    return len(trim(f)) > 1
+
+
+def find_z_sswu():
+    ctr = 1
+    while True:
+        for z in (ctr, -ctr):
+            if is_square(z) or z % P == P - 1 or has_root(B - z):
+                continue
+            if is_square(g(B * pow(z * A, -1, P))):
+                return z % P
+        ctr += 1
+
+
+Z = find_z_sswu()
+
+
+def expand_message_xmd(msg, dst, len_in_bytes):
+    ell = -(-len_in_bytes // 32)
+    dst_prime = dst + bytes([len(dst)])
+    b_0 = hashlib.sha256(bytes(64) + msg + len_in_bytes.to_bytes(2, 'big') + b'\x00' + dst_prime).digest()
+    b = [hashlib.sha256(b_0 + b'\x01' + dst_prime).digest()]
+    for i in range(2, ell + 1):
+        xored = bytes(x ^ y for x, y in zip(b_0, b[-1]))
+        b.append(hashlib.sha256(xored + bytes([i]) + dst_prime).digest())
+    return b''.join(b)[:len_in_bytes]
+
+
+def hash_to_field(msg, dst, count):
+    uniform_bytes = expand_message_xmd(msg, dst, count * L)
+    return [int.from_bytes(uniform_bytes[i * L:(i + 1) * L], 'big') % P for i in range(count)]
+
+
+def map_to_curve(u):
+    tv1 = (Z * Z * pow(u, 4, P) + Z * u * u) % P
+    tv1 = pow(tv1, -1, P) if tv1 else 0
+    x1 = -B * pow(A, -1, P) * (1 + tv1) % P if tv1 else B * pow(Z * A, -1, P) % P
+    if is_square(g(x1)):
+        x, y = x1, sqrt(g(x1))
+    else:
+        x = Z * u * u * x1 % P
+        y = sqrt(g(x))
+    if u % 2 != y % 2:
+        y = -y % P
+    return x, y
+
+
+def add(p, q):
+    if p[0] == q[0]:
+        assert (p[1] + q[1]) % P != 0
+        l = (3 * p[0] * p[0] + A) * pow(2 * p[1], -1, P) % P
+    else:
+        l = (q[1] - p[1]) * pow(q[0] - p[0], -1, P) % P
+    x = (l * l - p[0] - q[0]) % P
+    return x, (l * (p[0] - x) - p[1]) % P
+
+
+def hash_to_curve(msg, dst):
+    # the cofactor is 1
+    u0, u1 = hash_to_field(msg, dst, 2)
+    return add(map_to_curve(u0), map_to_curve(u1))
+
+
+if __name__ == '__main__':
+    print('Z =', Z)
+    x, y = hash_to_curve(b'abc', b'STARK_TEST-V01-CS01-with-STARK_XMD:SHA-256_SSWU_RO_')
+    assert g(x) == y * y % P
+    print('hash_to_curve(abc) =', x, y)